@@ -12,6 +12,7 @@ use super::{type_state::TypeStatePair, FieldAttr, SkippedField};
 pub struct Builder {
     pub declaration: DeriveInput,
     pub impl_build: proc_macro2::TokenStream,
+    pub impl_to_builder: proc_macro2::TokenStream,
     pub impl_constructor: proc_macro2::TokenStream,
     pub impl_methods: proc_macro2::TokenStream,
     pub path: Path,
@@ -113,7 +114,18 @@ pub fn create_builder(
         &builder_ident,
     );
 
+    let impl_to_builder = create_to_builder(
+        crate_path,
+        &lifetime,
+        task_generics,
+        type_state_pairs,
+        &classified_fields,
+        ident,
+        &builder_ident,
+    );
+
     let impl_methods = create_methods(
+        crate_path,
         &lifetime,
         builder_decl_generics,
         task_generics,
@@ -125,6 +137,7 @@ pub fn create_builder(
     Builder {
         declaration: decl,
         impl_build,
+        impl_to_builder,
         impl_constructor,
         impl_methods,
         path,
@@ -132,6 +145,7 @@ pub fn create_builder(
 }
 
 fn create_methods(
+    crate_path: &Path,
     lifetime: &Option<LifetimeParam>,
     builder_decl_generics: Generics,
     task_generics: &Generics,
@@ -161,11 +175,7 @@ fn create_methods(
 
     let task_generic_count =
         task_generics.type_params().count() + builder_decl_generics.lifetimes().count();
-    let required_methods = type_state_pairs.iter().enumerate().map(|(i, x)| {
-        let ident = x.field.ident.as_ref().unwrap();
-        let field_attr = classified_fields.required[i].clone();
-        let ty = field_attr.impl_into_type;
-        let doc_attr = &classified_fields.required[i].field.attrs;
+    let provided_ty_generics = |i: usize| {
         let mut ty_generics = vec![lifetime.as_ref().map(ToTokens::to_token_stream)]
             .into_iter()
             .flatten()
@@ -187,7 +197,17 @@ fn create_methods(
                     .map(|pair| pair.generic.to_token_stream()),
             )
             .collect::<Vec<_>>();
-        ty_generics[i + task_generic_count] = x.provided.path.to_token_stream();
+        ty_generics[i + task_generic_count] = type_state_pairs[i].provided.path.to_token_stream();
+
+        ty_generics
+    };
+
+    let required_methods = type_state_pairs.iter().enumerate().map(|(i, x)| {
+        let ident = x.field.ident.as_ref().unwrap();
+        let field_attr = classified_fields.required[i].clone();
+        let ty = field_attr.impl_into_type;
+        let doc_attr = &classified_fields.required[i].field.attrs;
+        let ty_generics = provided_ty_generics(i);
 
         let mut required_set = classified_fields
             .required
@@ -235,15 +255,94 @@ fn create_methods(
         }
     });
 
+    let session_method = create_session_method(
+        crate_path,
+        type_state_pairs,
+        &classified_fields.optional,
+        &builder_ident,
+        provided_ty_generics,
+    );
+
     quote! {
         impl #impl_generics #builder_ident #ty_generics #where_clause {
             #(#required_methods)*
 
             #(#optional_methods)*
+
+            #session_method
         }
     }
 }
 
+/// Creates a `session` method that fills the user agent, cookies and proxy of
+/// the builder with those of a `SessionProfile`, if the task has any of them.
+/// A required user agent is set through its own setter, changing the type state
+fn create_session_method(
+    crate_path: &Path,
+    type_state_pairs: &[TypeStatePair],
+    optional_fields: &[FieldAttr],
+    builder_ident: &Ident,
+    provided_ty_generics: impl Fn(usize) -> Vec<proc_macro2::TokenStream>,
+) -> Option<proc_macro2::TokenStream> {
+    let required_user_agent = type_state_pairs
+        .iter()
+        .position(|x| x.field.ident.as_ref().is_some_and(|x| x == "user_agent"));
+
+    let optional_set = optional_fields
+        .iter()
+        .filter_map(|x| {
+            let ident = x.field.ident.as_ref().unwrap();
+
+            match ident.to_string().as_str() {
+                "user_agent" => Some(quote! {
+                    builder.user_agent = Some(session.user_agent.clone().into());
+                }),
+                "cookies" => Some(quote! {
+                    if !session.cookies.is_empty() {
+                        builder.cookies = Some(
+                            session.cookies.iter().map(|(name, value)| (name, value)).collect()
+                        );
+                    }
+                }),
+                "proxy" => Some(quote! {
+                    if session.proxy.is_some() {
+                        builder.proxy = session.proxy.clone();
+                    }
+                }),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if required_user_agent.is_none() && optional_set.is_empty() {
+        return None;
+    }
+
+    let (return_type, initial) = match required_user_agent {
+        Some(i) => {
+            let ty_generics = provided_ty_generics(i);
+
+            (
+                quote! { #builder_ident<#(#ty_generics),*> },
+                quote! { self.user_agent(session.user_agent.clone()) },
+            )
+        }
+        None => (quote! { Self }, quote! { self }),
+    };
+
+    let mutability = (!optional_set.is_empty()).then(|| quote! { mut });
+
+    Some(quote! {
+        /// Fills the user agent, cookies and proxy of the task with
+        /// those of the session, if the task accepts them
+        pub fn session(self, session: &#crate_path::session::SessionProfile) -> #return_type {
+            let #mutability builder = #initial;
+            #(#optional_set)*
+            builder
+        }
+    })
+}
+
 fn create_build(
     lifetime: &Option<LifetimeParam>,
     task_generics: &Generics,
@@ -254,28 +353,7 @@ fn create_build(
     crate_path: &Path,
     builder_ident: &Ident,
 ) -> proc_macro2::TokenStream {
-    let build_ty_generics = vec![lifetime.as_ref().map(ToTokens::to_token_stream)]
-        .into_iter()
-        .flatten()
-        .chain(
-            task_generics
-                .type_params()
-                .cloned()
-                .map(|mut x| {
-                    x.eq_token = None;
-                    x.default = None;
-
-                    x
-                })
-                .map(|x| ToTokens::to_token_stream(&x)),
-        )
-        .chain(
-            type_state_pairs
-                .iter()
-                .map(|x| x.provided.path.clone())
-                .map(|x| ToTokens::to_token_stream(&x)),
-        )
-        .collect::<Vec<_>>();
+    let build_ty_generics = provided_builder_generics(lifetime, task_generics, type_state_pairs);
 
     let (task_impl_generics, task_ty_generics, where_clause) = task_generics.split_for_impl();
 
@@ -364,6 +442,111 @@ fn create_build(
     }
 }
 
+/// The generics of the builder once every required field is provided
+fn provided_builder_generics(
+    lifetime: &Option<LifetimeParam>,
+    task_generics: &Generics,
+    type_state_pairs: &[TypeStatePair],
+) -> Vec<proc_macro2::TokenStream> {
+    vec![lifetime.as_ref().map(ToTokens::to_token_stream)]
+        .into_iter()
+        .flatten()
+        .chain(
+            task_generics
+                .type_params()
+                .cloned()
+                .map(|mut x| {
+                    x.eq_token = None;
+                    x.default = None;
+
+                    x
+                })
+                .map(|x| ToTokens::to_token_stream(&x)),
+        )
+        .chain(
+            type_state_pairs
+                .iter()
+                .map(|x| x.provided.path.clone())
+                .map(|x| ToTokens::to_token_stream(&x)),
+        )
+        .collect()
+}
+
+/// Creates a `to_builder` method on the task, which returns a builder with
+/// every field of a clone of the task, undoing `parse_with` with `unparse_with`,
+/// and implements `ToBuilder` with it
+fn create_to_builder(
+    crate_path: &Path,
+    lifetime: &Option<LifetimeParam>,
+    task_generics: &Generics,
+    type_state_pairs: &[TypeStatePair],
+    classified_fields: &ClassifiedFields,
+    ident: &Ident,
+    builder_ident: &Ident,
+) -> proc_macro2::TokenStream {
+    let builder_ty_generics = provided_builder_generics(lifetime, task_generics, type_state_pairs);
+
+    let (task_impl_generics, task_ty_generics, where_clause) = task_generics.split_for_impl();
+    let where_predicates = where_clause.map(|x| &x.predicates);
+
+    let required_set = classified_fields
+        .required
+        .iter()
+        .zip(type_state_pairs)
+        .map(|(x, pair)| {
+            let ident = &x.original_ident;
+            let builder_ident = x.field.ident.as_ref().unwrap();
+            let provided = &pair.provided.ident;
+            let value = match x.unparse_with {
+                Some(ref path) => quote! { #path(task.#ident) },
+                None => quote! { task.#ident },
+            };
+
+            quote! { #builder_ident: #provided(#value.into()) }
+        });
+
+    let optional_set = classified_fields.optional.iter().map(|x| {
+        let ident = &x.original_ident;
+        let builder_ident = x.field.ident.as_ref().unwrap();
+        let value = match x.unparse_with {
+            Some(ref path) => quote! { task.#ident.map(#path).map(Into::into) },
+            None => quote! { task.#ident.into() },
+        };
+
+        quote! { #builder_ident: #value }
+    });
+
+    quote! {
+        impl #task_impl_generics #ident #task_ty_generics #where_clause {
+            /// A builder with every field of the task already provided,
+            /// so it can be submitted again with some of them changed
+            pub fn to_builder(&self) -> #builder_ident<#(#builder_ty_generics),*>
+            where
+                Self: Clone,
+            {
+                let task = self.clone();
+
+                #builder_ident {
+                    #(#required_set,)*
+                    #(#optional_set,)*
+                }
+            }
+        }
+
+        impl #task_impl_generics #crate_path::captcha_types::ToBuilder for #ident #task_ty_generics
+        where
+            Self: Clone,
+            #where_predicates
+        {
+            type Builder = #builder_ident<#(#builder_ty_generics),*>;
+
+            fn to_builder(&self) -> Self::Builder {
+                Self::to_builder(self)
+            }
+        }
+    }
+}
+
 fn create_constructor(
     lifetime: &Option<LifetimeParam>,
     task_generics: &Generics,
@@ -2,6 +2,7 @@ use quote::ToTokens;
 use syn::{Field, Type, Visibility};
 
 use super::{FieldAttr, TaskFieldAttribute, TaskParseField};
+use crate::util::has_lifetime;
 
 pub(crate) fn parse_field_data(mut field: Field) -> deluxe::Result<Option<FieldAttr>> {
     let attr: TaskFieldAttribute = deluxe::extract_attributes(&mut field)?;
@@ -43,7 +44,9 @@ pub(crate) fn parse_field_data(mut field: Field) -> deluxe::Result<Option<FieldA
         ty
     })?;
 
-    let has_lifetime = builder_type.to_token_stream().to_string().contains('\'');
+    let unparse_with = attr.unparse_with.clone();
+
+    let has_lifetime = has_lifetime(&builder_type);
 
     field.ident = match attr.rename {
         Some(ref x) => Some(x.clone()),
@@ -67,6 +70,7 @@ pub(crate) fn parse_field_data(mut field: Field) -> deluxe::Result<Option<FieldA
         builder_type,
         impl_into_type,
         parse_with,
+        unparse_with,
         is_fallible,
         has_lifetime,
         original_ident,
@@ -1,7 +1,10 @@
 use quote::quote;
 use syn::{Field, Ident, Path, Type};
 
-use crate::util::{classify_fields, extract_named_fields, extract_struct_data};
+use crate::{
+    expansion::into_owned::{owned_fields, owned_generics},
+    util::{classify_fields, extract_named_fields, extract_struct_data},
+};
 
 use builder::create_builder;
 use type_state::create_type_state;
@@ -17,6 +20,7 @@ mod type_state;
 struct TaskStructAttribute {
     timeout: u64,
     solution: Type,
+    validity: Option<u64>,
 
     #[deluxe(rename = crate, default = syn::parse2(quote!{captcha_oxide}).unwrap())]
     crate_path: Path,
@@ -32,6 +36,10 @@ pub struct TaskFieldAttribute {
 
     #[deluxe()]
     pub parse_with: Option<TaskParseField>,
+
+    /// Turns the value of the field back into the one given to the builder,
+    /// undoing `parse_with` in `to_builder`
+    pub unparse_with: Option<Path>,
 }
 
 #[derive(deluxe::ParseMetaItem, Clone)]
@@ -53,6 +61,7 @@ pub struct FieldAttr {
     pub impl_into_type: Type,
     pub attr: TaskFieldAttribute,
     pub parse_with: Option<Parser>,
+    pub unparse_with: Option<Path>,
     pub is_fallible: bool,
     pub has_lifetime: bool,
     pub original_ident: Ident,
@@ -73,12 +82,18 @@ pub fn derive_captcha_task_expansion(
         timeout,
         crate_path,
         solution,
+        validity,
     } = deluxe::extract_attributes(&mut ast)?;
 
     let data_struct = extract_struct_data(ast.data)?;
 
-    let fields =
-        extract_named_fields(data_struct.fields, data_struct.struct_token.span)?.into_iter();
+    let fields = extract_named_fields(data_struct.fields, data_struct.struct_token.span)?;
+
+    let ident = &ast.ident;
+    let owned_generics = owned_generics(&ast.generics);
+    let owned_fields = owned_fields(ident, fields.iter(), &crate_path);
+
+    let fields = fields.into_iter();
 
     let skipped_fields = fields
         .clone()
@@ -99,7 +114,6 @@ pub fn derive_captcha_task_expansion(
 
     let classified_fields = classify_fields(fields);
 
-    let ident = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
     let type_state_pairs = create_type_state(
@@ -114,6 +128,7 @@ pub fn derive_captcha_task_expansion(
     let Builder {
         declaration: builder_decl,
         impl_build,
+        impl_to_builder,
         impl_constructor,
         impl_methods,
         path,
@@ -126,6 +141,14 @@ pub fn derive_captcha_task_expansion(
         &type_state_pairs,
     );
 
+    let get_validity = validity.map(|validity| {
+        quote! {
+            fn get_validity(&self) -> Option<std::time::Duration> {
+                Some(std::time::Duration::from_secs(#validity))
+            }
+        }
+    });
+
     Ok(quote! {
         mod type_state {
             use super::*;
@@ -140,12 +163,22 @@ pub fn derive_captcha_task_expansion(
 
             #impl_build
 
+            #impl_to_builder
+
             #impl_constructor
 
             #impl_methods
         }
 
         use type_state::*;
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Turns the task into one that owns all of its data,
+            /// so it can be stored and submitted again later
+            pub fn into_owned(self) -> #ident #owned_generics {
+                #owned_fields
+            }
+        }
+
         impl #impl_generics #crate_path::CaptchaTask for #ident #ty_generics #where_clause {
             type Solution = #solution;
             type Builder = #path;
@@ -153,6 +186,8 @@ pub fn derive_captcha_task_expansion(
             fn get_timeout(&self) -> std::time::Duration {
                 std::time::Duration::from_secs(#timeout)
             }
+
+            #get_validity
         }
     })
 }
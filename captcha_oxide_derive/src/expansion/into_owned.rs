@@ -0,0 +1,70 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Field, GenericParam, Generics, Ident, Path};
+
+use crate::util::{extract_named_fields, extract_struct_data, has_lifetime};
+
+#[derive(deluxe::ExtractAttributes)]
+#[deluxe(attributes(into_owned))]
+struct IntoOwnedAttribute {
+    #[deluxe(rename = crate, default = syn::parse2(quote!{captcha_oxide}).unwrap())]
+    crate_path: Path,
+}
+
+pub fn into_owned_expansion(input: TokenStream) -> syn::Result<TokenStream> {
+    let mut ast: syn::DeriveInput = syn::parse2(input)?;
+
+    let IntoOwnedAttribute { crate_path } = deluxe::extract_attributes(&mut ast)?;
+
+    let data_struct = extract_struct_data(ast.data)?;
+    let fields = extract_named_fields(data_struct.fields, data_struct.struct_token.span)?;
+
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let owned_generics = owned_generics(&ast.generics);
+    let owned_fields = owned_fields(ident, fields.iter(), &crate_path);
+
+    Ok(quote! {
+        impl #impl_generics #crate_path::owned::IntoOwned for #ident #ty_generics #where_clause {
+            type Owned = #ident #owned_generics;
+
+            fn into_owned(self) -> Self::Owned {
+                #owned_fields
+            }
+        }
+    })
+}
+
+/// The generics of the owned version of a type, in which every lifetime is `'static`
+pub fn owned_generics(generics: &Generics) -> TokenStream {
+    if generics.params.is_empty() {
+        return TokenStream::new();
+    }
+
+    let params = generics.params.iter().map(|x| match x {
+        GenericParam::Lifetime(_) => quote!('static),
+        GenericParam::Type(x) => x.ident.to_token_stream(),
+        GenericParam::Const(x) => x.ident.to_token_stream(),
+    });
+
+    quote!(<#(#params),*>)
+}
+
+/// Builds the owned version of a struct from `self`, converting the fields
+/// whose types have a lifetime with `IntoOwned` and moving the rest
+pub fn owned_fields<'a>(
+    ident: &Ident,
+    fields: impl Iterator<Item = &'a Field>,
+    crate_path: &Path,
+) -> TokenStream {
+    let fields = fields.map(|field| {
+        let name = field.ident.as_ref().unwrap();
+
+        match has_lifetime(&field.ty) {
+            true => quote!(#name: #crate_path::owned::IntoOwned::into_owned(self.#name)),
+            false => quote!(#name: self.#name),
+        }
+    });
+
+    quote!(#ident { #(#fields),* })
+}
@@ -1,2 +1,3 @@
 pub(crate) mod derive_captcha_task;
+pub(crate) mod into_owned;
 pub(crate) mod proxy_task;
@@ -74,7 +74,7 @@ pub fn proxy_task_expansion(
         #ast
 
         mod task_type {
-            #[derive(serde::Serialize, Debug)]
+            #[derive(serde::Serialize, Debug, Clone)]
             #[serde(tag = "type")]
             pub enum TaskType<'a> {
                 #[serde(rename = #proxyless)]
@@ -84,11 +84,33 @@ pub fn proxy_task_expansion(
                 WithProxy(crate::proxy::Proxy<'a>),
             }
 
+            impl crate::owned::IntoOwned for TaskType<'_> {
+                type Owned = TaskType<'static>;
+
+                fn into_owned(self) -> Self::Owned {
+                    match self {
+                        Self::ProxyLess => TaskType::ProxyLess,
+                        Self::WithProxy(proxy) => {
+                            TaskType::WithProxy(crate::owned::IntoOwned::into_owned(proxy))
+                        }
+                    }
+                }
+            }
+
             impl<'a> From<Option<crate::proxy::Proxy<'a>>> for TaskType<'a> {
                 fn from(value: Option<crate::proxy::Proxy<'a>>) -> Self {
                     value.map_or(Self::ProxyLess, Self::WithProxy)
                 }
             }
+
+            impl<'a> From<TaskType<'a>> for Option<crate::proxy::Proxy<'a>> {
+                fn from(value: TaskType<'a>) -> Self {
+                    match value {
+                        TaskType::ProxyLess => None,
+                        TaskType::WithProxy(proxy) => Some(proxy),
+                    }
+                }
+            }
         }
     })
 }
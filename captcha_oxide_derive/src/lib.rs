@@ -36,3 +36,8 @@ pub fn proxy_task(
 pub fn derive_captcha_task(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     expansion::derive_captcha_task::derive_captcha_task_expansion(input.into()).resolve()
 }
+
+#[proc_macro_derive(IntoOwned, attributes(into_owned))]
+pub fn derive_into_owned(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expansion::into_owned::into_owned_expansion(input.into()).resolve()
+}
@@ -0,0 +1,18 @@
+use syn::{visit::Visit, Lifetime, Type};
+
+/// Looks for lifetimes anywhere in a type, e.g.: `Option<Cow<'a, str>>`
+struct LifetimeVisitor {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for LifetimeVisitor {
+    fn visit_lifetime(&mut self, _: &'ast Lifetime) {
+        self.found = true;
+    }
+}
+
+pub fn has_lifetime(ty: &Type) -> bool {
+    let mut visitor = LifetimeVisitor { found: false };
+    visitor.visit_type(ty);
+    visitor.found
+}
@@ -8,3 +8,4 @@ macro_rules! declare {
 declare!(extract_named_fields);
 declare!(extract_struct_data);
 declare!(classify_fields);
+declare!(has_lifetime);
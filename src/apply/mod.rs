@@ -0,0 +1,367 @@
+//! Submission of solutions to the site that served the captcha
+//!
+//! The solutions of widgets that submit them in a known place implement
+//! [`ApplySolution`], which describes the form fields, cookies and user agent
+//! the site expects the solution in, e.g.: `g-recaptcha-response` for
+//! reCAPTCHA or the `datadome` cookie for DataDome, and writes them into a
+//! [`reqwest::RequestBuilder`] or a form map.
+//!
+//! The answers of image, audio and text captchas are sent in a field each
+//! site names as it wants, so their solutions have an `application` method
+//! that takes the name of the field instead, and the [`Application`] it
+//! returns implements [`ApplySolution`] itself.
+//!
+//! # Example
+//! ```no_run
+//! use std::collections::HashMap;
+//!
+//! use captcha_oxide::{
+//!     apply::ApplySolution,
+//!     captcha_types::recaptcha::RecaptchaV2,
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = CaptchaSolver::new("YOUR_API_KEY");
+//! let task = RecaptchaV2::builder()
+//!     .website_url("https://example.com/login")
+//!     .website_key("SOME_SITE_KEY")
+//!     .build()?;
+//!
+//! let solution = solver.solve(task).await?.expect("No callback url was set");
+//!
+//! let form = HashMap::from([("username".to_owned(), "user".to_owned())]);
+//! let response = solution
+//!     .apply_form(reqwest::Client::new().post("https://example.com/login"), form)
+//!     .send()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{borrow::Cow, collections::HashMap};
+
+use reqwest::{
+    header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, USER_AGENT},
+    Method, Request, RequestBuilder,
+};
+
+use crate::solution::CaptchaSolution;
+
+/// The form fields, cookies and user agent a site expects a solution in
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Application<'a> {
+    /// Sent as form fields or query parameters
+    pub fields: Vec<(&'static str, Cow<'a, str>)>,
+
+    pub cookies: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+
+    /// The user agent the solution was obtained with, which the
+    /// site may require the request to be sent with
+    pub user_agent: Option<Cow<'a, str>>,
+}
+
+impl<'a> Application<'a> {
+    pub fn field(mut self, name: &'static str, value: impl Into<Cow<'a, str>>) -> Self {
+        self.fields.push((name, value.into()));
+        self
+    }
+
+    pub fn cookie(mut self, name: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        self.cookies.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<Cow<'a, str>>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn into_owned(self) -> Application<'static> {
+        let owned = |x: Cow<'_, str>| Cow::Owned(x.into_owned());
+
+        Application {
+            fields: self
+                .fields
+                .into_iter()
+                .map(|(name, value)| (name, owned(value)))
+                .collect(),
+            cookies: self
+                .cookies
+                .into_iter()
+                .map(|(name, value)| (owned(name), owned(value)))
+                .collect(),
+            user_agent: self.user_agent.map(owned),
+        }
+    }
+
+    /// Applies the solution to a request that was already built: the fields
+    /// are added to its form body, or to its query if it doesn't have one, and
+    /// the cookies to its `Cookie` header, replacing any existing values
+    /// with the same names
+    pub fn apply_to(&self, request: &mut Request) {
+        if !self.fields.is_empty() {
+            self.apply_fields(request);
+        }
+
+        self.apply_headers_to(request);
+    }
+
+    fn apply_headers_to(&self, request: &mut Request) {
+        if !self.cookies.is_empty() {
+            let cookies = cookies(request)
+                .into_iter()
+                .filter(|(name, _)| self.cookies.iter().all(|(x, _)| x != name))
+                .map(|(name, value)| format!("{name}={value}"))
+                .chain(
+                    self.cookies
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}")),
+                )
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            if let Ok(cookies) = HeaderValue::from_str(&cookies) {
+                request.headers_mut().insert(COOKIE, cookies);
+            }
+        }
+
+        if let Some(Ok(user_agent)) = self.user_agent.as_deref().map(HeaderValue::from_str) {
+            request.headers_mut().insert(USER_AGENT, user_agent);
+        }
+    }
+
+    fn append_headers(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if !self.cookies.is_empty() {
+            let cookies = self
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            request = request.header(COOKIE, cookies);
+        }
+
+        if let Some(ref user_agent) = self.user_agent {
+            request = request.header(USER_AGENT, user_agent.as_ref());
+        }
+
+        request
+    }
+
+    fn apply_fields(&self, request: &mut Request) {
+        let is_form = request.method() == Method::POST
+            && request
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|x| x.to_str().ok())
+                .is_some_and(|x| x.starts_with("application/x-www-form-urlencoded"));
+
+        let form = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .filter(|_| is_form)
+            .map(|body| self.replace_fields(url::form_urlencoded::parse(body)));
+
+        match form {
+            Some(form) => {
+                request
+                    .headers_mut()
+                    .insert(CONTENT_LENGTH, HeaderValue::from(form.len()));
+                *request.body_mut() = Some(form.into());
+            }
+            None => {
+                let query = self.replace_fields(request.url().query_pairs());
+                request.url_mut().set_query(Some(&query));
+            }
+        }
+    }
+
+    fn replace_fields<'b>(
+        &self,
+        pairs: impl Iterator<Item = (Cow<'b, str>, Cow<'b, str>)>,
+    ) -> String {
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+
+        for (name, value) in pairs.filter(|(name, _)| self.fields.iter().all(|(x, _)| x != name)) {
+            serializer.append_pair(&name, &value);
+        }
+
+        for (name, value) in &self.fields {
+            serializer.append_pair(name, value);
+        }
+
+        serializer.finish()
+    }
+}
+
+impl ApplySolution for Application<'_> {
+    fn application(&self) -> Application<'_> {
+        self.clone()
+    }
+}
+
+/// Writes a solution into the request that submits it to the target site
+pub trait ApplySolution {
+    /// The form fields, cookies and user agent the site expects the solution in
+    fn application(&self) -> Application<'_>;
+
+    /// Inserts the fields of the solution into a form map
+    fn fill_form(&self, form: &mut HashMap<String, String>) {
+        for (name, value) in self.application().fields {
+            form.insert(name.to_owned(), value.into_owned());
+        }
+    }
+
+    /// Sets the cookies and user agent of the solution. The cookies are merged
+    /// into the `Cookie` header of the request, replacing any existing values
+    /// with the same names, as [`Application::apply_to`] does
+    fn apply_headers(&self, request: RequestBuilder) -> RequestBuilder {
+        let application = self.application();
+
+        match request.try_clone().map(RequestBuilder::build_split) {
+            Some((client, Ok(mut built))) => {
+                application.apply_headers_to(&mut built);
+                RequestBuilder::from_parts(client, built)
+            }
+            // Requests with a streaming body can't be cloned to be merged into, and
+            // those that failed to build keep their error to be reported when sent
+            _ => application.append_headers(request),
+        }
+    }
+
+    /// Sends the fields of the solution as query parameters, along with its
+    /// cookies and user agent
+    fn apply_query(&self, request: RequestBuilder) -> RequestBuilder {
+        let fields = self.application().fields;
+        self.apply_headers(request).query(&fields)
+    }
+
+    /// Sends `form` with the fields of the solution as a URL encoded body,
+    /// along with its cookies and user agent
+    fn apply_form(
+        &self,
+        request: RequestBuilder,
+        mut form: HashMap<String, String>,
+    ) -> RequestBuilder {
+        self.fill_form(&mut form);
+        self.apply_headers(request).form(&form)
+    }
+}
+
+impl<T: ApplySolution> ApplySolution for CaptchaSolution<'_, T> {
+    fn application(&self) -> Application<'_> {
+        self.solution.application()
+    }
+}
+
+/// The cookies of the `Cookie` header of the request
+pub(crate) fn cookies(request: &Request) -> Vec<(String, String)> {
+    request
+        .headers()
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|x| x.to_str().ok())
+        .flat_map(|x| x.split(';'))
+        .filter_map(|x| x.split_once('='))
+        .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use reqwest::{header::COOKIE, Client, Method, Request};
+
+    use super::ApplySolution;
+    use crate::captcha_types::{
+        data_dome_captcha::DataDomeCaptchaSolution, geetest::GeeTestV3Solution,
+        normal_captcha::NormalCaptchaSolution, turnstile_captcha::TurnstileCaptchaSolution,
+    };
+
+    #[test]
+    fn applies_to_builders() {
+        let solution = TurnstileCaptchaSolution {
+            token: "TOKEN".into(),
+            user_agent: "Mozilla/5.0".into(),
+        };
+
+        let form = HashMap::from([("user".to_owned(), "name".to_owned())]);
+        let request = solution
+            .apply_form(Client::new().post("https://example.com/login"), form)
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["user-agent"], "Mozilla/5.0");
+
+        let body = std::str::from_utf8(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        let mut fields = body.split('&').collect::<Vec<_>>();
+        fields.sort_unstable();
+        assert_eq!(fields, ["cf-turnstile-response=TOKEN", "user=name"]);
+
+        let solution = GeeTestV3Solution {
+            challenge: "c".into(),
+            validate: "v".into(),
+            seccode: "s".into(),
+        };
+
+        let request = solution
+            .apply_query(Client::new().get("https://example.com/verify"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().query(),
+            Some("geetest_challenge=c&geetest_validate=v&geetest_seccode=s")
+        );
+    }
+
+    #[test]
+    fn applies_to_requests() {
+        let solution = DataDomeCaptchaSolution {
+            cookie: "datadome=SOLVED; Max-Age=31536000; Domain=.example.com; Path=/".into(),
+        };
+
+        let mut request = Request::new(Method::GET, "https://example.com/".parse().unwrap());
+        request
+            .headers_mut()
+            .insert(COOKIE, "session=abc; datadome=OLD".parse().unwrap());
+
+        solution.application().apply_to(&mut request);
+        assert_eq!(request.headers()[COOKIE], "session=abc; datadome=SOLVED");
+    }
+
+    #[test]
+    fn merges_cookies_into_builders() {
+        let solution = DataDomeCaptchaSolution {
+            cookie: "datadome=SOLVED; Max-Age=31536000; Domain=.example.com; Path=/".into(),
+        };
+
+        let request = Client::new()
+            .get("https://example.com/")
+            .header(COOKIE, "session=abc; datadome=OLD");
+        let request = solution.apply_headers(request).build().unwrap();
+
+        assert_eq!(request.headers().get_all(COOKIE).iter().count(), 1);
+        assert_eq!(request.headers()[COOKIE], "session=abc; datadome=SOLVED");
+    }
+
+    #[test]
+    fn applies_to_named_fields() {
+        let solution = NormalCaptchaSolution {
+            text: "w68hp".into(),
+        };
+
+        let request = solution
+            .application("code")
+            .apply_query(Client::new().get("https://example.com/verify"))
+            .build()
+            .unwrap();
+
+        assert_eq!(request.url().query(), Some("code=w68hp"));
+    }
+}
@@ -0,0 +1,534 @@
+//! A dataset of the image and audio tasks that were paid for, to train your own models
+//!
+//! A [`TrainingArchive`] set with [`crate::solver::SolverBuilder::archive`]
+//! keeps a [`Record`] of every task with an image or audio `body` that is
+//! solved by 2captcha: the task with its media, the solution, its cost and
+//! the number of workers that solved it, along with the [`SolutionStatus`]
+//! it's later reported with. Cookies, proxies and keys are removed from the
+//! task before it's stored.
+//!
+//! The archive is a directory with one `records/<task_id>.json` file per
+//! record and an `index.jsonl` file with one [`IndexEntry`] per line. Lines are
+//! appended as tasks are archived and reported, and the last line of a task
+//! replaces the ones before it. The index is only rewritten when retention
+//! removes records or when most of its lines were replaced.
+//!
+//! Archiving is best-effort and happens off the async runtime: a task whose
+//! record can't be written is still solved.
+//!
+//! # Example
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use captcha_oxide::{archive::TrainingArchive, CaptchaSolver};
+//!
+//! let archive = TrainingArchive::new("/srv/captcha-dataset")
+//!     .sample_rate(0.25)
+//!     .max_records(Some(100_000))
+//!     .max_age(Some(Duration::from_secs(90 * 24 * 60 * 60)));
+//!
+//! let solver = CaptchaSolver::builder()
+//!     .api_key("YOUR_API_KEY")
+//!     .archive(Some(archive))
+//!     .build();
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use crate::{prelude::*, solver::SolutionStatus, CaptchaTask};
+
+/// The parameters that may identify the client, which are never archived
+const SENSITIVE: &[&str] = &["cookie", "proxy", "key", "token", "password", "useragent"];
+
+/// An archived task and its solution
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct Record {
+    pub task_id: u64,
+
+    /// The `type` of the task, e.g.: `"ImageToTextTask"`
+    pub task_type: String,
+
+    /// The task as it was sent to 2captcha, including its media
+    /// but without cookies, proxies or keys
+    pub task: Value,
+
+    /// The `solution` object returned by 2captcha
+    pub solution: Value,
+
+    pub cost: String,
+
+    /// The number of workers that attempted the task
+    pub solve_count: u8,
+
+    /// How the solution was reported, if it was
+    pub status: Option<SolutionStatus>,
+
+    pub archived_at: DateTime<Utc>,
+}
+
+/// A line of `index.jsonl`, which is a [`Record`] without its task and solution
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct IndexEntry {
+    pub task_id: u64,
+    pub task_type: String,
+    pub cost: String,
+    pub solve_count: u8,
+    pub status: Option<SolutionStatus>,
+    pub archived_at: DateTime<Utc>,
+
+    /// The path of the record, relative to the archive
+    pub path: PathBuf,
+}
+
+/// A directory where the tasks solved by a [`crate::CaptchaSolver`] are archived
+#[derive(Debug)]
+pub struct TrainingArchive {
+    directory: PathBuf,
+    sample_rate: f64,
+    max_records: Option<usize>,
+    max_age: Option<Duration>,
+    index: Mutex<Option<Index>>,
+}
+
+/// The entries of `index.jsonl`, and the number of lines of the file
+#[derive(Debug, Default)]
+struct Index {
+    entries: Vec<IndexEntry>,
+    lines: usize,
+}
+
+impl TrainingArchive {
+    /// An archive in `directory` that keeps every task forever
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            sample_rate: 1.0,
+            max_records: None,
+            max_age: None,
+            index: Mutex::new(None),
+        }
+    }
+
+    /// The fraction of the tasks that are archived, from 0 to 1
+    pub const fn sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// The number of records that are kept. The oldest ones are removed first
+    pub const fn max_records(mut self, max_records: Option<usize>) -> Self {
+        self.max_records = max_records;
+        self
+    }
+
+    /// How long records are kept for
+    pub const fn max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Every record in the archive, from the oldest to the newest
+    pub fn index(&self) -> Result<Vec<IndexEntry>> {
+        self.with_index(|index| Ok(index.entries.clone()))
+    }
+
+    pub fn record(&self, task_id: u64) -> Result<Option<Record>> {
+        self.with_index(|_| match fs::read_to_string(self.record_path(task_id)) {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        })
+    }
+
+    /// Archives the task and the `/getTaskResult` response it was solved with,
+    /// if it has an image or audio `body` and it's sampled. The files are
+    /// written on a blocking thread
+    pub(crate) async fn archive<T>(
+        self: &Arc<Self>,
+        task: &T,
+        task_id: u64,
+        json: &str,
+    ) -> Result<()>
+    where
+        T: CaptchaTask,
+    {
+        let task = serde_json::to_value(task)?;
+        let json = json.to_owned();
+        let archive = self.clone();
+
+        tokio::task::spawn_blocking(move || archive.write(task, task_id, &json))
+            .await
+            .unwrap_or(Ok(()))
+    }
+
+    /// Stores how the solution of the task was reported, if it's archived
+    pub(crate) async fn set_status(
+        self: &Arc<Self>,
+        task_id: u64,
+        status: SolutionStatus,
+    ) -> Result<()> {
+        let archive = self.clone();
+
+        tokio::task::spawn_blocking(move || archive.write_status(task_id, status))
+            .await
+            .unwrap_or(Ok(()))
+    }
+
+    fn write(&self, task: Value, task_id: u64, json: &str) -> Result<()> {
+        let Value::Object(mut task) = task else {
+            return Ok(());
+        };
+
+        if !task.contains_key("body") || rand::random::<f64>() >= self.sample_rate {
+            return Ok(());
+        }
+
+        task.retain(|key, _| {
+            let key = key.to_lowercase();
+            !SENSITIVE.iter().any(|x| key.contains(x))
+        });
+
+        let mut result: Map<String, Value> = serde_json::from_str(json)?;
+        let record = Record {
+            task_id,
+            task_type: task
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+            solution: result.remove("solution").unwrap_or_default(),
+            cost: result
+                .remove("cost")
+                .and_then(|x| x.as_str().map(str::to_owned))
+                .unwrap_or_default(),
+            solve_count: result
+                .get("solveCount")
+                .and_then(Value::as_u64)
+                .map_or(0, |x| x.try_into().unwrap_or(u8::MAX)),
+            task: Value::Object(task),
+            status: None,
+            archived_at: Utc::now(),
+        };
+
+        self.with_index(|index| {
+            fs::create_dir_all(self.directory.join("records"))?;
+            self.write_record(&record)?;
+
+            let entry = IndexEntry {
+                task_id,
+                task_type: record.task_type,
+                cost: record.cost,
+                solve_count: record.solve_count,
+                status: None,
+                archived_at: record.archived_at,
+                path: Path::new("records").join(format!("{task_id}.json")),
+            };
+            index.entries.push(entry.clone());
+
+            match self.apply_retention(&mut index.entries) {
+                true => self.write_index(index),
+                false => self.append_index(index, &entry),
+            }
+        })
+    }
+
+    fn write_status(&self, task_id: u64, status: SolutionStatus) -> Result<()> {
+        self.with_index(|index| {
+            let Some(entry) = index.entries.iter_mut().find(|x| x.task_id == task_id) else {
+                return Ok(());
+            };
+            entry.status = Some(status);
+            let entry = entry.clone();
+
+            let json = fs::read_to_string(self.record_path(task_id))?;
+            let mut record: Record = serde_json::from_str(&json)?;
+            record.status = Some(status);
+            self.write_record(&record)?;
+
+            self.append_index(index, &entry)
+        })
+    }
+
+    /// Runs `f` with the index locked, reading it from `index.jsonl` the first time
+    fn with_index<R>(&self, f: impl FnOnce(&mut Index) -> Result<R>) -> Result<R> {
+        let mut index = self.index.lock().unwrap_or_else(|x| x.into_inner());
+
+        let index = match *index {
+            Some(ref mut index) => index,
+            None => index.insert(self.read_index()?),
+        };
+
+        f(index)
+    }
+
+    /// Reads `index.jsonl`, in which the last line of a task replaces the ones before it
+    fn read_index(&self) -> Result<Index> {
+        let lines = match fs::read_to_string(self.directory.join("index.jsonl")) {
+            Ok(lines) => lines,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Index::default()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut index = Index::default();
+        let mut positions = HashMap::new();
+
+        for line in lines.lines().filter(|x| !x.trim().is_empty()) {
+            let entry: IndexEntry = serde_json::from_str(line)?;
+            index.lines += 1;
+
+            match positions.get(&entry.task_id) {
+                Some(&position) => index.entries[position] = entry,
+                None => {
+                    positions.insert(entry.task_id, index.entries.len());
+                    index.entries.push(entry);
+                }
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Removes the records that are too old, then the oldest ones until
+    /// there are at most [`TrainingArchive::max_records`]. Returns
+    /// whether any record was removed
+    fn apply_retention(&self, index: &mut Vec<IndexEntry>) -> bool {
+        let mut removed = Vec::new();
+
+        if let Some(max_age) = self
+            .max_age
+            .and_then(|x| chrono::Duration::from_std(x).ok())
+        {
+            let oldest = Utc::now() - max_age;
+            let kept = index.iter().take_while(|x| x.archived_at < oldest).count();
+            removed.extend(index.drain(..kept));
+        }
+
+        if let Some(max_records) = self.max_records {
+            let excess = index.len().saturating_sub(max_records);
+            removed.extend(index.drain(..excess));
+        }
+
+        let is_removed = !removed.is_empty();
+        for entry in removed {
+            let _ = fs::remove_file(self.directory.join(entry.path));
+        }
+
+        is_removed
+    }
+
+    fn record_path(&self, task_id: u64) -> PathBuf {
+        self.directory
+            .join("records")
+            .join(format!("{task_id}.json"))
+    }
+
+    fn write_record(&self, record: &Record) -> Result<()> {
+        fs::write(
+            self.record_path(record.task_id),
+            serde_json::to_vec(record)?,
+        )?;
+        Ok(())
+    }
+
+    /// Appends the entry to `index.jsonl`, unless most of its lines were
+    /// replaced by later ones, in which case the index is rewritten
+    fn append_index(&self, index: &mut Index, entry: &IndexEntry) -> Result<()> {
+        if index.lines + 1 > 2 * index.entries.len() {
+            return self.write_index(index);
+        }
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.directory.join("index.jsonl"))?
+            .write_all(line.as_bytes())?;
+        index.lines += 1;
+
+        Ok(())
+    }
+
+    /// Replaces `index.jsonl`, writing to a temporary file first so
+    /// the index is never left half written
+    fn write_index(&self, index: &mut Index) -> Result<()> {
+        let mut lines = String::new();
+        for entry in &index.entries {
+            lines += &serde_json::to_string(entry)?;
+            lines.push('\n');
+        }
+
+        let path = self.directory.join("index.jsonl");
+        let temporary = self.directory.join("index.jsonl.tmp");
+
+        fs::write(&temporary, lines)?;
+        fs::rename(temporary, path)?;
+        index.lines = index.entries.len();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use chrono::Utc;
+    use serde_json::{json, Value};
+
+    use super::{IndexEntry, TrainingArchive};
+    use crate::{
+        captcha_types::normal_captcha::NormalCaptcha,
+        solver::SolutionStatus,
+        test_server::{self, Response},
+        CaptchaTask,
+    };
+
+    const RESULT: &str = r#"{"errorId":0,"status":"ready","solution":{"text":"w68hp"},"cost":"0.00299","solveCount":1}"#;
+
+    fn directory() -> PathBuf {
+        std::env::temp_dir().join(format!("archive-{}", rand::random::<u32>()))
+    }
+
+    fn task() -> Value {
+        json!({ "type": "ImageToTextTask", "body": "IMAGE" })
+    }
+
+    fn archived(archive: &TrainingArchive) -> Vec<u64> {
+        archive.index().unwrap().iter().map(|x| x.task_id).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn archives_tasks() {
+        let directory = directory();
+
+        let url = test_server::serve(|request| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => Response::task_ready(r#"{"text":"w68hp"}"#),
+            _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+        });
+
+        let solver = test_server::solver_builder(url)
+            .archive(Some(TrainingArchive::new(&directory)))
+            .build();
+
+        let task = NormalCaptcha::builder()
+            .body("IMAGE")
+            .comment(Some("Type the red characters"))
+            .build();
+        let solution = solver.solve(task).await.unwrap().unwrap();
+        solver.report(solution, SolutionStatus::Bad).await.unwrap();
+
+        let archive = TrainingArchive::new(&directory);
+        let index = archive.index().unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].task_type, "ImageToTextTask");
+        assert_eq!(index[0].status, Some(SolutionStatus::Bad));
+
+        let record = archive.record(index[0].task_id).unwrap().unwrap();
+        assert_eq!(record.task["body"], "IMAGE");
+        assert_eq!(record.task["comment"], "Type the red characters");
+        assert_eq!(record.solution["text"], "w68hp");
+        assert_eq!(record.cost, "0.00299");
+        assert_eq!(record.solve_count, 1);
+
+        // The report is appended rather than rewriting the index
+        let lines = fs::read_to_string(directory.join("index.jsonl")).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn samples_tasks() {
+        let directory = directory();
+
+        let archive = TrainingArchive::new(&directory).sample_rate(0.0);
+        for task_id in 0..10 {
+            archive.write(task(), task_id, RESULT).unwrap();
+        }
+        assert!(archived(&archive).is_empty());
+
+        // Tasks without media aren't archived, whatever the sample rate
+        let archive = TrainingArchive::new(&directory);
+        archive
+            .write(json!({ "type": "TextCaptchaTask" }), 10, RESULT)
+            .unwrap();
+        archive.write(task(), 11, RESULT).unwrap();
+        assert_eq!(archived(&archive), [11]);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn applies_retention() {
+        let directory = directory();
+
+        let archive = TrainingArchive::new(&directory).max_records(Some(2));
+        for task_id in 0..4 {
+            archive.write(task(), task_id, RESULT).unwrap();
+        }
+
+        assert_eq!(archived(&archive), [2, 3]);
+        assert!(archive.record(1).unwrap().is_none());
+        assert!(archive.record(3).unwrap().is_some());
+
+        // The rewritten index is read back the same
+        assert_eq!(archived(&TrainingArchive::new(&directory)), [2, 3]);
+
+        let archive = TrainingArchive::new(&directory).max_age(Some(Duration::from_secs(60)));
+        let entry = |task_id, age| IndexEntry {
+            task_id,
+            task_type: "ImageToTextTask".into(),
+            cost: "0.00299".into(),
+            solve_count: 1,
+            status: None,
+            archived_at: Utc::now() - chrono::Duration::seconds(age),
+            path: PathBuf::from("records").join(format!("{task_id}.json")),
+        };
+
+        let mut index = vec![entry(2, 120), entry(3, 30)];
+        assert!(archive.apply_retention(&mut index));
+        assert_eq!(index.iter().map(|x| x.task_id).collect::<Vec<_>>(), [3]);
+        assert!(!directory.join("records/2.json").exists());
+        assert!(!archive.apply_retention(&mut index));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn strips_sensitive_parameters() {
+        let directory = directory();
+        let archive = TrainingArchive::new(&directory);
+
+        let task = json!({
+            "type": "ImageToTextTask",
+            "body": "IMAGE",
+            "comment": "Type the red characters",
+            "cookies": "session=abc",
+            "proxyAddress": "1.2.3.4",
+            "proxyPassword": "secret",
+            "websiteKey": "SITE_KEY",
+            "userAgent": "Mozilla/5.0",
+        });
+        archive.write(task, 1, RESULT).unwrap();
+
+        let record = archive.record(1).unwrap().unwrap();
+        let mut keys = record.task.as_object().unwrap().keys().collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, ["body", "comment", "type"]);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,571 @@
+//! Classification of responses blocked by anti-bot services
+//!
+//! When a scraper receives a `403` or `429`, [`classify`] looks at the status,
+//! headers and body of the response to tell which service blocked it and
+//! collects the parameters needed to solve the challenge it served, if any.
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::blocked::{classify, Block, BlockTask};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), captcha_oxide::Error> {
+//! let response = reqwest::get("https://example.com/").await?;
+//! let url = response.url().to_string();
+//! let status = response.status();
+//! let headers = response.headers().clone();
+//! let body = response.text().await?;
+//!
+//! if let Some(blocked) = classify(&url, status, &headers, &body)? {
+//!     match &blocked.block {
+//!         Block::DataDome { is_banned: true, .. } => { /* change the proxy */ }
+//!         Block::RateLimit { retry_after } => { /* wait */ }
+//!         _ => {
+//!             if let Some(BlockTask::DataDome(task)) = blocked.to_task("Mozilla/5.0 ...", None)? {
+//!                 // solver.solve(task).await?
+//!             }
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, time::Duration};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER, SERVER, SET_COOKIE},
+    StatusCode,
+};
+use url::Url;
+
+use crate::{
+    captcha_types::{
+        data_dome_captcha::DataDomeCaptcha, turnstile_captcha::TurnstileChallengePageCaptcha,
+    },
+    detect::{self, DetectedTask, DetectedWidget, TaskContext, Vendor},
+    prelude::*,
+    proxy::Proxy,
+    CaptchaTask,
+};
+
+lazy_static! {
+    static ref DATA_DOME_OBJECT: Regex =
+        Regex::new(r"\bdd\s*=\s*(\{[^}]*\})").expect("The DataDome pattern is valid");
+    static ref CHALLENGE_OPTIONS: Regex = Regex::new(r"_cf_chl_opt\s*=\s*\{([^}]*)\}")
+        .expect("The Cloudflare challenge pattern is valid");
+    static ref OPTION: Regex = Regex::new(r#"['"]?(\w+)['"]?\s*:\s*['"]([^'"]*)['"]"#)
+        .expect("The option pattern is valid");
+}
+
+/// The host DataDome serves its captcha from when the page doesn't name one
+const DATA_DOME_HOST: &str = "geo.captcha-delivery.com";
+
+/// The reason a response was blocked
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Block {
+    #[serde(rename_all = "camelCase")]
+    DataDome {
+        /// The URL of the captcha iframe, missing if DataDome served its
+        /// device check instead of a captcha
+        captcha_url: Option<Url>,
+
+        /// Whether the `t` parameter of the captcha URL is `bv`, in which case
+        /// the IP address is banned and the captcha cannot be solved
+        is_banned: bool,
+    },
+
+    /// A Cloudflare challenge page
+    CloudflareChallenge(ChallengePage),
+
+    /// A captcha widget embedded in the block page, e.g.: an Amazon WAF
+    /// or GeeTest captcha
+    Widget(DetectedWidget),
+
+    /// The response identifies the vendor, but the parameters of its
+    /// challenge were not found
+    Vendor(Vendor),
+
+    /// A `429` that none of the vendors claims
+    #[serde(rename_all = "camelCase")]
+    RateLimit {
+        /// The delay requested by the `Retry-After` header, if any
+        retry_after: Option<Duration>,
+    },
+}
+
+/// The parameters of a Cloudflare challenge page, found in its `window._cf_chl_opt`
+/// object. Most pages only pass the sitekey, `cData` and `chlPageData` to the
+/// `turnstile.render` call, in which case they have to be captured in the browser
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengePage {
+    /// The `cf-ray` header or the `cRay` option
+    pub ray_id: Option<String>,
+
+    /// The `cType` option, e.g.: `managed`
+    pub challenge_type: Option<String>,
+
+    pub website_key: Option<String>,
+    pub data: Option<String>,
+    pub page_data: Option<String>,
+}
+
+/// A response blocked by an anti-bot service
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedResponse {
+    /// The URL of the blocked page
+    pub website_url: Url,
+    pub status: u16,
+    pub block: Block,
+}
+
+/// A task that solves the challenge of a [`BlockedResponse`]
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum BlockTask<'a> {
+    DataDome(DataDomeCaptcha<'a>),
+    TurnstileChallengePage(TurnstileChallengePageCaptcha<'a>),
+    Widget(DetectedTask<'a>),
+}
+
+impl BlockedResponse {
+    /// The vendor that blocked the response, [`None`] for plain rate limits.
+    /// Cloudflare challenge pages are reported as [`Vendor::Turnstile`]
+    pub const fn vendor(&self) -> Option<Vendor> {
+        match &self.block {
+            Block::DataDome { .. } => Some(Vendor::DataDome),
+            Block::CloudflareChallenge(_) => Some(Vendor::Turnstile),
+            Block::Widget(widget) => Some(widget.vendor()),
+            Block::Vendor(vendor) => Some(*vendor),
+            Block::RateLimit { .. } => None,
+        }
+    }
+
+    /// Builds the task that solves the challenge of the response, with the
+    /// user agent and proxy the page will be reloaded with.
+    ///
+    /// Returns [`None`] if the parameters of the challenge were not found,
+    /// the IP address is banned by DataDome or, as DataDome must be solved
+    /// through a proxy, for DataDome captchas when `proxy` is [`None`].
+    /// Embedded widgets are solved without the proxy
+    ///
+    /// # Errors
+    /// This method will error if the task cannot be built
+    pub fn to_task<'a>(
+        &'a self,
+        user_agent: &'a str,
+        proxy: Option<Proxy<'a>>,
+    ) -> Result<Option<BlockTask<'a>>> {
+        self.build_task(
+            TaskContext {
+                user_agent: Some(user_agent),
+                cookies: &[],
+            },
+            proxy,
+        )
+    }
+
+    pub(crate) fn build_task<'a>(
+        &'a self,
+        context: TaskContext<'a>,
+        proxy: Option<Proxy<'a>>,
+    ) -> Result<Option<BlockTask<'a>>> {
+        let website_url = self.website_url.as_str();
+
+        let task = match &self.block {
+            Block::DataDome {
+                captcha_url: Some(captcha_url),
+                is_banned: false,
+            } => {
+                let (Some(user_agent), Some(proxy)) = (context.user_agent, proxy) else {
+                    return Ok(None);
+                };
+
+                BlockTask::DataDome(
+                    DataDomeCaptcha::builder()
+                        .website_url(website_url)
+                        .captcha_url(captcha_url.as_str())
+                        .user_agent(user_agent)
+                        .proxy(proxy)
+                        .build()?,
+                )
+            }
+            Block::CloudflareChallenge(ChallengePage {
+                challenge_type: Some(action),
+                website_key: Some(website_key),
+                data: Some(data),
+                page_data: Some(page_data),
+                ..
+            }) => {
+                let Some(user_agent) = context.user_agent else {
+                    return Ok(None);
+                };
+
+                BlockTask::TurnstileChallengePage(
+                    TurnstileChallengePageCaptcha::builder()
+                        .website_url(website_url)
+                        .website_key(website_key.as_str())
+                        .user_agent(user_agent)
+                        .action(action.as_str())
+                        .data(data.as_str())
+                        .page_data(page_data.as_str())
+                        .proxy(proxy)
+                        .build()?,
+                )
+            }
+            Block::Widget(widget) => BlockTask::Widget(widget.build_task(context)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(task))
+    }
+}
+
+/// Tells whether a response was blocked by an anti-bot service, and by which.
+/// DataDome, Cloudflare and Amazon WAF are recognized by their headers, other
+/// vendors by the captcha widgets embedded in the body of a `403`, `405` or
+/// `429`. As DataDome marks every response of the sites it protects and its tag
+/// is embedded in their pages, its headers and scripts only count on those
+/// statuses, unless the body is a DataDome challenge. Returns [`None`] if the
+/// response doesn't look blocked
+///
+/// # Errors
+/// This function will error if `url` is not a valid URL
+pub fn classify(
+    url: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> Result<Option<BlockedResponse>> {
+    let website_url = Url::parse(url)?;
+    let header = |name: &str| headers.get(name).and_then(|x| x.to_str().ok());
+    let widgets = || detect::detect(body, url);
+
+    let is_blocking_status = matches!(status.as_u16(), 403 | 405 | 429);
+
+    // DataDome sends `x-datadome` with every response of a protected site
+    let is_data_dome = (header("x-datadome").is_some()
+        || header("x-dd-b").is_some()
+        || body.contains("captcha-delivery.com"))
+        && is_blocking_status;
+
+    let block = if is_data_dome || is_data_dome_challenge(body) {
+        data_dome(&website_url, headers, body)
+    } else if header("cf-mitigated") == Some("challenge")
+        || (header(SERVER.as_str()) == Some("cloudflare") && body.contains("_cf_chl_opt"))
+    {
+        Block::CloudflareChallenge(challenge_page(header("cf-ray"), body))
+    } else if header("x-amzn-waf-action").is_some() {
+        widgets()?
+            .into_iter()
+            .find(|x| x.vendor() == Vendor::AmazonWaf)
+            .map_or(Block::Vendor(Vendor::AmazonWaf), Block::Widget)
+    } else if is_blocking_status {
+        if let Some(widget) = widgets()?.into_iter().next() {
+            Block::Widget(widget)
+        } else if body.contains("geetest.com") {
+            Block::Vendor(Vendor::GeeTest)
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            Block::RateLimit {
+                retry_after: header(RETRY_AFTER.as_str())
+                    .and_then(|x| x.trim().parse().ok())
+                    .map(Duration::from_secs),
+            }
+        } else {
+            return Ok(None);
+        }
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(BlockedResponse {
+        website_url,
+        status: status.as_u16(),
+        block,
+    }))
+}
+
+/// DataDome answers API requests with a JSON body holding the captcha URL and
+/// pages with a `dd` object the URL is built from
+fn data_dome(website_url: &Url, headers: &HeaderMap, body: &str) -> Block {
+    let captcha_url = serde_json::from_str::<HashMap<String, serde_json::Value>>(body)
+        .ok()
+        .and_then(|json| Url::parse(json.get("url")?.as_str()?).ok())
+        .or_else(|| data_dome_object(website_url, headers, body));
+
+    let is_banned = captcha_url
+        .as_ref()
+        .and_then(|url| detect::query(url, "t"))
+        .is_some_and(|t| t == "bv");
+
+    Block::DataDome {
+        captcha_url,
+        is_banned,
+    }
+}
+
+/// Whether the body is the JSON DataDome answers blocked requests with, whose
+/// `url` is its captcha page, or a page with the `dd` object of a captcha
+fn is_data_dome_challenge(body: &str) -> bool {
+    let is_captcha_url = serde_json::from_str::<HashMap<String, serde_json::Value>>(body)
+        .ok()
+        .and_then(|json| Url::parse(json.get("url")?.as_str()?).ok())
+        .is_some_and(|url| {
+            url.host_str()
+                .is_some_and(|host| host.ends_with(".captcha-delivery.com"))
+        });
+
+    is_captcha_url
+        || data_dome_values(body)
+            .is_some_and(|values| values.get("rt").and_then(|x| x.as_str()) == Some("c"))
+}
+
+/// The values of the `dd` object of a DataDome challenge page
+fn data_dome_values(body: &str) -> Option<HashMap<String, serde_json::Value>> {
+    let object = &DATA_DOME_OBJECT.captures(body)?[1];
+    serde_json::from_str(&object.replace('\'', "\"")).ok()
+}
+
+fn data_dome_object(website_url: &Url, headers: &HeaderMap, body: &str) -> Option<Url> {
+    let values = data_dome_values(body)?;
+
+    let value = |name: &str| match values.get(name)? {
+        serde_json::Value::String(x) => Some(x.clone()),
+        serde_json::Value::Number(x) => Some(x.to_string()),
+        _ => None,
+    };
+
+    // `rt` is `i` for the device check, which isn't a captcha
+    if value("rt").is_some_and(|rt| rt != "c") {
+        return None;
+    }
+
+    let cookie = value("cookie").or_else(|| {
+        headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|x| x.to_str().ok())
+            .find_map(|x| x.strip_prefix("datadome="))
+            .map(|x| x.split(';').next().unwrap_or_default().to_owned())
+    });
+
+    let host = value("host").unwrap_or_else(|| DATA_DOME_HOST.to_owned());
+    let mut url = Url::parse(&format!("https://{host}/captcha/")).ok()?;
+
+    {
+        let mut query = url.query_pairs_mut();
+        for (name, value) in [
+            ("initialCid", value("cid")),
+            ("hash", value("hsh")),
+            ("cid", cookie),
+            ("t", value("t")),
+            ("referer", Some(website_url.to_string())),
+            ("s", value("s")),
+            ("e", value("e")),
+        ] {
+            if let Some(value) = value {
+                query.append_pair(name, &value);
+            }
+        }
+    }
+
+    Some(url)
+}
+
+fn challenge_page(ray_id: Option<&str>, body: &str) -> ChallengePage {
+    let options = CHALLENGE_OPTIONS
+        .captures(body)
+        .map(|captures| {
+            OPTION
+                .captures_iter(&captures[1])
+                .map(|x| (x[1].to_owned(), x[2].to_owned()))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let option = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| options.get(*name))
+            .filter(|x| !x.is_empty())
+            .cloned()
+    };
+
+    ChallengePage {
+        ray_id: ray_id.map(str::to_owned).or_else(|| option(&["cRay"])),
+        challenge_type: option(&["cType"]),
+        website_key: option(&["chlApiSitekey", "sitekey"]),
+        data: option(&["cData"]),
+        page_data: option(&["chlPageData"]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        StatusCode,
+    };
+
+    use super::{classify, Block, BlockTask, ChallengePage};
+    use crate::{
+        detect::Vendor,
+        proxy::{Address, Proxy, ProxyType},
+    };
+
+    const URL: &str = "https://example.com/products";
+
+    fn headers(values: &[(&'static str, &'static str)]) -> HeaderMap {
+        values
+            .iter()
+            .map(|(name, value)| (*name, HeaderValue::from_static(value)))
+            .map(|(name, value)| (name.parse().unwrap(), value))
+            .collect()
+    }
+
+    fn proxy() -> Proxy<'static> {
+        Proxy {
+            proxy_type: ProxyType::Http,
+            proxy_address: Address::HostName("proxy.example.com".into()),
+            proxy_port: "8080".into(),
+            proxy_login: None,
+            proxy_password: None,
+        }
+    }
+
+    #[test]
+    fn classifies_data_dome() {
+        let protected = headers(&[("x-datadome", "protected")]);
+        assert!(classify(URL, StatusCode::OK, &protected, "<html></html>")
+            .unwrap()
+            .is_none());
+
+        // A page that embeds the DataDome tag isn't a block either
+        let tagged = headers(&[("x-dd-b", "1")]);
+        let page = r#"<script src="https://js.datadome.co/tags.js"></script><img src="https://geo.captcha-delivery.com/logo.png">"#;
+        assert!(classify(URL, StatusCode::OK, &tagged, page)
+            .unwrap()
+            .is_none());
+
+        let body = r"<html><script>var dd={'rt':'c','cid':'AHrlqAAAAAMA','hsh':'C0705ACD','t':'fe','s':40070,'e':'3e531bd3','host':'geo.captcha-delivery.com'}</script></html>";
+        let headers = headers(&[
+            ("x-datadome", "protected"),
+            ("set-cookie", "datadome=7sfa5xUf; Path=/; Secure"),
+        ]);
+
+        let blocked = classify(URL, StatusCode::FORBIDDEN, &headers, body)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blocked.vendor(), Some(Vendor::DataDome));
+
+        let Block::DataDome {
+            captcha_url: Some(captcha_url),
+            is_banned: false,
+        } = &blocked.block
+        else {
+            panic!("Unexpected block: {:?}", blocked.block);
+        };
+        assert_eq!(
+            captcha_url.as_str(),
+            "https://geo.captcha-delivery.com/captcha/?initialCid=AHrlqAAAAAMA&hash=C0705ACD&cid=7sfa5xUf&t=fe&referer=https%3A%2F%2Fexample.com%2Fproducts&s=40070&e=3e531bd3"
+        );
+
+        assert!(blocked.to_task("Mozilla/5.0", None).unwrap().is_none());
+        assert!(matches!(
+            blocked.to_task("Mozilla/5.0", Some(proxy())).unwrap(),
+            Some(BlockTask::DataDome(_))
+        ));
+
+        let body = r#"{"url":"https://geo.captcha-delivery.com/captcha/?initialCid=AHrlqAAAAAMA&hash=C0705ACD&cid=7sfa5xUf&t=bv&s=40070"}"#;
+        let blocked = classify(URL, StatusCode::FORBIDDEN, &headers, body)
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            blocked.block,
+            Block::DataDome {
+                is_banned: true,
+                ..
+            }
+        ));
+        assert!(blocked
+            .to_task("Mozilla/5.0", Some(proxy()))
+            .unwrap()
+            .is_none());
+
+        // The challenge itself is recognized whatever the status
+        let blocked = classify(URL, StatusCode::OK, &tagged, body)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blocked.vendor(), Some(Vendor::DataDome));
+    }
+
+    #[test]
+    fn classifies_other_blocks() {
+        let body = r"<script>window._cf_chl_opt={cvId: '3',cZone: 'example.com',cType: 'managed',cRay: '8c4c22e8ed9b85e9'};</script>";
+        let blocked = classify(
+            URL,
+            StatusCode::FORBIDDEN,
+            &headers(&[("cf-mitigated", "challenge"), ("server", "cloudflare")]),
+            body,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            blocked.block,
+            Block::CloudflareChallenge(ChallengePage {
+                ray_id: Some("8c4c22e8ed9b85e9".into()),
+                challenge_type: Some("managed".into()),
+                ..Default::default()
+            })
+        );
+        assert!(blocked.to_task("Mozilla/5.0", None).unwrap().is_none());
+
+        let body = r#"<script>window.gokuProps = {"key":"AQIDAHjcYu","iv":"CgAFRjIw2vAAABSM","context":"zPT0jOl1rQ"};</script>"#;
+        let blocked = classify(
+            URL,
+            StatusCode::METHOD_NOT_ALLOWED,
+            &headers(&[("x-amzn-waf-action", "captcha")]),
+            body,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(blocked.vendor(), Some(Vendor::AmazonWaf));
+        assert!(matches!(
+            blocked.to_task("Mozilla/5.0", None).unwrap(),
+            Some(BlockTask::Widget(_))
+        ));
+
+        let blocked = classify(
+            URL,
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("retry-after", "30")]),
+            "Too many requests",
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            blocked.block,
+            Block::RateLimit {
+                retry_after: Some(Duration::from_secs(30))
+            }
+        );
+        assert_eq!(blocked.vendor(), None);
+
+        assert!(
+            classify(URL, StatusCode::OK, &HeaderMap::new(), "<html></html>")
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! A content-addressed cache of the solutions of image captchas
+//!
+//! Sites that serve static images show the same captcha over and over, and
+//! every time it's sent to 2captcha it's paid for again. A [`SolutionCache`]
+//! set with [`crate::solver::SolverBuilder::cache`] keeps the solutions of
+//! [`CaptchaSolver::solve_cached`](crate::CaptchaSolver::solve_cached), keyed
+//! by a hash of the serialized task, so a task that was already solved isn't
+//! sent again until its solution expires or is reported as
+//! [`SolutionStatus::Bad`](crate::solver::SolutionStatus::Bad).
+//!
+//! # Example
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use captcha_oxide::{
+//!     cache::SolutionCache,
+//!     captcha_types::normal_captcha::NormalCaptcha,
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = CaptchaSolver::builder()
+//!     .api_key("YOUR_API_KEY")
+//!     .cache(Some(SolutionCache::new().ttl(Duration::from_secs(24 * 60 * 60))))
+//!     .build();
+//!
+//! for _ in 0..2 {
+//!     let task = NormalCaptcha::builder()
+//!         .body("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==")
+//!         .build();
+//!
+//!     // Only the first iteration sends the task to 2captcha
+//!     let solution = solver.solve_cached(task).await?.expect("No callback url was set");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use tokio::{sync::OnceCell, time::Instant};
+
+use crate::{
+    captcha_types::{
+        audio_captcha::AudioCaptcha, bounding_box_captcha::BoundingBoxCaptcha,
+        coordinates_captcha::CoordinatesCaptcha, draw_around_captcha::DrawAroundCaptcha,
+        grid_captcha::GridCaptcha, normal_captcha::NormalCaptcha, rotate_captcha::RotateCaptcha,
+    },
+    prelude::*,
+    CaptchaTask,
+};
+
+/// The tasks whose solution only depends on their content, which makes
+/// the solutions of identical tasks interchangeable
+pub trait CacheableTask: CaptchaTask {}
+
+impl CacheableTask for AudioCaptcha<'_> {}
+impl CacheableTask for BoundingBoxCaptcha<'_> {}
+impl CacheableTask for CoordinatesCaptcha<'_> {}
+impl CacheableTask for DrawAroundCaptcha<'_> {}
+impl CacheableTask for GridCaptcha<'_> {}
+impl CacheableTask for NormalCaptcha<'_> {}
+impl CacheableTask for RotateCaptcha<'_> {}
+
+/// Solutions of [`CacheableTask`]s, keyed by a hash of the serialized task
+#[derive(Debug)]
+pub struct SolutionCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<u64, Arc<OnceCell<Entry>>>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    task_id: u64,
+    json: String,
+    solved_at: Instant,
+}
+
+impl SolutionCache {
+    /// A cache that keeps up to 1000 solutions for an hour
+    pub fn new() -> Self {
+        Self {
+            ttl: Duration::from_secs(60 * 60),
+            capacity: 1000,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long a solution is kept after the task is solved
+    pub const fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How many solutions are kept. The oldest ones are evicted first
+    pub const fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// The number of solutions in the cache
+    pub fn len(&self) -> usize {
+        self.lock()
+            .values()
+            .filter(|x| x.get().is_some_and(|x| !self.is_expired(x)))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the cached task id and result JSON of `task`, or solves it with
+    /// `solve` if there are none. Callers that ask for the same task while it's
+    /// being solved wait for the same result
+    pub(crate) async fn get_or_solve<T, F, Fut>(&self, task: &T, solve: F) -> Result<(u64, String)>
+    where
+        T: CacheableTask,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(u64, String)>>,
+    {
+        let key = {
+            let mut hasher = DefaultHasher::new();
+            serde_json::to_vec(task)?.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let cell = {
+            let mut entries = self.lock();
+            entries.retain(|_, x| x.get().is_none_or(|x| !self.is_expired(x)));
+
+            let cell = Arc::clone(entries.entry(key).or_default());
+            self.shrink(&mut entries);
+
+            cell
+        };
+
+        let result = cell
+            .get_or_try_init(|| async {
+                let (task_id, json) = solve().await?;
+
+                Ok::<_, crate::Error>(Entry {
+                    task_id,
+                    json,
+                    solved_at: Instant::now(),
+                })
+            })
+            .await;
+
+        match result {
+            Ok(entry) => Ok((entry.task_id, entry.json.clone())),
+            Err(error) => {
+                let mut entries = self.lock();
+                if entries
+                    .get(&key)
+                    .is_some_and(|x| Arc::ptr_eq(x, &cell) && x.get().is_none())
+                {
+                    entries.remove(&key);
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Removes the solutions of the task with this id
+    pub(crate) fn evict(&self, task_id: u64) {
+        self.lock()
+            .retain(|_, x| x.get().is_none_or(|x| x.task_id != task_id));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Arc<OnceCell<Entry>>>> {
+        self.entries.lock().unwrap_or_else(|x| x.into_inner())
+    }
+
+    fn is_expired(&self, entry: &Entry) -> bool {
+        entry.solved_at.elapsed() >= self.ttl
+    }
+
+    /// Evicts the oldest solutions until the cache fits its capacity.
+    /// Tasks that are still being solved are never evicted
+    fn shrink(&self, entries: &mut HashMap<u64, Arc<OnceCell<Entry>>>) {
+        while entries.len() > self.capacity {
+            let oldest = entries
+                .iter()
+                .filter_map(|(key, x)| Some((*key, x.get()?.solved_at)))
+                .min_by_key(|(_, solved_at)| *solved_at)
+                .map(|(key, _)| key);
+
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+}
+
+impl Default for SolutionCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use url::Url;
+
+    use super::SolutionCache;
+    use crate::{
+        captcha_types::normal_captcha::NormalCaptcha,
+        solver::SolutionStatus,
+        test_server::{self, RequestLog, Response},
+        CaptchaTask,
+    };
+
+    /// Emulates the 2captcha API, solving every task with the same answer
+    fn server() -> (Url, RequestLog) {
+        test_server::serve_logged(|request, _| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => Response::task_ready(r#"{"text":"w9h5k"}"#),
+            _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+        })
+    }
+
+    fn task(body: &str) -> NormalCaptcha<'_> {
+        NormalCaptcha::builder().body(body).build()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deduplicates_tasks() {
+        let (url, requests) = server();
+        let solver = test_server::solver_builder(url)
+            .cache(Some(SolutionCache::new().capacity(1)))
+            .build();
+
+        let (first, second) = tokio::join!(
+            solver.solve_cached(task("IMAGE")),
+            solver.solve_cached(task("IMAGE")),
+        );
+        assert_eq!(first.unwrap().unwrap().solution.text, "w9h5k");
+        assert_eq!(second.unwrap().unwrap().solution.text, "w9h5k");
+        assert_eq!(requests.count("/createTask"), 1);
+
+        let solution = solver.solve_cached(task("IMAGE")).await.unwrap().unwrap();
+        assert_eq!(requests.count("/createTask"), 1);
+
+        solver.report(solution, SolutionStatus::Bad).await.unwrap();
+        solver.solve_cached(task("IMAGE")).await.unwrap();
+        assert_eq!(requests.count("/createTask"), 2);
+
+        // Evicts the first image, as the capacity is 1
+        solver.solve_cached(task("OTHER")).await.unwrap();
+        solver.solve_cached(task("IMAGE")).await.unwrap();
+
+        let bodies = requests.bodies("/createTask");
+        assert_eq!(bodies.len(), 4);
+        assert!(bodies[2].contains(r#""body":"OTHER""#));
+        assert!(bodies[3].contains(r#""body":"IMAGE""#));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expires_solutions() {
+        let (url, requests) = server();
+        let solver = test_server::solver_builder(url)
+            .cache(Some(SolutionCache::new().ttl(Duration::from_secs(60))))
+            .build();
+
+        solver.solve_cached(task("IMAGE")).await.unwrap();
+        solver.solve_cached(task("IMAGE")).await.unwrap();
+        assert_eq!(requests.count("/createTask"), 1);
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        solver.solve_cached(task("IMAGE")).await.unwrap();
+        assert_eq!(requests.count("/createTask"), 2);
+    }
+}
@@ -1,7 +1,19 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct AmazonCaptchaSolution<'a> {
     pub captcha_voucher: Cow<'a, str>,
     pub existing_token: Cow<'a, str>,
 }
+
+impl ApplySolution for AmazonCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("captcha_voucher", &*self.captcha_voucher)
+            .field("existing_token", &*self.existing_token)
+    }
+}
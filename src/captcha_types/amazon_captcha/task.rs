@@ -26,7 +26,7 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "AmazonTask", proxyless = "AmazonTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 20, solution = super::solution::AmazonCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct AmazonCaptcha<'a> {
@@ -34,7 +34,7 @@ pub struct AmazonCaptcha<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// Value of the `key` parameter you found on the page
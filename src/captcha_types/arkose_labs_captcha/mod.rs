@@ -3,3 +3,41 @@ mod task;
 
 pub use solution::*;
 pub use task::*;
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{captcha_types::arkose_labs_captcha::ArkoseLabsCaptcha, CaptchaTask, Error};
+
+    #[derive(serde::Serialize, Clone)]
+    struct Blob {
+        blob: &'static str,
+    }
+
+    #[test]
+    fn serializes_data_payloads() -> Result<(), Error> {
+        let task = ArkoseLabsCaptcha::<Blob>::builder()
+            .website_url("https://www.example.com")
+            .website_public_key("6220FF23-9856-3A6F-9FF1-A14F88123F55")
+            .data(Some(Blob { blob: "BLOB" }))
+            .build()?;
+
+        let json = serde_json::to_value(&task)?;
+        assert_eq!(json["data"], r#"{"blob":"BLOB"}"#);
+
+        let resubmitted = task.to_builder().user_agent(Some("Mozilla/5.0")).build()?;
+        assert_eq!(serde_json::to_value(&resubmitted)?["data"], json["data"]);
+
+        // Payloads that can't be written as JSON fail to build
+        let error = ArkoseLabsCaptcha::<HashMap<(u8, u8), u8>>::builder()
+            .website_url("https://www.example.com")
+            .website_public_key("6220FF23-9856-3A6F-9FF1-A14F88123F55")
+            .data(Some(HashMap::from([((1, 2), 3)])))
+            .build();
+
+        assert!(matches!(error, Err(Error::SerializeError(_))));
+
+        Ok(())
+    }
+}
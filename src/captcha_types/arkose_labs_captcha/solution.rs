@@ -1,7 +1,17 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct ArkoseLabsCaptchaSolution<'a> {
     pub token: Cow<'a, str>,
 }
+
+impl ApplySolution for ArkoseLabsCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default().field("fc-token", &*self.token)
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, marker::PhantomData};
+use std::borrow::Cow;
 
 use captcha_oxide_derive::proxy_task;
 use url::Url;
@@ -29,22 +29,18 @@ use crate::{captcha_types::empty_data::Empty, CaptchaTask};
 /// create a serializable unit struct if you don't plan to use the
 /// [`ArkoseLabsCaptcha::data`] field
 #[proxy_task(with_proxy = "FunCaptchaTask", proxyless = "FunCaptchaTaskProxyless", crate = crate)]
-#[derive(CaptchaTask, serde::Serialize)]
+#[derive(CaptchaTask, serde::Serialize, Clone)]
 #[task(timeout = 20, crate = crate, solution = super::solution::ArkoseLabsCaptchaSolution<'a>)]
 #[serde(rename_all = "camelCase")]
 pub struct ArkoseLabsCaptcha<'a, T = Empty>
 where
     T: serde::Serialize,
 {
-    #[serde(skip)]
-    #[task(default = Default::default)]
-    pub(super) _x: PhantomData<T>,
-
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse })})]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// ArkoseLabsCaptcha public key. The public key can be found in
@@ -60,11 +56,41 @@ where
     /// Additional data payload object.
     /// This data will be converted to a JSON string internally
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[task(builder_type = Option<T>, parse_with = { fallible({ path = serde_json::to_string, parse_ref }) })]
-    pub(super) data: Option<Cow<'a, str>>,
+    #[task(builder_type = Option<T>, parse_with = { fallible({ path = crate::captcha_types::arkose_labs_captcha::DataPayload::new }) }, unparse_with = crate::captcha_types::arkose_labs_captcha::DataPayload::into_value)]
+    pub(super) data: Option<DataPayload<T>>,
 
     /// User-Agent your browser will be used to load the captcha.
     /// Use only modern browsers' User-Agents
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) user_agent: Option<Cow<'a, str>>,
 }
+
+/// The data payload of an [`ArkoseLabsCaptcha`], converted to the JSON string
+/// 2captcha expects when the task is built, so building fails if it can't be,
+/// and kept as it was given to the builder for [`ArkoseLabsCaptcha::to_builder`]
+#[derive(Debug, Clone)]
+pub(crate) struct DataPayload<T> {
+    json: String,
+    value: T,
+}
+
+impl<T: serde::Serialize> DataPayload<T> {
+    pub(crate) fn new(value: T) -> serde_json::Result<Self> {
+        Ok(Self {
+            json: serde_json::to_string(&value)?,
+            value,
+        })
+    }
+}
+
+impl<T> DataPayload<T> {
+    pub(crate) fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T> serde::Serialize for DataPayload<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.json)
+    }
+}
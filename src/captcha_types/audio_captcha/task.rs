@@ -17,7 +17,7 @@ use crate::CaptchaTask;
 ///     .language(Language::Portuguese)
 ///     .build();
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 5, solution = super::solution::AudioCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "AudioTask")]
 pub struct AudioCaptcha<'a> {
@@ -36,7 +36,7 @@ pub struct AudioCaptcha<'a> {
     pub(super) language: Language,
 }
 
-#[derive(Default, serde::Serialize, Debug)]
+#[derive(Default, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Language {
     #[serde(rename = "en")]
     #[default]
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use super::{type_state::*, BoundingBoxCaptcha};
+use crate::captcha_types::image_instructions::image_instructions_builder;
 
 pub struct BoundingBoxCaptchaBuilder<T, U, V> {
     body: T,
@@ -8,37 +9,7 @@ pub struct BoundingBoxCaptchaBuilder<T, U, V> {
     img_instructions: V,
 }
 
-impl<'a>
-    BoundingBoxCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsProvided<'a>>
-{
-    pub fn build(self) -> BoundingBoxCaptcha<'a> {
-        BoundingBoxCaptcha {
-            body: self.body.0,
-            comment: Some(self.comment.0),
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
-
-impl<'a> BoundingBoxCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsMissing> {
-    pub fn build(self) -> BoundingBoxCaptcha<'a> {
-        BoundingBoxCaptcha {
-            body: self.body.0,
-            comment: Some(self.comment.0),
-            img_instructions: None,
-        }
-    }
-}
-
-impl<'a> BoundingBoxCaptchaBuilder<BodyProvided<'a>, CommentMissing, ImgInstructionsProvided<'a>> {
-    pub fn build(self) -> BoundingBoxCaptcha<'a> {
-        BoundingBoxCaptcha {
-            body: self.body.0,
-            comment: None,
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
+image_instructions_builder!(BoundingBoxCaptchaBuilder, BoundingBoxCaptcha {});
 
 impl BoundingBoxCaptchaBuilder<BodyMissing, CommentMissing, ImgInstructionsMissing> {
     pub const fn new() -> Self {
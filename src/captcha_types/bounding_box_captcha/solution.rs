@@ -1,13 +1,22 @@
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct BoundingBox {
-    pub x_min: u16,
-    pub y_min: u16,
-    pub x_max: u16,
-    pub y_max: u16,
-}
+use crate::apply::Application;
+use crate::geometry::Rect;
+use crate::owned::IntoOwned;
+
+/// Kept for backwards compatibility, see [`Rect`]
+pub type BoundingBox = Rect;
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct BoundingBoxCaptchaSolution {
-    pub bounding_boxes: Box<[Box<[BoundingBox]>]>,
+    pub bounding_boxes: Box<[Box<[Rect]>]>,
+}
+
+impl BoundingBoxCaptchaSolution {
+    /// The boxes, as JSON, sent in the form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        Application::default().field(
+            field,
+            serde_json::to_string(&self.bounding_boxes).unwrap_or_default(),
+        )
+    }
 }
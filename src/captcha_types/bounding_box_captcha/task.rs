@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use super::type_state::{BodyMissing, CommentMissing, ImgInstructionsMissing};
-use crate::CaptchaTask;
+use crate::{owned::IntoOwned, CaptchaTask};
 
 /// Can be used to solve tasks where you need to select a specific
 /// object or draw a box around an object shown on an image.
@@ -18,7 +18,7 @@ use crate::CaptchaTask;
 ///     .comment("Draw a box around the car")
 ///     .build();
 /// ```
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "DrawAroundTask")]
 pub struct BoundingBoxCaptcha<'a> {
     /// Image encoded into Base64 format. Data-URI format
@@ -39,6 +39,18 @@ pub struct BoundingBoxCaptcha<'a> {
     pub(super) img_instructions: Option<Cow<'a, str>>,
 }
 
+impl BoundingBoxCaptcha<'_> {
+    /// Turns the task into one that owns all of its data,
+    /// so it can be stored and submitted again later
+    pub fn into_owned(self) -> BoundingBoxCaptcha<'static> {
+        BoundingBoxCaptcha {
+            body: IntoOwned::into_owned(self.body),
+            comment: IntoOwned::into_owned(self.comment),
+            img_instructions: IntoOwned::into_owned(self.img_instructions),
+        }
+    }
+}
+
 impl<'a> CaptchaTask for BoundingBoxCaptcha<'a> {
     type Solution = super::solution::BoundingBoxCaptchaSolution;
     type Builder = super::builder::BoundingBoxCaptchaBuilder<
@@ -51,3 +63,13 @@ impl<'a> CaptchaTask for BoundingBoxCaptcha<'a> {
         std::time::Duration::from_secs(5)
     }
 }
+
+#[cfg(feature = "visualize")]
+impl<'a> crate::visualize::Visualize for BoundingBoxCaptcha<'a> {
+    fn visualize(
+        &self,
+        solution: &Self::Solution,
+    ) -> Result<crate::visualize::Visualizer, crate::preprocess::PreprocessError> {
+        Ok(crate::visualize::Visualizer::from_body(&self.body)?.bounding_boxes(solution))
+    }
+}
@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct CapyCaptchaSolution<'a> {
     #[serde(rename = "captchakey")]
     pub captcha_key: Cow<'a, str>,
@@ -11,3 +15,12 @@ pub struct CapyCaptchaSolution<'a> {
 
     pub resp_key: Cow<'a, str>,
 }
+
+impl ApplySolution for CapyCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("capy_captchakey", &*self.captcha_key)
+            .field("capy_challengekey", &*self.challenge_key)
+            .field("capy_answer", &*self.answer)
+    }
+}
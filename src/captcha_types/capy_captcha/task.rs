@@ -23,14 +23,14 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "CapyTask", proxyless = "CapyTaskProxyless", crate = crate)]
-#[derive(CaptchaTask, serde::Serialize)]
+#[derive(CaptchaTask, serde::Serialize, Clone)]
 #[task(timeout = 20, solution = super::solution::CapyCaptchaSolution::<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct CapyCaptcha<'a> {
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// Capy Puzzle Captcha `captchakey`.
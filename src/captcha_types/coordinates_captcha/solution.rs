@@ -1,10 +1,27 @@
-#[derive(Debug, serde::Deserialize)]
-pub struct Coordinates {
-    pub x: u16,
-    pub y: u16,
-}
+use crate::apply::Application;
+use crate::geometry::Point;
+use crate::owned::IntoOwned;
+
+/// Kept for backwards compatibility, see [`Point`]
+pub type Coordinates = Point;
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct CoordinatesCaptchaSolution {
-    pub coordinates: Box<[Coordinates]>,
+    pub coordinates: Box<[Point]>,
+}
+
+impl CoordinatesCaptchaSolution {
+    /// The points, as `x,y` pairs separated by semicolons, sent in the
+    /// form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        let coordinates = self
+            .coordinates
+            .iter()
+            .map(|point| format!("{},{}", point.x, point.y))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        Application::default().field(field, coordinates)
+    }
 }
@@ -25,7 +25,7 @@ use captcha_oxide_derive::CaptchaTask;
 ///     .comment(Some("Click the green apple"))
 ///     .build();
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 5, solution = super::solution::CoordinatesCaptchaSolution, crate = crate)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "CoordinatesTask")]
 pub struct CoordinatesCaptcha<'a> {
@@ -42,3 +42,13 @@ pub struct CoordinatesCaptcha<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) img_instructions: Option<Cow<'a, str>>,
 }
+
+#[cfg(feature = "visualize")]
+impl<'a> crate::visualize::Visualize for CoordinatesCaptcha<'a> {
+    fn visualize(
+        &self,
+        solution: &Self::Solution,
+    ) -> Result<crate::visualize::Visualizer, crate::preprocess::PreprocessError> {
+        Ok(crate::visualize::Visualizer::from_body(&self.body)?.coordinates(solution))
+    }
+}
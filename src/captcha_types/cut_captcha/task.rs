@@ -28,7 +28,7 @@ use url::Url;
     proxyless = "AntiCyberSiAraTaskProxyless",
     crate = crate
 )]
-#[derive(CaptchaTask, serde::Serialize)]
+#[derive(CaptchaTask, serde::Serialize, Clone)]
 #[task(timeout = 20, solution = super::solution::CutCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct CutCaptcha<'a> {
@@ -36,7 +36,7 @@ pub struct CutCaptcha<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// The value of the `CUTCAPTCHA_MISERY_KEY` variable defined on the page.
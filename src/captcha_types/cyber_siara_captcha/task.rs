@@ -29,7 +29,7 @@ use crate::CaptchaTask;
     proxyless = "AntiCyberSiAraTaskProxyless",
     crate = crate
 )]
-#[derive(CaptchaTask, serde::Serialize)]
+#[derive(CaptchaTask, serde::Serialize, Clone)]
 #[task(timeout = 20, solution = super::solution::CyberSiARACaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct CyberSiARACaptcha<'a> {
@@ -37,7 +37,7 @@ pub struct CyberSiARACaptcha<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// The value of the `MasterUrlId` parameter obtained from the request
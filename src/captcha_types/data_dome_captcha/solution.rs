@@ -1,6 +1,25 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct DataDomeCaptchaSolution<'a> {
     pub cookie: Cow<'a, str>,
 }
+
+impl ApplySolution for DataDomeCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        // The cookie is returned with its attributes, e.g.: `datadome=...; Max-Age=31536000; Path=/`
+        match self
+            .cookie
+            .split(';')
+            .next()
+            .and_then(|x| x.split_once('='))
+        {
+            Some((name, value)) => Application::default().cookie(name.trim(), value.trim()),
+            None => Application::default(),
+        }
+    }
+}
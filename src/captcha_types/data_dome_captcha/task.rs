@@ -18,7 +18,8 @@ use crate::proxy::Proxy;
 /// You need to monitor the quality of the proxy used. If your proxy
 /// is blocked by DataDome you will receive the following solving errors:
 /// * [crate::Error::TwoCaptchaError(crate::solver::error::SolveError::ProxyConnectionFailed)]
-/// * [crate::Error::TwoCaptchaError(crate::solver::error::SolveError::UnsolvableCaptcha)] \
+/// * [crate::Error::TwoCaptchaError(crate::solver::error::SolveError::UnsolvableCaptcha)]
+///
 /// In which case you need to change the proxy server used.
 ///
 /// # Example
@@ -45,7 +46,7 @@ use crate::proxy::Proxy;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 20, solution = super::solution::DataDomeCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "DataDomeSliderTask")]
 pub struct DataDomeCaptcha<'a> {
@@ -53,12 +54,12 @@ pub struct DataDomeCaptcha<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// The value of the `src` parameter for the `iframe` element containing
     /// the captcha on the page.
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) captcha_url: Url,
 
     /// User-Agent your browser will be used to load the captcha.
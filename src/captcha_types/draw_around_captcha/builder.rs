@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use super::{type_state::*, DrawAroundCaptcha};
+use crate::captcha_types::image_instructions::image_instructions_builder;
 
 pub struct DrawAroundCaptchaBuilder<T, U, V> {
     body: T,
@@ -8,37 +9,7 @@ pub struct DrawAroundCaptchaBuilder<T, U, V> {
     img_instructions: V,
 }
 
-impl<'a>
-    DrawAroundCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsProvided<'a>>
-{
-    pub fn build(self) -> DrawAroundCaptcha<'a> {
-        DrawAroundCaptcha {
-            body: self.body.0,
-            comment: Some(self.comment.0),
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
-
-impl<'a> DrawAroundCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsMissing> {
-    pub fn build(self) -> DrawAroundCaptcha<'a> {
-        DrawAroundCaptcha {
-            body: self.body.0,
-            comment: Some(self.comment.0),
-            img_instructions: None,
-        }
-    }
-}
-
-impl<'a> DrawAroundCaptchaBuilder<BodyProvided<'a>, CommentMissing, ImgInstructionsProvided<'a>> {
-    pub fn build(self) -> DrawAroundCaptcha<'a> {
-        DrawAroundCaptcha {
-            body: self.body.0,
-            comment: None,
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
+image_instructions_builder!(DrawAroundCaptchaBuilder, DrawAroundCaptcha {});
 
 impl DrawAroundCaptchaBuilder<BodyMissing, CommentMissing, ImgInstructionsMissing> {
     pub const fn new() -> Self {
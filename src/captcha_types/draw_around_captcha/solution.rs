@@ -1,10 +1,22 @@
-#[derive(Debug, serde::Deserialize)]
-pub struct Coordinates {
-    pub x: u16,
-    pub y: u16,
-}
+use crate::apply::Application;
+use crate::geometry::{Point, Polygon};
+use crate::owned::IntoOwned;
+
+/// Kept for backwards compatibility, see [`Point`]
+pub type Coordinates = Point;
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct DrawAroundCaptchaSolution {
-    pub canvas: Box<[Box<[Coordinates]>]>,
+    pub canvas: Box<[Polygon]>,
+}
+
+impl DrawAroundCaptchaSolution {
+    /// The polygons, as JSON, sent in the form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        Application::default().field(
+            field,
+            serde_json::to_string(&self.canvas).unwrap_or_default(),
+        )
+    }
 }
@@ -1,2 +1,2 @@
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 pub enum Empty {}
@@ -1,6 +1,16 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct FriendlyCaptchaSolution<'a> {
     pub token: Cow<'a, str>,
 }
+
+impl ApplySolution for FriendlyCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default().field("frc-captcha-solution", &*self.token)
+    }
+}
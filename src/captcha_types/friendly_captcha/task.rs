@@ -20,7 +20,7 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "FriendlyCaptchaTask", proxyless = "FriendlyCaptchaTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 20, solution = super::solution::FriendlyCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct FriendlyCaptcha<'a> {
@@ -28,7 +28,7 @@ pub struct FriendlyCaptcha<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     pub(super) website_key: Cow<'a, str>,
@@ -1,9 +1,22 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct GeeTestV3Solution<'a> {
     pub challenge: Cow<'a, str>,
     pub validate: Cow<'a, str>,
     pub seccode: Cow<'a, str>,
 }
+
+impl ApplySolution for GeeTestV3Solution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("geetest_challenge", &*self.challenge)
+            .field("geetest_validate", &*self.validate)
+            .field("geetest_seccode", &*self.seccode)
+    }
+}
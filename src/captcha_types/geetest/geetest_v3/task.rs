@@ -25,7 +25,7 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "GeeTestTask", proxyless = "GeeTestTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 20, solution = super::solution::GeeTestV3Solution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct GeeTestV3<'a> {
@@ -33,7 +33,7 @@ pub struct GeeTestV3<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// GeeTest `gt` value.
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use super::{type_state::*, GeeTestV4, InitParameters};
-use crate::{prelude::*, proxy::Proxy};
+use crate::{captcha_types::ToBuilder, prelude::*, proxy::Proxy, session::SessionProfile};
 
 pub struct GeeTestV4Builder<'a, T, U, V, W, X>
 where
@@ -32,7 +32,7 @@ where
     pub fn build(self) -> Result<GeeTestV4<'a, T>> {
         Ok(GeeTestV4 {
             task_type: self.proxy.into(),
-            website_url: url::Url::parse(self.website_url.0)?,
+            website_url: url::Url::parse(&self.website_url.0)?,
             gt: self.gt.0,
             challenge: self.challenge.0,
             geetest_api_server_subdomain: self.geetest_api_server_subdomain,
@@ -46,6 +46,55 @@ where
     }
 }
 
+impl<'a, T> GeeTestV4<'a, T>
+where
+    T: serde::Serialize + Clone,
+{
+    /// A builder with every field of the task already provided,
+    /// so it can be submitted again with some of them changed
+    pub fn to_builder(
+        &self,
+    ) -> GeeTestV4Builder<
+        'a,
+        UrlProvided<'a>,
+        GtProvided<'a>,
+        ChallengeProvided<'a>,
+        CaptchaIdProvided<'a>,
+        T,
+    > {
+        let task = self.clone();
+
+        GeeTestV4Builder {
+            website_url: UrlProvided(String::from(task.website_url).into()),
+            gt: GtProvided(task.gt),
+            challenge: ChallengeProvided(task.challenge),
+            captcha_id: CaptchaIdProvided(task.init_parameters.captcha_id),
+            geetest_api_server_subdomain: task.geetest_api_server_subdomain,
+            user_agent: task.user_agent,
+            init_parameters_data: task.init_parameters.data,
+            proxy: task.task_type.into(),
+        }
+    }
+}
+
+impl<'a, T> ToBuilder for GeeTestV4<'a, T>
+where
+    T: serde::Serialize + Clone,
+{
+    type Builder = GeeTestV4Builder<
+        'a,
+        UrlProvided<'a>,
+        GtProvided<'a>,
+        ChallengeProvided<'a>,
+        CaptchaIdProvided<'a>,
+        T,
+    >;
+
+    fn to_builder(&self) -> Self::Builder {
+        Self::to_builder(self)
+    }
+}
+
 impl<'a, T> GeeTestV4Builder<'a, UrlMissing, GtMissing, ChallengeMissing, CaptchaIdMissing, T>
 where
     T: serde::Serialize,
@@ -81,9 +130,12 @@ where
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
-    pub fn website_url(self, website_url: &str) -> GeeTestV4Builder<'a, UrlProvided, U, V, W, X> {
+    pub fn website_url(
+        self,
+        website_url: impl Into<Cow<'a, str>>,
+    ) -> GeeTestV4Builder<'a, UrlProvided<'a>, U, V, W, X> {
         GeeTestV4Builder {
-            website_url: UrlProvided(website_url),
+            website_url: UrlProvided(website_url.into()),
             gt: self.gt,
             challenge: self.challenge,
             captcha_id: self.captcha_id,
@@ -175,4 +227,15 @@ where
         self.proxy = proxy;
         self
     }
+
+    /// Fills the user agent and proxy of the task with those of the session
+    pub fn session(mut self, session: &SessionProfile) -> Self {
+        self.user_agent = Some(session.user_agent.clone().into());
+
+        if session.proxy.is_some() {
+            self.proxy = session.proxy.clone();
+        }
+
+        self
+    }
 }
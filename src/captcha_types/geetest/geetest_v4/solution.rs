@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct GeeTestV4Solution<'a> {
     pub captcha_id: Cow<'a, str>,
@@ -9,3 +13,14 @@ pub struct GeeTestV4Solution<'a> {
     pub gen_time: Cow<'a, str>,
     pub captcha_output: Cow<'a, str>,
 }
+
+impl ApplySolution for GeeTestV4Solution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("captcha_id", &*self.captcha_id)
+            .field("lot_number", &*self.lot_number)
+            .field("pass_token", &*self.pass_token)
+            .field("gen_time", &*self.gen_time)
+            .field("captcha_output", &*self.captcha_output)
+    }
+}
@@ -5,6 +5,7 @@ use url::Url;
 
 use crate::{
     captcha_types::{empty_data::Empty, geetest::geetest_v4::type_state::*},
+    owned::IntoOwned,
     CaptchaTask,
 };
 
@@ -36,7 +37,7 @@ use super::{builder::GeeTestV4Builder, solution::GeeTestV4Solution};
 /// create a serializable unit struct if you don't plan to use the
 /// [`GeetestV4::init_params`] field
 #[proxy_task(with_proxy = "GeeTestTask", proxyless = "GeeTestTaskProxyless")]
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GeeTestV4<'a, T = Empty>
 where
@@ -73,7 +74,8 @@ where
     pub(super) version: u8,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct InitParameters<'a, T> {
     pub(super) captcha_id: Cow<'a, str>,
 
@@ -81,6 +83,26 @@ pub struct InitParameters<'a, T> {
     pub(super) data: Option<T>,
 }
 
+impl<T> GeeTestV4<'_, T>
+where
+    T: serde::Serialize,
+{
+    /// Turns the task into one that owns all of its data,
+    /// so it can be stored and submitted again later
+    pub fn into_owned(self) -> GeeTestV4<'static, T> {
+        GeeTestV4 {
+            task_type: IntoOwned::into_owned(self.task_type),
+            website_url: self.website_url,
+            gt: IntoOwned::into_owned(self.gt),
+            challenge: IntoOwned::into_owned(self.challenge),
+            geetest_api_server_subdomain: IntoOwned::into_owned(self.geetest_api_server_subdomain),
+            user_agent: IntoOwned::into_owned(self.user_agent),
+            init_parameters: IntoOwned::into_owned(self.init_parameters),
+            version: self.version,
+        }
+    }
+}
+
 impl<'a, T> CaptchaTask for GeeTestV4<'a, T>
 where
     T: serde::Serialize,
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 pub struct UrlMissing;
-pub struct UrlProvided<'a>(pub &'a str);
+pub struct UrlProvided<'a>(pub Cow<'a, str>);
 
 pub struct GtMissing;
 pub struct GtProvided<'a>(pub Cow<'a, str>);
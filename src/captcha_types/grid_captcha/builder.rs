@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use super::{type_state::*, GridCaptcha};
+use crate::captcha_types::image_instructions::image_instructions_builder;
 
 pub struct GridCaptchaBuilder<T, U, V> {
     body: T,
@@ -10,41 +11,7 @@ pub struct GridCaptchaBuilder<T, U, V> {
     img_instructions: V,
 }
 
-impl<'a> GridCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsProvided<'a>> {
-    pub fn build(self) -> GridCaptcha<'a> {
-        GridCaptcha {
-            body: self.body.0,
-            rows: self.rows,
-            columns: self.columns,
-            comment: Some(self.comment.0),
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
-
-impl<'a> GridCaptchaBuilder<BodyProvided<'a>, CommentProvided<'a>, ImgInstructionsMissing> {
-    pub fn build(self) -> GridCaptcha<'a> {
-        GridCaptcha {
-            body: self.body.0,
-            rows: self.rows,
-            columns: self.columns,
-            comment: Some(self.comment.0),
-            img_instructions: None,
-        }
-    }
-}
-
-impl<'a> GridCaptchaBuilder<BodyProvided<'a>, CommentMissing, ImgInstructionsProvided<'a>> {
-    pub fn build(self) -> GridCaptcha<'a> {
-        GridCaptcha {
-            body: self.body.0,
-            rows: self.rows,
-            columns: self.columns,
-            comment: None,
-            img_instructions: Some(self.img_instructions.0),
-        }
-    }
-}
+image_instructions_builder!(GridCaptchaBuilder, GridCaptcha { rows, columns });
 
 impl GridCaptchaBuilder<BodyMissing, CommentMissing, ImgInstructionsMissing> {
     pub const fn new() -> Self {
@@ -79,13 +46,13 @@ impl<'a, T, U, V> GridCaptchaBuilder<T, U, V> {
 
     /// Number of grid rows
     pub fn rows(mut self, rows: Option<u8>) -> Self {
-        self.rows = rows.map(Into::into);
+        self.rows = rows;
         self
     }
 
     /// Number of grid columns
     pub fn columns(mut self, columns: Option<u8>) -> Self {
-        self.columns = columns.map(Into::into);
+        self.columns = columns;
         self
     }
 
@@ -0,0 +1,135 @@
+use image::{imageops, DynamicImage, RgbaImage};
+
+use crate::{
+    geometry::Rect,
+    preprocess::{PreprocessError, PreprocessedImage, Preprocessor},
+};
+
+use super::GridLayout;
+
+/// An image split into the tiles of a [`GridLayout`]. Can be stitched
+/// together from separate tile images and have individual tiles replaced,
+/// as it happens in reCAPTCHA's dynamic challenges
+#[derive(Debug, Clone)]
+pub struct GridImage {
+    layout: GridLayout,
+    tile_width: u32,
+    tile_height: u32,
+    canvas: RgbaImage,
+}
+
+impl GridImage {
+    /// Splits an image containing the whole grid into tiles. Pixels left
+    /// over when the size of the image isn't divisible by the number of
+    /// rows or columns are discarded
+    pub fn from_image(image: &DynamicImage, layout: GridLayout) -> Self {
+        let tile_width = image.width() / u32::from(layout.columns.max(1));
+        let tile_height = image.height() / u32::from(layout.rows.max(1));
+
+        Self {
+            layout,
+            tile_width,
+            tile_height,
+            canvas: image
+                .crop_imm(
+                    0,
+                    0,
+                    tile_width * u32::from(layout.columns),
+                    tile_height * u32::from(layout.rows),
+                )
+                .to_rgba8(),
+        }
+    }
+
+    /// Stitches separate tile images into a single grid, in the order
+    /// of their indices. All tiles are resized to the size of the first one
+    ///
+    /// # Errors
+    /// This method will error if the number of tiles doesn't match the layout
+    pub fn from_tiles(tiles: &[DynamicImage], layout: GridLayout) -> Result<Self, PreprocessError> {
+        let Some(first) = tiles.first().filter(|_| tiles.len() == layout.len()) else {
+            return Err(PreprocessError::TileCount {
+                expected: layout.len(),
+                found: tiles.len(),
+            });
+        };
+
+        let mut grid = Self {
+            layout,
+            tile_width: first.width(),
+            tile_height: first.height(),
+            canvas: RgbaImage::new(
+                first.width() * u32::from(layout.columns),
+                first.height() * u32::from(layout.rows),
+            ),
+        };
+
+        for (index, tile) in (1..).zip(tiles) {
+            grid.replace_tile(index, tile)?;
+        }
+
+        Ok(grid)
+    }
+
+    pub const fn layout(&self) -> GridLayout {
+        self.layout
+    }
+
+    /// The area covered by the tile with the given one-based index
+    pub fn tile_rect(&self, index: u8) -> Option<Rect> {
+        self.layout.tile(
+            index,
+            f64::from(self.canvas.width()),
+            f64::from(self.canvas.height()),
+        )
+    }
+
+    /// Replaces the tile with the given one-based index, resizing
+    /// the new tile if needed
+    ///
+    /// # Errors
+    /// This method will error if the index is out of the grid
+    pub fn replace_tile(&mut self, index: u8, tile: &DynamicImage) -> Result<(), PreprocessError> {
+        let (row, column) = self
+            .layout
+            .position(index)
+            .ok_or(PreprocessError::InvalidTile(index))?;
+
+        let tile = if tile.width() == self.tile_width && tile.height() == self.tile_height {
+            tile.to_rgba8()
+        } else {
+            tile.resize_exact(
+                self.tile_width,
+                self.tile_height,
+                imageops::FilterType::Lanczos3,
+            )
+            .to_rgba8()
+        };
+
+        imageops::replace(
+            &mut self.canvas,
+            &tile,
+            i64::from(u32::from(column) * self.tile_width),
+            i64::from(u32::from(row) * self.tile_height),
+        );
+
+        Ok(())
+    }
+
+    /// The whole grid as a single image
+    pub fn to_image(&self) -> DynamicImage {
+        DynamicImage::ImageRgba8(self.canvas.clone())
+    }
+
+    /// Encodes the grid so it can be used as the `body` of a
+    /// [`super::GridCaptcha`]
+    ///
+    /// # Errors
+    /// This method will error if the image cannot be compressed enough
+    pub fn encode(
+        &self,
+        preprocessor: &Preprocessor,
+    ) -> Result<PreprocessedImage, PreprocessError> {
+        preprocessor.process_image(self.to_image())
+    }
+}
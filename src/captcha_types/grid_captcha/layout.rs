@@ -0,0 +1,96 @@
+use crate::geometry::{Point, Rect};
+
+use super::GridCaptchaSolution;
+
+/// The number of rows and columns of a grid captcha. The tiles are numbered
+/// from `1`, left to right and top to bottom, which is how the indices in
+/// [`GridCaptchaSolution::click`] are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridLayout {
+    pub rows: u8,
+    pub columns: u8,
+}
+
+impl GridLayout {
+    /// The layout used by most reCAPTCHA and hCaptcha image challenges
+    pub const THREE_BY_THREE: Self = Self::new(3, 3);
+
+    /// The layout used by reCAPTCHA challenges that split a single image into tiles
+    pub const FOUR_BY_FOUR: Self = Self::new(4, 4);
+
+    pub const fn new(rows: u8, columns: u8) -> Self {
+        Self { rows, columns }
+    }
+
+    /// The number of tiles in the grid
+    pub const fn len(&self) -> usize {
+        self.rows as usize * self.columns as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The zero-based `(row, column)` of the tile with the given one-based index.
+    /// Returns [`None`] if the index is out of the grid
+    pub const fn position(&self, index: u8) -> Option<(u8, u8)> {
+        if index == 0 || index as usize > self.len() {
+            return None;
+        }
+
+        let index = index - 1;
+        Some((index / self.columns, index % self.columns))
+    }
+
+    /// The one-based index of the tile at the zero-based `row` and `column`.
+    /// Returns [`None`] if the position is out of the grid, or if its index
+    /// doesn't fit in a [`u8`], as with the last tiles of a 16 by 16 grid
+    pub const fn index(&self, row: u8, column: u8) -> Option<u8> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+
+        match row.checked_mul(self.columns) {
+            Some(index) => match index.checked_add(column) {
+                Some(index) => index.checked_add(1),
+                None => None,
+            },
+            None => None,
+        }
+    }
+
+    /// The area covered by the tile with the given one-based index in an image
+    /// of `width` by `height` pixels. Returns [`None`] if the index is out of the grid
+    pub fn tile(&self, index: u8, width: f64, height: f64) -> Option<Rect> {
+        let (row, column) = self.position(index)?;
+
+        let tile_width = width / f64::from(self.columns);
+        let tile_height = height / f64::from(self.rows);
+
+        Some(Rect::from_size(
+            Point::new(f64::from(column) * tile_width, f64::from(row) * tile_height),
+            tile_width,
+            tile_height,
+        ))
+    }
+}
+
+impl GridCaptchaSolution {
+    /// The zero-based `(row, column)` of each clicked tile.
+    /// Indices outside of the grid are skipped
+    pub fn positions(&self, layout: GridLayout) -> Box<[(u8, u8)]> {
+        self.click
+            .iter()
+            .filter_map(|index| layout.position(*index))
+            .collect()
+    }
+
+    /// The area covered by each clicked tile in an image of `width` by
+    /// `height` pixels. Indices outside of the grid are skipped
+    pub fn tiles(&self, layout: GridLayout, width: f64, height: f64) -> Box<[Rect]> {
+        self.click
+            .iter()
+            .filter_map(|index| layout.tile(*index, width, height))
+            .collect()
+    }
+}
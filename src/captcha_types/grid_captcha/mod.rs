@@ -1,7 +1,137 @@
 mod builder;
+#[cfg(feature = "image")]
+mod grid_image;
+mod layout;
+#[cfg(feature = "image")]
+mod rounds;
 mod solution;
 mod task;
 mod type_state;
 
+#[cfg(feature = "image")]
+pub use grid_image::*;
+pub use layout::*;
+#[cfg(feature = "image")]
+pub use rounds::*;
 pub use solution::*;
 pub use task::*;
+
+#[cfg(test)]
+mod test {
+    use super::{GridCaptcha, GridCaptchaSolution, GridLayout};
+    use crate::{
+        geometry::{Point, Rect},
+        CaptchaTask,
+    };
+
+    #[test]
+    fn turns_back_into_builders() {
+        let task = GridCaptcha::builder()
+            .body("IMAGE")
+            .comment("Select all cars")
+            .rows(Some(4))
+            .build();
+
+        let json = |task: &GridCaptcha| serde_json::to_value(task).unwrap();
+
+        assert_eq!(json(&task.to_builder().build()), json(&task));
+
+        let resubmitted = task.to_builder().comment("Select all buses").build();
+        assert_eq!(json(&resubmitted)["comment"], "Select all buses");
+        assert_eq!(json(&resubmitted)["rows"], 4);
+    }
+
+    #[test]
+    fn maps_clicks_to_tiles() {
+        let solution = GridCaptchaSolution {
+            click: Box::new([1, 6, 16, 17]),
+        };
+
+        assert_eq!(
+            &*solution.positions(GridLayout::FOUR_BY_FOUR),
+            &[(0, 0), (1, 1), (3, 3)]
+        );
+        assert_eq!(
+            &*solution.tiles(GridLayout::THREE_BY_THREE, 300.0, 300.0),
+            &[
+                Rect::from_size(Point::ORIGIN, 100.0, 100.0),
+                Rect::from_size(Point::new(200.0, 100.0), 100.0, 100.0),
+            ]
+        );
+        assert_eq!(GridLayout::new(2, 4).index(1, 3), Some(8));
+        assert_eq!(GridLayout::new(16, 16).index(15, 14), Some(255));
+        assert_eq!(GridLayout::new(16, 16).index(15, 15), None);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn stitches_and_replaces_tiles() -> Result<(), crate::preprocess::PreprocessError> {
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use super::GridImage;
+
+        let tile = |shade: u8| {
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(10, 10, Rgba([shade, 0, 0, 255])))
+        };
+
+        let tiles = (0..9).map(|x| tile(x * 20)).collect::<Vec<_>>();
+        let mut grid = GridImage::from_tiles(&tiles, GridLayout::THREE_BY_THREE)?;
+        grid.replace_tile(5, &tile(255))?;
+
+        let image = grid.to_image().to_rgba8();
+        assert_eq!((image.width(), image.height()), (30, 30));
+        assert_eq!(image.get_pixel(25, 5)[0], 40);
+        assert_eq!(image.get_pixel(15, 15)[0], 255);
+        assert!(GridImage::from_tiles(&tiles[1..], GridLayout::THREE_BY_THREE).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[tokio::test(start_paused = true)]
+    async fn solves_dynamic_challenges() -> Result<(), crate::Error> {
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use super::{DynamicGridChallenge, GridImage};
+        use crate::test_server::{self, Response};
+
+        // The worker clicks two tiles in the first round and none in the second
+        let (url, requests) =
+            test_server::serve_logged(|request, log| match request.path.as_str() {
+                "/createTask" => Response::task_created(),
+                _ => match log.count("/createTask") {
+                    1 => Response::task_ready(r#"{"click":[2,5]}"#),
+                    _ => Response::task_ready(r#"{"click":[]}"#),
+                },
+            });
+
+        let solver = test_server::solver(url);
+
+        let tile = |shade: u8| {
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(10, 10, Rgba([shade, 0, 0, 255])))
+        };
+        let tiles = (0..9).map(|_| tile(0)).collect::<Vec<_>>();
+        let grid = GridImage::from_tiles(&tiles, GridLayout::THREE_BY_THREE)?;
+
+        let mut clicks = Vec::new();
+        let rounds = DynamicGridChallenge::new("Select all images with a bus")
+            .solve(&solver, grid, |click| {
+                clicks.push(click.clone());
+                let replacements = click.iter().map(|x| (*x, tile(255))).collect();
+                async move { Ok::<_, crate::Error>(replacements) }
+            })
+            .await?;
+
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(&*rounds[0].click, &[2, 5]);
+        assert!(rounds[1].click.is_empty());
+        assert_eq!(clicks, [Box::from([2, 5])]);
+
+        let bodies = requests.bodies("/createTask");
+        assert_eq!(bodies.len(), 2);
+        assert_ne!(bodies[0], bodies[1]);
+        assert!(bodies[1].contains(r#""rows":3"#));
+
+        Ok(())
+    }
+}
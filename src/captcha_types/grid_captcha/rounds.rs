@@ -0,0 +1,149 @@
+use std::{borrow::Cow, future::Future};
+
+use image::DynamicImage;
+
+use crate::{preprocess::Preprocessor, CaptchaSolver};
+
+use super::{GridCaptcha, GridImage};
+
+/// The outcome of a single round of a [`DynamicGridChallenge`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridRound {
+    /// The one-based indices of the tiles the worker clicked
+    pub click: Box<[u8]>,
+
+    /// The task price charged from your balance
+    pub cost: String,
+}
+
+/// Drives reCAPTCHA's dynamic image challenges, in which every clicked tile
+/// is replaced by a new image that may also have to be clicked.
+///
+/// Each round submits the current grid as a [`GridCaptcha`] and hands the
+/// clicked tiles to a callback, which is expected to click them on the page
+/// and return the images that replaced them. The challenge ends when the
+/// worker clicks no tiles, when the callback returns no new tiles or when
+/// [`DynamicGridChallenge::max_rounds`] is reached.
+///
+/// # Example
+/// ```no_run
+/// use captcha_oxide::{
+///     CaptchaSolver,
+///     captcha_types::grid_captcha::{DynamicGridChallenge, GridImage, GridLayout},
+/// };
+///
+/// # async fn example(grid: image::DynamicImage) -> Result<(), captcha_oxide::Error> {
+/// let solver = CaptchaSolver::new("YOUR_API_KEY");
+/// let grid = GridImage::from_image(&grid, GridLayout::THREE_BY_THREE);
+///
+/// let rounds = DynamicGridChallenge::new("Select all images with a bus")
+///     .solve(&solver, grid, |click| async move {
+///         // Click the tiles in the browser and download their replacements
+///         Ok::<_, captcha_oxide::Error>(vec![])
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicGridChallenge<'a> {
+    comment: Cow<'a, str>,
+    img_instructions: Option<Cow<'a, str>>,
+    max_rounds: u8,
+    preprocessor: Preprocessor,
+}
+
+impl<'a> DynamicGridChallenge<'a> {
+    /// The comment will be shown to the workers in every round
+    pub fn new(comment: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            comment: comment.into(),
+            img_instructions: None,
+            max_rounds: 5,
+            preprocessor: Preprocessor::new(),
+        }
+    }
+
+    /// An optional image with instruction that will be shown to workers.
+    /// The image must be encoded into Base64 format. Max file size: 100 kB.
+    pub fn img_instructions(mut self, img_instructions: Option<impl Into<Cow<'a, str>>>) -> Self {
+        self.img_instructions = img_instructions.map(Into::into);
+        self
+    }
+
+    /// The maximum number of tasks submitted to 2captcha. Default value: `5`
+    pub fn max_rounds(mut self, max_rounds: u8) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// The [`Preprocessor`] used to encode the grid before each round
+    pub fn preprocessor(mut self, preprocessor: Preprocessor) -> Self {
+        self.preprocessor = preprocessor;
+        self
+    }
+
+    /// Solves the challenge, returning every round that was submitted
+    ///
+    /// `on_click` receives the indices clicked in each round and must return
+    /// the replacement image of each tile that changed, along with its index
+    ///
+    /// # Errors
+    /// This method will error if a grid cannot be encoded, if a task fails
+    /// or if `on_click` fails
+    pub async fn solve<F, Fut, E>(
+        &self,
+        solver: &CaptchaSolver,
+        mut grid: GridImage,
+        mut on_click: F,
+    ) -> Result<Vec<GridRound>, E>
+    where
+        F: FnMut(Box<[u8]>) -> Fut,
+        Fut: Future<Output = Result<Vec<(u8, DynamicImage)>, E>>,
+        E: From<crate::Error>,
+    {
+        let layout = grid.layout();
+        let mut rounds = Vec::new();
+
+        while rounds.len() < usize::from(self.max_rounds) {
+            let body = grid
+                .encode(&self.preprocessor)
+                .map_err(crate::Error::from)?
+                .body;
+
+            let task = GridCaptcha {
+                body: body.into(),
+                rows: Some(layout.rows),
+                columns: Some(layout.columns),
+                comment: Some(self.comment.clone()),
+                img_instructions: self.img_instructions.clone(),
+            };
+
+            let solution = solver
+                .solve(task)
+                .await?
+                .ok_or(crate::Error::CallbackUrlSet)?;
+
+            rounds.push(GridRound {
+                click: solution.solution.click.clone(),
+                cost: solution.cost.into_owned(),
+            });
+
+            if solution.solution.click.is_empty() {
+                break;
+            }
+
+            let replacements = on_click(solution.solution.click).await?;
+            if replacements.is_empty() {
+                break;
+            }
+
+            for (index, tile) in replacements {
+                grid.replace_tile(index, &tile)
+                    .map_err(crate::Error::from)?;
+            }
+        }
+
+        Ok(rounds)
+    }
+}
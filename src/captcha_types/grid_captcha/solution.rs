@@ -1,4 +1,22 @@
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::Application;
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct GridCaptchaSolution {
     pub click: Box<[u8]>,
 }
+
+impl GridCaptchaSolution {
+    /// The clicked tiles, separated by commas, sent in the form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        let click = self
+            .click
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Application::default().field(field, click)
+    }
+}
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::CaptchaTask;
+use crate::{owned::IntoOwned, CaptchaTask};
 
 use super::type_state::*;
 
@@ -21,7 +21,7 @@ use super::type_state::*;
 ///     .columns(Some(3))
 ///     .build();
 /// ```
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "GridTask")]
 pub struct GridCaptcha<'a> {
     /// Image encoded into Base64 format. Data-URI format
@@ -42,6 +42,20 @@ pub struct GridCaptcha<'a> {
     pub(super) img_instructions: Option<Cow<'a, str>>,
 }
 
+impl GridCaptcha<'_> {
+    /// Turns the task into one that owns all of its data,
+    /// so it can be stored and submitted again later
+    pub fn into_owned(self) -> GridCaptcha<'static> {
+        GridCaptcha {
+            body: IntoOwned::into_owned(self.body),
+            rows: self.rows,
+            columns: self.columns,
+            comment: IntoOwned::into_owned(self.comment),
+            img_instructions: IntoOwned::into_owned(self.img_instructions),
+        }
+    }
+}
+
 impl<'a> CaptchaTask for GridCaptcha<'a> {
     type Solution = super::solution::GridCaptchaSolution;
     type Builder =
@@ -51,3 +65,16 @@ impl<'a> CaptchaTask for GridCaptcha<'a> {
         std::time::Duration::from_secs(5)
     }
 }
+
+#[cfg(feature = "visualize")]
+impl<'a> crate::visualize::Visualize for GridCaptcha<'a> {
+    /// Grids without [`GridCaptcha::rows`] or [`GridCaptcha::columns`]
+    /// are drawn as 3x3, as that is how they are shown to the workers
+    fn visualize(
+        &self,
+        solution: &Self::Solution,
+    ) -> Result<crate::visualize::Visualizer, crate::preprocess::PreprocessError> {
+        let layout = super::GridLayout::new(self.rows.unwrap_or(3), self.columns.unwrap_or(3));
+        Ok(crate::visualize::Visualizer::from_body(&self.body)?.grid(solution, layout))
+    }
+}
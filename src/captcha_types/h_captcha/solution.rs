@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct HCaptchaSolution<'a> {
     pub token: Cow<'a, str>,
@@ -8,3 +12,12 @@ pub struct HCaptchaSolution<'a> {
     pub user_agent: Cow<'a, str>,
     pub g_recaptcha_response: Cow<'a, str>,
 }
+
+impl ApplySolution for HCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("h-captcha-response", &*self.token)
+            .field("g-recaptcha-response", &*self.token)
+            .user_agent(&*self.user_agent)
+    }
+}
@@ -25,8 +25,8 @@ use crate::{captcha_types::empty_data::Empty, CaptchaTask};
 /// create a serializable unit struct if you don't plan to use the
 /// [`HCaptcha::enterprise_payload`] field
 #[proxy_task(with_proxy = "HCaptchaTask", proxyless = "HCaptchaTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
-#[task(timeout = 20, solution = super::solution::HCaptchaSolution<'a>, crate = crate)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
+#[task(timeout = 20, validity = 120, solution = super::solution::HCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct HCaptcha<'a, T = Empty>
 where
@@ -36,7 +36,7 @@ where
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     pub(super) website_key: Cow<'a, str>,
@@ -46,4 +46,9 @@ where
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) enterprise_payload: Option<T>,
+
+    /// User-Agent your browser will be used to load the captcha.
+    /// Use only modern browsers' User-Agents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) user_agent: Option<Cow<'a, str>>,
 }
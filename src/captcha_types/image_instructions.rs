@@ -0,0 +1,85 @@
+/// Implements `build`, `to_builder` and `ToBuilder` for the builder of an
+/// image task that requires a comment, image instructions or both. The
+/// builder must have a `body`, `comment` and `img_instructions` field, plus
+/// the given fields which are copied as they are
+macro_rules! image_instructions_builder {
+    ($builder:ident, $task:ident { $($field:ident),* }) => {
+        image_instructions_builder!(@build $builder, $task { $($field),* }, provided, provided);
+        image_instructions_builder!(@build $builder, $task { $($field),* }, provided, missing);
+        image_instructions_builder!(@build $builder, $task { $($field),* }, missing, provided);
+        image_instructions_builder!(@build $builder, $task { $($field),* }, optional, optional);
+        image_instructions_builder!(@build $builder, $task { $($field),* }, provided, optional);
+        image_instructions_builder!(@build $builder, $task { $($field),* }, optional, provided);
+
+        impl<'a> $task<'a> {
+            /// A builder with every field of the task already provided,
+            /// so it can be submitted again with some of them changed.
+            /// Since either the comment or the image instructions may be
+            /// missing, their type states are wrapped in [`Option`]s
+            pub fn to_builder(
+                &self,
+            ) -> $builder<
+                BodyProvided<'a>,
+                Option<CommentProvided<'a>>,
+                Option<ImgInstructionsProvided<'a>>,
+            > {
+                let task = self.clone();
+
+                $builder {
+                    body: BodyProvided(task.body),
+                    $($field: task.$field,)*
+                    comment: task.comment.map(CommentProvided),
+                    img_instructions: task.img_instructions.map(ImgInstructionsProvided),
+                }
+            }
+        }
+
+        impl<'a> $crate::captcha_types::ToBuilder for $task<'a> {
+            type Builder = $builder<
+                BodyProvided<'a>,
+                Option<CommentProvided<'a>>,
+                Option<ImgInstructionsProvided<'a>>,
+            >;
+
+            fn to_builder(&self) -> Self::Builder {
+                Self::to_builder(self)
+            }
+        }
+    };
+
+    (@build $builder:ident, $task:ident { $($field:ident),* }, $comment:ident, $img_instructions:ident) => {
+        impl<'a>
+            $builder<
+                BodyProvided<'a>,
+                image_instructions_builder!(@state $comment, CommentProvided<'a>, CommentMissing),
+                image_instructions_builder!(
+                    @state $img_instructions,
+                    ImgInstructionsProvided<'a>,
+                    ImgInstructionsMissing
+                ),
+            >
+        {
+            pub fn build(self) -> $task<'a> {
+                $task {
+                    body: self.body.0,
+                    $($field: self.$field,)*
+                    comment: image_instructions_builder!(@value $comment, self.comment),
+                    img_instructions: image_instructions_builder!(
+                        @value $img_instructions,
+                        self.img_instructions
+                    ),
+                }
+            }
+        }
+    };
+
+    (@state provided, $provided:ty, $missing:ty) => { $provided };
+    (@state missing, $provided:ty, $missing:ty) => { $missing };
+    (@state optional, $provided:ty, $missing:ty) => { Option<$provided> };
+
+    (@value provided, $state:expr) => { Some($state.0) };
+    (@value missing, $state:expr) => { None };
+    (@value optional, $state:expr) => { $state.map(|x| x.0) };
+}
+
+pub(super) use image_instructions_builder;
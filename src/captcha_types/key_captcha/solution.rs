@@ -1,6 +1,17 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::Application;
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct KeyCaptchaSolution<'a> {
     pub token: Cow<'a, str>,
 }
+
+impl KeyCaptchaSolution<'_> {
+    /// The token sent in the form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        Application::default().field(field, &*self.token)
+    }
+}
@@ -24,14 +24,14 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "KeyCaptchaTask", proxyless = "KeyCaptchaTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 20, solution = super::solution::KeyCaptchaSolution<'a>, crate = crate)]
 pub struct KeyCaptcha<'a> {
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// The value of the `s_s_c_user_id` parameter found on page
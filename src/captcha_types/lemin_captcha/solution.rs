@@ -1,8 +1,22 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::Application;
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct LeminCaptchaSolution<'a> {
     pub answer: Cow<'a, str>,
 
     pub challenge_id: Cow<'a, str>,
 }
+
+impl LeminCaptchaSolution<'_> {
+    /// The answer and challenge id sent in the form fields
+    /// named `answer` and `challenge_id`
+    pub fn application(&self, answer: &'static str, challenge_id: &'static str) -> Application<'_> {
+        Application::default()
+            .field(answer, &*self.answer)
+            .field(challenge_id, &*self.challenge_id)
+    }
+}
@@ -1,4 +1,5 @@
 mod empty_data;
+mod image_instructions;
 
 pub mod amazon_captcha;
 pub mod arkose_labs_captcha;
@@ -40,4 +41,21 @@ pub trait CaptchaTask: serde::Serialize {
     /// The amount of time that should be waited after creating a task to check
     /// if it is ready
     fn get_timeout(&self) -> std::time::Duration;
+
+    /// How long the solution can be used after the task is solved,
+    /// or [`None`] if it doesn't expire
+    fn get_validity(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+/// Tasks that can be turned back into a builder, so they can be submitted
+/// again with some of their fields changed. The tasks also have an
+/// inherent `to_builder` method
+pub trait ToBuilder {
+    /// The builder with every required field of the task provided
+    type Builder;
+
+    /// A builder with every field of the task already provided
+    fn to_builder(&self) -> Self::Builder;
 }
@@ -1,14 +1,124 @@
 mod solution;
 mod task;
+mod validation;
 
 pub use solution::*;
 pub use task::*;
+pub use validation::*;
 
 #[cfg(test)]
 mod test {
     use std::env;
 
-    use crate::{captcha_types::normal_captcha::NormalCaptcha, CaptchaSolver, CaptchaTask, Error};
+    use regex::Regex;
+
+    use super::{AnswerType, AnswerViolation};
+    use crate::{
+        captcha_types::normal_captcha::NormalCaptcha,
+        test_server::{self, Response},
+        CaptchaSolver, CaptchaTask, Error,
+    };
+
+    #[test]
+    fn checks_answers() {
+        let captcha = NormalCaptcha::builder()
+            .body("IMAGE")
+            .numeric(Some(AnswerType::AlphaNumerical))
+            .phrase(Some(false))
+            .min_length(Some(4_u32))
+            .max_length(Some(6_u32))
+            .build();
+
+        let rules = captcha.answer_rules();
+        assert_eq!(rules.check("w68hp"), Ok(()));
+        assert_eq!(
+            rules.check("w6h"),
+            Err(AnswerViolation::TooShort {
+                length: 3,
+                min_length: 4
+            })
+        );
+        assert_eq!(rules.check("w6 8hp"), Ok(()));
+        assert_eq!(
+            rules.check("wbhpq"),
+            Err(AnswerViolation::NotAlphaNumerical)
+        );
+
+        let rules = rules.pattern(Regex::new("^[a-z0-9]+$").unwrap());
+        assert_eq!(
+            rules.check("W68HP"),
+            Err(AnswerViolation::PatternMismatch("^[a-z0-9]+$".into()))
+        );
+
+        let captcha = NormalCaptcha::builder()
+            .body("IMAGE")
+            .math(Some(true))
+            .build();
+
+        assert_eq!(captcha.answer_rules().check("-12"), Ok(()));
+        assert_eq!(
+            captcha.answer_rules().check("twelve"),
+            Err(AnswerViolation::NotNumber)
+        );
+
+        let captcha = NormalCaptcha::builder()
+            .body("IMAGE")
+            .phrase(Some(true))
+            .min_length(Some(0_u32))
+            .max_length(Some(0_u32))
+            .build();
+
+        assert_eq!(captcha.answer_rules().check("w6 8hp"), Ok(()));
+        assert_eq!(
+            captcha.answer_rules().check("w68hp"),
+            Err(AnswerViolation::NotPhrase)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resubmits_invalid_answers() {
+        let (url, requests) =
+            test_server::serve_logged(|request, log| match request.path.as_str() {
+                "/createTask" => Response::task_created(),
+                "/getTaskResult" => match log.count("/createTask") {
+                    1 => Response::task_ready(r#"{"text":"48l7"}"#),
+                    _ => Response::task_ready(r#"{"text":"4817"}"#),
+                },
+                _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+            });
+
+        let solver = test_server::solver(url);
+
+        let captcha = NormalCaptcha::builder()
+            .body("IMAGE")
+            .numeric(Some(AnswerType::Numeric))
+            .build();
+
+        let rules = captcha.answer_rules();
+        let solution = solver
+            .solve_checked(captcha, rules.clone(), 1)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(solution.solution.text, "4817");
+        assert_eq!(requests.paths("/report"), ["/reportIncorrect"]);
+
+        let captcha = NormalCaptcha::builder()
+            .body("IMAGE")
+            .numeric(Some(AnswerType::Numeric))
+            .build();
+
+        let error = solver
+            .solve_checked(captcha, rules.pattern(Regex::new("^9").unwrap()), 1)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::InvalidAnswer(AnswerViolation::PatternMismatch(_))
+        ));
+    }
 
     #[tokio::test]
     async fn normal_captcha() -> Result<(), Error> {
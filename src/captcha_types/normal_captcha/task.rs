@@ -14,7 +14,7 @@ use crate::CaptchaTask;
 ///     .comment(Some("Enter the text you see on the image"))
 ///     .build();
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 5, solution = super::solution::NormalCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "ImageToTextTask")]
 pub struct NormalCaptcha<'a> {
@@ -56,7 +56,7 @@ pub struct NormalCaptcha<'a> {
     pub(super) img_instructions: Option<Cow<'a, str>>,
 }
 
-#[derive(serde_repr::Serialize_repr)]
+#[derive(serde_repr::Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum AnswerType {
     NoPreference = 0,
@@ -0,0 +1,144 @@
+use regex::Regex;
+
+use super::{AnswerType, NormalCaptcha};
+
+/// The constraints an answer to a [`NormalCaptcha`] has to meet, used by
+/// [`crate::CaptchaSolver::solve_checked`] to reject obviously wrong answers
+///
+/// # Example
+/// ```
+/// use captcha_oxide::{
+///     captcha_types::normal_captcha::{AnswerType, AnswerViolation, NormalCaptcha},
+///     CaptchaTask,
+/// };
+/// use regex::Regex;
+///
+/// let captcha = NormalCaptcha::builder()
+///     .body("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==")
+///     .numeric(Some(AnswerType::Numeric))
+///     .min_length(Some(4_u32))
+///     .build();
+///
+/// let rules = captcha.answer_rules().pattern(Regex::new("^[1-9]").unwrap());
+///
+/// assert_eq!(rules.check("1234"), Ok(()));
+/// assert_eq!(rules.check("12a4"), Err(AnswerViolation::NotNumeric));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnswerRules {
+    phrase: Option<bool>,
+    numeric: Option<AnswerType>,
+    math: Option<bool>,
+    min_length: Option<u32>,
+    max_length: Option<u32>,
+    patterns: Vec<Regex>,
+}
+
+/// The reason an answer was rejected by [`AnswerRules::check`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AnswerViolation {
+    #[error("The answer has {length} characters, but at least {min_length} were required")]
+    TooShort { length: usize, min_length: u32 },
+
+    #[error("The answer has {length} characters, but at most {max_length} were allowed")]
+    TooLong { length: usize, max_length: u32 },
+
+    #[error("The answer should contain several words separated by spaces")]
+    NotPhrase,
+
+    #[error("The answer should only contain numbers")]
+    NotNumeric,
+
+    #[error("The answer should only contain letters")]
+    NotAlphabetical,
+
+    #[error("The answer should only contain either letters or numbers")]
+    MixedCharacters,
+
+    #[error("The answer should contain both letters and numbers")]
+    NotAlphaNumerical,
+
+    #[error("The answer should be the result of a calculation")]
+    NotNumber,
+
+    #[error("The answer doesn't match `{0}`")]
+    PatternMismatch(String),
+}
+
+impl AnswerRules {
+    /// Also requires the answer to match `pattern`
+    pub fn pattern(mut self, pattern: Regex) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    pub fn check(&self, answer: &str) -> Result<(), AnswerViolation> {
+        let length = answer.chars().count();
+
+        // 2captcha treats a length of 0 as no limit
+        if let Some(min_length) = self.min_length.filter(|x| *x > 0 && length < *x as usize) {
+            return Err(AnswerViolation::TooShort { length, min_length });
+        }
+
+        if let Some(max_length) = self.max_length.filter(|x| *x > 0 && length > *x as usize) {
+            return Err(AnswerViolation::TooLong { length, max_length });
+        }
+
+        // `phrase: false` means the answer may or may not have spaces
+        if self.phrase == Some(true) && !answer.trim().contains(char::is_whitespace) {
+            return Err(AnswerViolation::NotPhrase);
+        }
+
+        if self.math == Some(true) && answer.trim().parse::<f64>().is_err() {
+            return Err(AnswerViolation::NotNumber);
+        }
+
+        let characters = answer.chars().filter(|x| !x.is_whitespace());
+        let has_digits = characters.clone().any(|x| x.is_numeric());
+        let has_letters = characters.clone().any(|x| x.is_alphabetic());
+
+        match self.numeric {
+            Some(AnswerType::Numeric)
+                if has_letters || !characters.clone().all(char::is_numeric) =>
+            {
+                return Err(AnswerViolation::NotNumeric)
+            }
+            Some(AnswerType::Alphabetical)
+                if has_digits || !characters.clone().all(char::is_alphabetic) =>
+            {
+                return Err(AnswerViolation::NotAlphabetical)
+            }
+            Some(AnswerType::AlphabeticalOrNumerical) if has_digits && has_letters => {
+                return Err(AnswerViolation::MixedCharacters)
+            }
+            Some(AnswerType::AlphaNumerical) if !(has_digits && has_letters) => {
+                return Err(AnswerViolation::NotAlphaNumerical)
+            }
+            _ => {}
+        }
+
+        match self.patterns.iter().find(|x| !x.is_match(answer)) {
+            Some(pattern) => Err(AnswerViolation::PatternMismatch(pattern.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl NormalCaptcha<'_> {
+    /// The constraints of the task the answer has to meet. Whether the
+    /// answer is case sensitive can't be checked, so `case` is ignored
+    pub fn answer_rules(&self) -> AnswerRules {
+        AnswerRules {
+            phrase: self.phrase,
+            numeric: self.numeric,
+            math: self.math,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            patterns: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_case_sensitive(&self) -> bool {
+        self.case == Some(true)
+    }
+}
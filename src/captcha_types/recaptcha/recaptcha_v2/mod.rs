@@ -7,7 +7,45 @@ mod test {
     use dotenv::dotenv;
     use std::env;
 
-    use crate::{captcha_types::recaptcha::RecaptchaV2, CaptchaSolver, CaptchaTask, Error};
+    use crate::{
+        captcha_types::recaptcha::RecaptchaV2,
+        cookie::Cookies,
+        proxy::{Address, Proxy, ProxyType},
+        CaptchaSolver, CaptchaTask, Error,
+    };
+
+    #[test]
+    fn turns_back_into_builders() -> Result<(), Error> {
+        let proxy = Proxy {
+            proxy_type: ProxyType::Http,
+            proxy_address: Address::IpAddress([1, 2, 3, 4].into()),
+            proxy_port: "8080".into(),
+            proxy_login: None,
+            proxy_password: None,
+        };
+
+        let task = RecaptchaV2::builder()
+            .website_url("https://patrickhlauke.github.io/recaptcha/")
+            .website_key("6Ld2sf4SAAAAAKSgzs0Q13IZhY02Pyo31S2jgOB5")
+            .cookies(Some(
+                [("session", "abc"), ("theme", "dark")]
+                    .into_iter()
+                    .collect::<Cookies>(),
+            ))
+            .proxy(Some(proxy))
+            .build()?;
+
+        let resubmitted = task.to_builder().user_agent(Some("Mozilla/5.0")).build()?;
+
+        let mut expected = serde_json::to_value(&task)?;
+        expected["userAgent"] = "Mozilla/5.0".into();
+
+        assert_eq!(serde_json::to_value(&resubmitted)?, expected);
+        assert_eq!(expected["cookies"], "session=abc;theme=dark");
+        assert_eq!(expected["type"], "RecaptchaV2Task");
+
+        Ok(())
+    }
 
     #[tokio::test]
     async fn recaptcha_v2() -> Result<(), Error> {
@@ -33,8 +33,8 @@ use crate::{captcha_types::empty_data::Empty, CaptchaTask};
     proxyless = "RecaptchaV2EnterpriseTaskProxyless",
     crate = crate,
 )]
-#[derive(serde::Serialize, CaptchaTask)]
-#[task(timeout = 20, solution = super::super::solution::RecaptchaSolution<'a>, crate = crate)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
+#[task(timeout = 20, validity = 120, solution = super::super::solution::RecaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct RecaptchaV2Enterprise<'a, T = Empty>
 where
@@ -44,7 +44,7 @@ where
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// reCAPTCHA sitekey. Can be found inside `data-sitekey` property of the reCAPTCHA
@@ -75,7 +75,7 @@ where
     /// May be passed in as an iterable (array, slice or [Vec]) of
     /// [`crate::cookie::Cookie`] or [`(impl ToString, impl ToString)`]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[task(builder_type = Option<crate::cookie::Cookies<'a>>, parse_with = { infallible({ path = crate::cookie::Cookies::stringify, parse_ref }) })]
+    #[task(builder_type = Option<crate::cookie::Cookies<'a>>, parse_with = { infallible({ path = crate::cookie::Cookies::stringify, parse_ref }) }, unparse_with = crate::cookie::Cookies::parse)]
     pub(super) cookies: Option<Cow<'a, str>>,
 
     /// Domain used to load the captcha: `google.com` or `recaptcha.net`.
@@ -23,8 +23,8 @@ use crate::CaptchaTask;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
-#[task(timeout = 20, solution = super::super::solution::RecaptchaSolution<'a>, crate = crate)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
+#[task(timeout = 20, validity = 120, solution = super::super::solution::RecaptchaSolution<'a>, crate = crate)]
 #[serde(
     rename_all = "camelCase",
     tag = "type",
@@ -35,7 +35,7 @@ pub struct RecaptchaV3<'a> {
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// reCAPTCHA sitekey. Can be found inside `data-sitekey` property of the reCAPTCHA
@@ -1,8 +1,18 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct RecaptchaSolution<'a> {
     pub g_recaptcha_response: Cow<'a, str>,
     pub token: Cow<'a, str>,
 }
+
+impl ApplySolution for RecaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default().field("g-recaptcha-response", &*self.g_recaptcha_response)
+    }
+}
@@ -1,4 +1,22 @@
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::Application;
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct RotateCaptchaSolution {
     pub rotate: u16,
 }
+
+impl RotateCaptchaSolution {
+    /// The distance a slider must be dragged to apply the rotation, for
+    /// sliders in which moving across the whole `track_length` rotates
+    /// the image a full turn
+    pub fn slider_offset(&self, track_length: f64) -> f64 {
+        f64::from(self.rotate % 360) / 360.0 * track_length
+    }
+
+    /// The angle sent in the form field named `field`
+    pub fn application(&self, field: &'static str) -> Application<'_> {
+        Application::default().field(field, self.rotate.to_string())
+    }
+}
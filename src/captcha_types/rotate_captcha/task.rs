@@ -18,7 +18,7 @@ use captcha_oxide_derive::CaptchaTask;
 ///     .angle(Some(60_u16))
 ///     .build();
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[task(timeout = 5, solution = super::solution::RotateCaptchaSolution, crate = crate)]
 #[serde(rename_all = "camelCase", tag = "type", rename = "RotateTask")]
 pub struct RotateCaptcha<'a> {
@@ -41,3 +41,13 @@ pub struct RotateCaptcha<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) img_instructions: Option<Cow<'a, str>>,
 }
+
+#[cfg(feature = "visualize")]
+impl<'a> crate::visualize::Visualize for RotateCaptcha<'a> {
+    fn visualize(
+        &self,
+        solution: &Self::Solution,
+    ) -> Result<crate::visualize::Visualizer, crate::preprocess::PreprocessError> {
+        Ok(crate::visualize::Visualizer::from_body(&self.body)?.rotation(solution))
+    }
+}
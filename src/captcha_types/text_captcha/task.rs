@@ -13,7 +13,7 @@ use crate::CaptchaTask;
 ///     .comment("If tomorrow is Saturday, what day is today?")
 ///     .build();
 /// ```
-#[derive(serde::Serialize, CaptchaTask)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
 #[serde(tag = "type", rename = "TextCaptchaTask")]
 #[task(timeout = 5, solution = super::solution::TextCaptchaSolution<'a>, crate = crate)]
 pub struct TextCaptcha<'a> {
@@ -29,15 +29,15 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "TurnstileTask", proxyless = "TurnstileTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
-#[task(timeout = 20, solution = super::super::solution::TurnstileCaptchaSolution<'a>, crate = crate)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
+#[task(timeout = 20, validity = 300, solution = super::super::solution::TurnstileCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct TurnstileChallengePageCaptcha<'a> {
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
     #[serde(rename = "websiteURL")]
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     pub(super) website_url: Url,
 
     /// Turnstile sitekey. Can be found inside the `data-sitekey` property of
@@ -1,7 +1,19 @@
 use std::borrow::Cow;
 
-#[derive(serde::Deserialize, Debug)]
+use crate::apply::{Application, ApplySolution};
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, IntoOwned)]
+#[into_owned(crate = crate)]
 pub struct TurnstileCaptchaSolution<'a> {
     pub token: Cow<'a, str>,
     pub user_agent: Cow<'a, str>,
 }
+
+impl ApplySolution for TurnstileCaptchaSolution<'_> {
+    fn application(&self) -> Application<'_> {
+        Application::default()
+            .field("cf-turnstile-response", &*self.token)
+            .user_agent(&*self.user_agent)
+    }
+}
@@ -25,14 +25,14 @@ use crate::CaptchaTask;
 /// # }
 /// ```
 #[proxy_task(with_proxy = "TurnstileTask", proxyless = "TurnstileTaskProxyless", crate = crate)]
-#[derive(serde::Serialize, CaptchaTask)]
-#[task(timeout = 20, solution = super::super::solution::TurnstileCaptchaSolution<'a>, crate = crate)]
+#[derive(serde::Serialize, Clone, CaptchaTask)]
+#[task(timeout = 20, validity = 300, solution = super::super::solution::TurnstileCaptchaSolution<'a>, crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct TurnstileStandaloneCaptcha<'a> {
     /// The full URL of target web page where the captcha is loaded.
     /// We do not open the page, so it is not a problem if it is available
     /// only for authenticated users
-    #[task(builder_type = &'a str, parse_with = { fallible({ path = url::Url::parse }) })]
+    #[task(builder_type = Cow<'a, str>, parse_with = { fallible({ path = url::Url::parse, parse_ref }) }, unparse_with = String::from)]
     #[serde(rename = "websiteURL")]
     pub(super) website_url: Url,
 
@@ -44,4 +44,14 @@ pub struct TurnstileStandaloneCaptcha<'a> {
     /// Use only modern browsers' User-Agents
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) user_agent: Option<Cow<'a, str>>,
+
+    /// The value of the `data-action` attribute of the Turnstile `div`
+    /// element or of the `action` parameter of the `turnstile.render` call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) action: Option<Cow<'a, str>>,
+
+    /// The value of the `data-cdata` attribute of the Turnstile `div`
+    /// element or of the `cData` parameter of the `turnstile.render` call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) data: Option<Cow<'a, str>>,
 }
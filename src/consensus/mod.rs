@@ -0,0 +1,387 @@
+//! Solving image captchas several times and comparing the answers
+//!
+//! A single worker gets a fair share of image captchas wrong. For the ones
+//! that matter, [`CaptchaSolver::solve_consensus`](crate::CaptchaSolver::solve_consensus)
+//! submits the same task several times and only trusts an answer once enough
+//! workers agree on it. Text answers agree when they are equal once trimmed
+//! and lowercased, grid clicks when the sets of cells overlap enough, and
+//! rotations when the angles are close enough.
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     captcha_types::normal_captcha::NormalCaptcha,
+//!     consensus::ConsensusOptions,
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = CaptchaSolver::new("YOUR_API_KEY");
+//!
+//! let task = NormalCaptcha::builder()
+//!     .body("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==")
+//!     .build();
+//!
+//! let options = ConsensusOptions::new()
+//!     .submissions(5)
+//!     .agreement(3)
+//!     .report_minority(true);
+//!
+//! let consensus = solver.solve_consensus(task, options).await?;
+//! println!("{} votes for ${}", consensus.submissions(), consensus.cost());
+//!
+//! if let Some(solution) = consensus.winner() {
+//!     println!("{}", solution.solution.text);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod options;
+
+pub use options::ConsensusOptions;
+
+use std::{cmp::Reverse, collections::HashSet};
+
+use crate::{
+    captcha_types::{
+        grid_captcha::GridCaptcha, normal_captcha::NormalCaptcha, rotate_captcha::RotateCaptcha,
+        text_captcha::TextCaptcha,
+    },
+    solution::CaptchaSolution,
+    CaptchaTask, Error,
+};
+
+/// The tasks whose answers can be compared to each other
+pub trait ConsensusTask: CaptchaTask {
+    /// Whether two solutions of the task are the same answer
+    fn agree(&self, a: &Self::Solution, b: &Self::Solution, options: &ConsensusOptions) -> bool;
+}
+
+impl ConsensusTask for NormalCaptcha<'_> {
+    fn agree(&self, a: &Self::Solution, b: &Self::Solution, _: &ConsensusOptions) -> bool {
+        let case_sensitive = self.is_case_sensitive();
+        normalize(&a.text, case_sensitive) == normalize(&b.text, case_sensitive)
+    }
+}
+
+impl ConsensusTask for TextCaptcha<'_> {
+    fn agree(&self, a: &Self::Solution, b: &Self::Solution, _: &ConsensusOptions) -> bool {
+        normalize(&a.text, false) == normalize(&b.text, false)
+    }
+}
+
+impl ConsensusTask for GridCaptcha<'_> {
+    fn agree(&self, a: &Self::Solution, b: &Self::Solution, options: &ConsensusOptions) -> bool {
+        let a = a.click.iter().collect::<HashSet<_>>();
+        let b = b.click.iter().collect::<HashSet<_>>();
+
+        let union = a.union(&b).count();
+        if union == 0 {
+            return true;
+        }
+
+        a.intersection(&b).count() as f64 / union as f64 >= options.overlap
+    }
+}
+
+impl ConsensusTask for RotateCaptcha<'_> {
+    fn agree(&self, a: &Self::Solution, b: &Self::Solution, options: &ConsensusOptions) -> bool {
+        let difference = a.rotate.abs_diff(b.rotate) % 360;
+        difference.min(360 - difference) <= options.tolerance
+    }
+}
+
+/// Trims the text and collapses its whitespace, lowercasing it
+/// unless the answer is case sensitive
+fn normalize(text: &str, case_sensitive: bool) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match case_sensitive {
+        true => text,
+        false => text.to_lowercase(),
+    }
+}
+
+/// The solutions that agree with each other
+#[derive(Debug)]
+pub struct Vote<'a, T> {
+    pub solutions: Vec<CaptchaSolution<'a, T>>,
+}
+
+impl<'a, T> Vote<'a, T> {
+    pub fn count(&self) -> usize {
+        self.solutions.len()
+    }
+
+    /// The first solution of the vote, which the others were compared to
+    pub fn answer(&self) -> &CaptchaSolution<'a, T> {
+        &self.solutions[0]
+    }
+}
+
+/// Every answer of [`crate::CaptchaSolver::solve_consensus`], grouped in
+/// votes from the most to the least agreed upon
+#[derive(Debug)]
+pub struct Consensus<'a, T> {
+    pub votes: Vec<Vote<'a, T>>,
+
+    /// The errors of the submissions that weren't solved, which
+    /// count as submissions but not as votes, followed by those of
+    /// the minority answers that couldn't be reported
+    pub failures: Vec<Error>,
+
+    agreement: usize,
+    failed_reports: usize,
+}
+
+impl<'a, T> Consensus<'a, T> {
+    pub(crate) const fn new(agreement: usize) -> Self {
+        Self {
+            votes: Vec::new(),
+            failures: Vec::new(),
+            agreement,
+            failed_reports: 0,
+        }
+    }
+
+    pub(crate) fn fail(&mut self, error: Error) {
+        self.failures.push(error);
+    }
+
+    /// Keeps the error of a minority answer that couldn't be reported,
+    /// which isn't counted as a submission
+    pub(crate) fn fail_report(&mut self, error: Error) {
+        self.failures.push(error);
+        self.failed_reports += 1;
+    }
+
+    /// Adds the solution to the first vote it agrees with, or to a new one
+    pub(crate) fn push<U>(
+        &mut self,
+        task: &U,
+        solution: CaptchaSolution<'a, T>,
+        options: &ConsensusOptions,
+    ) where
+        U: ConsensusTask<Solution = T>,
+    {
+        let vote = self
+            .votes
+            .iter_mut()
+            .find(|x| task.agree(&x.answer().solution, &solution.solution, options));
+
+        match vote {
+            Some(vote) => vote.solutions.push(solution),
+            None => self.votes.push(Vote {
+                solutions: vec![solution],
+            }),
+        }
+
+        self.votes.sort_by_key(|x| Reverse(x.count()));
+    }
+
+    /// Whether enough answers agree
+    pub fn is_reached(&self) -> bool {
+        self.leading() >= self.agreement
+    }
+
+    /// The number of answers of the most agreed upon vote
+    pub fn leading(&self) -> usize {
+        self.votes.first().map_or(0, Vote::count)
+    }
+
+    /// The answer enough workers agreed on, if any
+    pub fn winner(&self) -> Option<&CaptchaSolution<'a, T>> {
+        self.votes
+            .first()
+            .filter(|_| self.is_reached())
+            .map(Vote::answer)
+    }
+
+    pub fn into_winner(self) -> Option<CaptchaSolution<'a, T>> {
+        let is_reached = self.is_reached();
+
+        self.votes
+            .into_iter()
+            .next()
+            .filter(|_| is_reached)
+            .and_then(|x| x.solutions.into_iter().next())
+    }
+
+    /// The solutions that disagree with the winner, or none if there's no winner
+    pub fn minority(&self) -> impl Iterator<Item = &CaptchaSolution<'a, T>> {
+        let skip = match self.is_reached() {
+            true => 1,
+            false => self.votes.len(),
+        };
+
+        self.votes.iter().skip(skip).flat_map(|x| &x.solutions)
+    }
+
+    /// The number of times the task was submitted, including the failed submissions
+    pub fn submissions(&self) -> usize {
+        self.votes.iter().map(Vote::count).sum::<usize>() + self.failures.len()
+            - self.failed_reports
+    }
+
+    /// The total price charged for every submission
+    pub fn cost(&self) -> f64 {
+        self.votes
+            .iter()
+            .flat_map(|x| &x.solutions)
+            .filter_map(|x| x.cost.parse::<f64>().ok())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ConsensusOptions, ConsensusTask};
+    use crate::{
+        captcha_types::{
+            grid_captcha::{GridCaptcha, GridCaptchaSolution},
+            normal_captcha::NormalCaptcha,
+            rotate_captcha::{RotateCaptcha, RotateCaptchaSolution},
+        },
+        test_server::{self, Response},
+        CaptchaTask, Error,
+    };
+
+    #[test]
+    fn compares_answers() {
+        let options = ConsensusOptions::new().overlap(0.5).tolerance(10);
+
+        let grid = GridCaptcha::builder()
+            .body("IMAGE")
+            .rows(Some(3))
+            .columns(Some(3))
+            .comment("Select all cars")
+            .build();
+        let click = |x: &[u8]| GridCaptchaSolution { click: x.into() };
+
+        assert!(grid.agree(&click(&[1, 2, 3]), &click(&[3, 2, 1]), &options));
+        assert!(grid.agree(&click(&[1, 2, 3]), &click(&[1, 2]), &options));
+        assert!(!grid.agree(&click(&[1, 2, 3]), &click(&[1, 4]), &options));
+
+        let rotate = RotateCaptcha::builder().body("IMAGE").build();
+        let angle = |rotate| RotateCaptchaSolution { rotate };
+
+        assert!(rotate.agree(&angle(355), &angle(5), &options));
+        assert!(rotate.agree(&angle(90), &angle(100), &options));
+        assert!(!rotate.agree(&angle(90), &angle(120), &options));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reaches_consensus() {
+        // The n-th task is solved with the n-th answer
+        let (url, requests) =
+            test_server::serve_logged(|request, log| match request.path.as_str() {
+                "/createTask" => {
+                    let n = log.count("/createTask");
+                    Response::ok(format!(r#"{{"errorId":0,"taskId":{n}}}"#))
+                }
+                "/getTaskResult" => {
+                    let text = match request.body.contains(r#""taskId":2"#) {
+                        true => "w68hq",
+                        false => " W68hp",
+                    };
+
+                    Response::task_ready(&format!(r#"{{"text":"{text}"}}"#))
+                }
+                _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+            });
+
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let consensus = solver
+            .solve_consensus(task, ConsensusOptions::new().report_minority(true))
+            .await
+            .unwrap();
+
+        assert!(consensus.is_reached());
+        assert_eq!(consensus.submissions(), 3);
+        assert_eq!(consensus.votes.len(), 2);
+        assert_eq!(consensus.leading(), 2);
+        assert!((consensus.cost() - 3.0 * 0.00299).abs() < 1e-9);
+        assert_eq!(consensus.minority().next().unwrap().solution.text, "w68hq");
+        assert_eq!(
+            consensus
+                .winner()
+                .unwrap()
+                .solution
+                .text
+                .trim()
+                .to_lowercase(),
+            "w68hp"
+        );
+
+        assert_eq!(requests.count("/report"), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn counts_failed_submissions() {
+        // The second task is unsolvable
+        let (url, requests) =
+            test_server::serve_logged(|request, log| match request.path.as_str() {
+                "/createTask" => {
+                    let n = log.count("/createTask");
+                    Response::ok(format!(r#"{{"errorId":0,"taskId":{n}}}"#))
+                }
+                _ if request.body.contains(r#""taskId":2"#) => {
+                    Response::ok(r#"{"errorId":12,"errorCode":"ERROR_CAPTCHA_UNSOLVABLE"}"#)
+                }
+                _ => Response::task_ready(r#"{"text":"w68hp"}"#),
+            });
+
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let consensus = solver
+            .solve_consensus(task, ConsensusOptions::new())
+            .await
+            .unwrap();
+
+        assert!(consensus.is_reached());
+        assert_eq!(consensus.submissions(), 3);
+        assert_eq!(consensus.leading(), 2);
+        assert!(matches!(
+            consensus.failures[..],
+            [Error::TwoCaptchaError(_)]
+        ));
+        assert_eq!(requests.count("/createTask"), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_consensus_when_reports_fail() {
+        // The second task disagrees, and can't be reported
+        let (url, _) = test_server::serve_logged(|request, log| match request.path.as_str() {
+            "/createTask" => {
+                let n = log.count("/createTask");
+                Response::ok(format!(r#"{{"errorId":0,"taskId":{n}}}"#))
+            }
+            "/getTaskResult" if request.body.contains(r#""taskId":2"#) => {
+                Response::task_ready(r#"{"text":"w68hq"}"#)
+            }
+            "/getTaskResult" => Response::task_ready(r#"{"text":"w68hp"}"#),
+            _ => Response::ok(r#"{"errorId":16,"errorCode":"ERROR_NO_SUCH_CAPCHA_ID"}"#),
+        });
+
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let consensus = solver
+            .solve_consensus(task, ConsensusOptions::new().report_minority(true))
+            .await
+            .unwrap();
+
+        assert_eq!(consensus.winner().unwrap().solution.text, "w68hp");
+        assert_eq!(consensus.submissions(), 3);
+        assert!(matches!(
+            consensus.failures[..],
+            [Error::TwoCaptchaError(_)]
+        ));
+    }
+}
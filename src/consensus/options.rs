@@ -0,0 +1,66 @@
+/// How many times [`crate::CaptchaSolver::solve_consensus`] submits a task
+/// and how its answers are compared
+#[derive(Debug, Clone, Copy)]
+pub struct ConsensusOptions {
+    pub(crate) submissions: usize,
+    pub(crate) agreement: usize,
+    pub(crate) report_minority: bool,
+    pub(super) overlap: f64,
+    pub(super) tolerance: u16,
+}
+
+impl ConsensusOptions {
+    /// Submits the task up to 3 times, until 2 answers agree
+    pub const fn new() -> Self {
+        Self {
+            submissions: 3,
+            agreement: 2,
+            report_minority: false,
+            overlap: 0.8,
+            tolerance: 15,
+        }
+    }
+
+    /// The maximum number of times the task is submitted
+    pub const fn submissions(mut self, submissions: usize) -> Self {
+        self.submissions = submissions;
+        self
+    }
+
+    /// The number of answers that have to agree. The task is only submitted
+    /// as many times as needed to reach it, so it's submitted `agreement`
+    /// times at first, and again when the answers disagree
+    pub const fn agreement(mut self, agreement: usize) -> Self {
+        self.agreement = agreement;
+        self
+    }
+
+    /// Whether the answers that disagree with the consensus are reported as
+    /// [`crate::solver::SolutionStatus::Bad`]. Nothing is reported if the
+    /// agreement isn't reached
+    pub const fn report_minority(mut self, report_minority: bool) -> Self {
+        self.report_minority = report_minority;
+        self
+    }
+
+    /// How much two sets of clicked cells of a
+    /// [`crate::captcha_types::grid_captcha::GridCaptcha`] must overlap to
+    /// agree, as the size of their intersection over the size of their union
+    pub const fn overlap(mut self, overlap: f64) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// How many degrees two angles of a
+    /// [`crate::captcha_types::rotate_captcha::RotateCaptcha`] may differ by to agree
+    pub const fn tolerance(mut self, tolerance: u16) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl Default for ConsensusOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -31,6 +31,15 @@ impl<'a> Cookies<'a> {
     pub fn stringify(&self) -> Cow<'a, str> {
         self.iter.iter().map(Cookie::stringify).join(";").into()
     }
+
+    /// Parses cookies stringified with [`Cookies::stringify`]
+    pub fn parse(cookies: Cow<'a, str>) -> Self {
+        cookies
+            .split(';')
+            .filter_map(|x| x.split_once('='))
+            .map(|(name, value)| Cookie::new(name.trim().to_owned(), value.trim().to_owned()))
+            .collect()
+    }
 }
 
 impl<'a, I> FromIterator<I> for Cookies<'a>
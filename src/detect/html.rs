@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+lazy_static! {
+    static ref TAG: Regex = Regex::new(r#"(?is)<([a-z][a-z0-9-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>"#)
+        .expect("The tag pattern is valid");
+    static ref ATTRIBUTE: Regex =
+        Regex::new(r#"(?s)([^\s"'<>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#)
+            .expect("The attribute pattern is valid");
+    static ref INLINE_SCRIPT: Regex = Regex::new(r"(?is)<script\b[^>]*>(.*?)</script\s*>")
+        .expect("The inline script pattern is valid");
+}
+
+/// An opening tag found in the document, with its attributes
+#[derive(Debug)]
+pub(crate) struct Tag {
+    pub(crate) name: String,
+
+    /// The byte offset of the tag in the document
+    pub(crate) offset: usize,
+    attributes: HashMap<String, String>,
+}
+
+impl Tag {
+    pub(crate) fn get(&self, attribute: &str) -> Option<&str> {
+        self.attributes
+            .get(attribute)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    pub(crate) fn has_class(&self, class: &str) -> bool {
+        self.get("class")
+            .is_some_and(|classes| classes.split_whitespace().any(|x| x == class))
+    }
+
+    /// The URL of the `src` attribute, resolved against the page's URL
+    pub(crate) fn src(&self, page_url: &Url) -> Option<Url> {
+        page_url.join(self.get("src")?).ok()
+    }
+}
+
+/// Every opening tag in the document, in the order they appear
+pub(crate) fn tags(html: &str) -> impl Iterator<Item = Tag> + '_ {
+    TAG.captures_iter(html).map(|captures| {
+        let attributes = ATTRIBUTE
+            .captures_iter(captures.get(2).map_or("", |x| x.as_str()))
+            .map(|attribute| {
+                let value = (2..=4)
+                    .find_map(|group| attribute.get(group))
+                    .map_or("", |x| x.as_str());
+
+                (attribute[1].to_ascii_lowercase(), unescape(value))
+            })
+            .collect();
+
+        Tag {
+            name: captures[1].to_ascii_lowercase(),
+            offset: captures.get(0).map_or(0, |x| x.start()),
+            attributes,
+        }
+    })
+}
+
+/// The contents of every inline `script` element, with their byte offset
+/// in the document
+pub(crate) fn inline_scripts(html: &str) -> impl Iterator<Item = (usize, &str)> {
+    INLINE_SCRIPT
+        .captures_iter(html)
+        .filter_map(|captures| captures.get(1))
+        .map(|x| (x.start(), x.as_str()))
+}
+
+/// Decodes the character references that commonly appear inside attribute values
+fn unescape(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_owned();
+    }
+
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
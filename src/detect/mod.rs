@@ -0,0 +1,857 @@
+//! Detection of captcha widgets embedded in HTML documents
+//!
+//! [`detect`] looks for the elements, iframes and scripts each vendor uses
+//! to render its widget and extracts the parameters 2captcha needs, such as
+//! the sitekey, whether the widget is invisible or uses an enterprise script.
+//! Each [`DetectedWidget`] can then be turned into a task with
+//! [`DetectedWidget::to_task`].
+//!
+//! # Example
+//! ```
+//! use captcha_oxide::detect::{detect, DetectedTask, Vendor};
+//!
+//! # fn main() -> Result<(), captcha_oxide::Error> {
+//! let html = r#"
+//!     <script src="https://www.google.com/recaptcha/api.js" async defer></script>
+//!     <form>
+//!         <div class="g-recaptcha" data-sitekey="6Le-wvkSAAAAAPBMRTvw0Q4Muexq9bi0DJwx_mJ-"></div>
+//!     </form>
+//! "#;
+//!
+//! let widgets = detect(html, "https://example.com/login")?;
+//! assert_eq!(widgets[0].vendor(), Vendor::Recaptcha);
+//!
+//! match widgets[0].to_task()? {
+//!     DetectedTask::RecaptchaV2(task) => { /* solver.solve(task).await? */ }
+//!     _ => unreachable!(),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub(crate) mod html;
+mod task;
+
+pub(crate) use task::TaskContext;
+pub use task::{DetectedBuilder, DetectedTask};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::prelude::*;
+
+use self::html::Tag;
+
+/// The score requested for reCAPTCHA V3 widgets, as the page doesn't reveal
+/// the score it requires
+pub const DEFAULT_MIN_SCORE: f32 = 0.3;
+
+lazy_static! {
+    static ref RECAPTCHA_EXECUTE: Regex = Regex::new(
+        r#"grecaptcha\s*\.\s*(enterprise\s*\.\s*)?execute\s*\(\s*['"]([\w-]+)['"](?:\s*,\s*\{[^}]*?action\s*:\s*['"]([^'"]+)['"])?"#
+    )
+    .expect("The reCAPTCHA execute pattern is valid");
+    static ref RENDER_SITEKEY: Regex = Regex::new(
+        r#"(grecaptcha\s*\.\s*enterprise|grecaptcha|hcaptcha|turnstile)\s*\.\s*render\s*\([^{]*\{[^}]*?['"]?sitekey['"]?\s*:\s*['"]([\w-]+)['"]"#
+    )
+    .expect("The render pattern is valid");
+    static ref RENDER_OPTION: Regex =
+        Regex::new(r#"['"]?(action|cData)['"]?\s*:\s*['"]([^'"]*)['"]"#)
+            .expect("The render option pattern is valid");
+    static ref MT_CAPTCHA_SITEKEY: Regex =
+        Regex::new(r#"['"]?sitekey['"]?\s*:\s*['"](MTPublic-[\w-]+)['"]"#)
+            .expect("The MTCaptcha pattern is valid");
+    static ref GOKU_PROPS: Regex = Regex::new(r"gokuProps\s*=\s*(\{[^}]*\})")
+        .expect("The Amazon WAF pattern is valid");
+    static ref ARKOSE_SCRIPT: Regex = Regex::new(r"(?i)^/v2/([0-9a-f]{8}(?:-[0-9a-f]{4}){3}-[0-9a-f]{12})/api\.js$")
+        .expect("The Arkose Labs script pattern is valid");
+}
+
+/// The company behind a captcha widget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum Vendor {
+    Recaptcha,
+    HCaptcha,
+    Turnstile,
+    FriendlyCaptcha,
+    MtCaptcha,
+    ArkoseLabs,
+    GeeTest,
+    AmazonWaf,
+
+    /// Only found by [`crate::blocked::classify`], as DataDome doesn't embed a widget
+    DataDome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum RecaptchaVersion {
+    V2,
+    V3,
+}
+
+/// The vendor specific parameters of a [`DetectedWidget`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase", tag = "vendor")]
+pub enum Widget {
+    #[serde(rename_all = "camelCase")]
+    Recaptcha {
+        version: RecaptchaVersion,
+
+        /// Whether the widget was loaded with the `enterprise.js` script
+        /// or is used through `grecaptcha.enterprise`
+        is_enterprise: bool,
+
+        is_invisible: bool,
+
+        /// `recaptcha.net` if the widget isn't loaded from `google.com`
+        api_domain: Option<String>,
+
+        /// The value of the `data-s` attribute, used on Google services
+        data_s: Option<String>,
+
+        /// The value of the `data-action` attribute or of the `action`
+        /// passed to `grecaptcha.execute`
+        action: Option<String>,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    HCaptcha {
+        is_invisible: bool,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    Turnstile {
+        /// The value of the `data-action` attribute, or the `action`
+        /// passed to `turnstile.render`
+        action: Option<String>,
+
+        /// The value of the `data-cdata` attribute, or the `cData`
+        /// passed to `turnstile.render`
+        cdata: Option<String>,
+    },
+
+    FriendlyCaptcha,
+
+    MtCaptcha,
+
+    #[serde(rename_all = "camelCase")]
+    ArkoseLabs {
+        /// The custom subdomain the widget is loaded from, if any,
+        /// e.g.: `sample-api.arkoselabs.com`
+        api_subdomain: Option<String>,
+
+        /// The `data[blob]` value sent when the challenge is requested
+        data_blob: Option<String>,
+    },
+
+    /// The `website_key` of GeeTest widgets is their `gt` value
+    #[serde(rename_all = "camelCase")]
+    GeeTestV3 {
+        challenge: String,
+
+        /// The custom API domain the widget is loaded from, if any,
+        /// e.g.: `api-na.geetest.com`
+        api_server: Option<String>,
+    },
+
+    /// The `website_key` of GeeTest V4 widgets is their `captcha_id`
+    #[serde(rename_all = "camelCase")]
+    GeeTestV4 {
+        challenge: String,
+
+        /// The custom API domain the widget is loaded from, if any
+        api_server: Option<String>,
+    },
+
+    /// The `website_key` of Amazon WAF widgets is the `key` of `window.gokuProps`
+    #[serde(rename_all = "camelCase")]
+    AmazonWaf {
+        iv: String,
+        context: String,
+
+        /// The URL of the `challenge.js` script
+        challenge_script: Option<String>,
+
+        /// The URL of the `captcha.js` script
+        captcha_script: Option<String>,
+    },
+}
+
+/// A captcha widget found in a page
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedWidget {
+    /// The URL of the page the widget was found in
+    pub website_url: Url,
+
+    /// The sitekey, or public key for Arkose Labs, of the widget
+    pub website_key: String,
+
+    pub widget: Widget,
+}
+
+impl DetectedWidget {
+    pub const fn vendor(&self) -> Vendor {
+        match self.widget {
+            Widget::Recaptcha { .. } => Vendor::Recaptcha,
+            Widget::HCaptcha { .. } => Vendor::HCaptcha,
+            Widget::Turnstile { .. } => Vendor::Turnstile,
+            Widget::FriendlyCaptcha => Vendor::FriendlyCaptcha,
+            Widget::MtCaptcha => Vendor::MtCaptcha,
+            Widget::ArkoseLabs { .. } => Vendor::ArkoseLabs,
+            Widget::GeeTestV3 { .. } | Widget::GeeTestV4 { .. } => Vendor::GeeTest,
+            Widget::AmazonWaf { .. } => Vendor::AmazonWaf,
+        }
+    }
+
+    /// Whether both widgets are the same one, found through different elements
+    pub(crate) fn is_same(&self, other: &Self) -> bool {
+        self.website_key == other.website_key
+            && match (&self.widget, &other.widget) {
+                (Widget::Recaptcha { version: a, .. }, Widget::Recaptcha { version: b, .. }) => {
+                    a == b
+                }
+                (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+            }
+    }
+
+    /// Fills the parameters missing from `self` with those found in `other`
+    pub(crate) fn merge(&mut self, other: Self) {
+        match (&mut self.widget, other.widget) {
+            (
+                Widget::Recaptcha {
+                    is_enterprise,
+                    is_invisible,
+                    api_domain,
+                    data_s,
+                    action,
+                    ..
+                },
+                Widget::Recaptcha {
+                    is_enterprise: other_enterprise,
+                    is_invisible: other_invisible,
+                    api_domain: other_api_domain,
+                    data_s: other_data_s,
+                    action: other_action,
+                    ..
+                },
+            ) => {
+                *is_enterprise |= other_enterprise;
+                *is_invisible |= other_invisible;
+                fill(api_domain, other_api_domain);
+                fill(data_s, other_data_s);
+                fill(action, other_action);
+            }
+            (
+                Widget::HCaptcha { is_invisible },
+                Widget::HCaptcha {
+                    is_invisible: other_invisible,
+                },
+            ) => *is_invisible |= other_invisible,
+            (
+                Widget::Turnstile { action, cdata },
+                Widget::Turnstile {
+                    action: other_action,
+                    cdata: other_cdata,
+                },
+            ) => {
+                fill(action, other_action);
+                fill(cdata, other_cdata);
+            }
+            (
+                Widget::ArkoseLabs {
+                    api_subdomain,
+                    data_blob,
+                },
+                Widget::ArkoseLabs {
+                    api_subdomain: other_api_subdomain,
+                    data_blob: other_data_blob,
+                },
+            ) => {
+                fill(api_subdomain, other_api_subdomain);
+                fill(data_blob, other_data_blob);
+            }
+            (
+                Widget::GeeTestV3 { api_server, .. },
+                Widget::GeeTestV3 {
+                    api_server: other_api_server,
+                    ..
+                },
+            )
+            | (
+                Widget::GeeTestV4 { api_server, .. },
+                Widget::GeeTestV4 {
+                    api_server: other_api_server,
+                    ..
+                },
+            ) => fill(api_server, other_api_server),
+            (
+                Widget::AmazonWaf {
+                    challenge_script,
+                    captcha_script,
+                    ..
+                },
+                Widget::AmazonWaf {
+                    challenge_script: other_challenge_script,
+                    captcha_script: other_captcha_script,
+                    ..
+                },
+            ) => {
+                fill(challenge_script, other_challenge_script);
+                fill(captcha_script, other_captcha_script);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn fill(value: &mut Option<String>, other: Option<String>) {
+    if value.is_none() {
+        *value = other;
+    }
+}
+
+/// Finds the captcha widgets embedded in an HTML document, in the order
+/// they appear. Widgets found through more than one element, e.g.: a `div`
+/// and the iframe rendered inside it, are only returned once
+///
+/// # Errors
+/// This function will error if `page_url` is not a valid URL
+pub fn detect(html: &str, page_url: &str) -> Result<Vec<DetectedWidget>> {
+    let page_url = Url::parse(page_url)?;
+    let mut detector = Detector {
+        page_url: &page_url,
+        widgets: Vec::new(),
+        recaptcha_script: RecaptchaScript::default(),
+        amazon_scripts: AmazonScripts::default(),
+    };
+
+    let mut tags = html::tags(html).peekable();
+    for (offset, script) in html::inline_scripts(html) {
+        while let Some(tag) = tags.next_if(|tag| tag.offset < offset) {
+            detector.tag(&tag);
+        }
+
+        detector.inline_script(script);
+    }
+
+    for tag in tags {
+        detector.tag(&tag);
+    }
+
+    Ok(detector.finish())
+}
+
+/// The parameters of the reCAPTCHA script, which apply to every reCAPTCHA widget
+#[derive(Default)]
+struct RecaptchaScript {
+    is_enterprise: bool,
+    api_domain: Option<String>,
+}
+
+/// The scripts used by Amazon WAF, which are only referenced by URL
+#[derive(Default)]
+struct AmazonScripts {
+    challenge: Option<String>,
+    captcha: Option<String>,
+}
+
+/// The parameters Amazon WAF pages assign to `window.gokuProps`
+#[derive(serde::Deserialize)]
+struct GokuProps {
+    key: String,
+    iv: String,
+    context: String,
+}
+
+struct Detector<'a> {
+    page_url: &'a Url,
+    widgets: Vec<DetectedWidget>,
+    recaptcha_script: RecaptchaScript,
+    amazon_scripts: AmazonScripts,
+}
+
+impl<'a> Detector<'a> {
+    fn add(&mut self, website_key: &str, widget: Widget) {
+        let widget = DetectedWidget {
+            website_url: self.page_url.clone(),
+            website_key: website_key.trim().to_owned(),
+            widget,
+        };
+
+        if widget.website_key.is_empty() {
+            return;
+        }
+
+        match self.widgets.iter_mut().find(|x| x.is_same(&widget)) {
+            Some(existing) => existing.merge(widget),
+            None => self.widgets.push(widget),
+        }
+    }
+
+    fn recaptcha(&mut self, website_key: &str, version: RecaptchaVersion, tag: Option<&Tag>) {
+        self.add(
+            website_key,
+            Widget::Recaptcha {
+                version,
+                is_enterprise: false,
+                is_invisible: tag.is_some_and(|tag| {
+                    tag.get("data-size") == Some("invisible") || tag.name == "button"
+                }),
+                api_domain: None,
+                data_s: tag.and_then(|tag| tag.get("data-s")).map(str::to_owned),
+                action: tag
+                    .and_then(|tag| tag.get("data-action"))
+                    .map(str::to_owned),
+            },
+        );
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        let sitekey = tag.get("data-sitekey");
+
+        match sitekey {
+            Some(key) if tag.has_class("g-recaptcha") => {
+                self.recaptcha(key, RecaptchaVersion::V2, Some(tag))
+            }
+            Some(key) if tag.has_class("h-captcha") => self.add(
+                key,
+                Widget::HCaptcha {
+                    is_invisible: tag.get("data-size") == Some("invisible"),
+                },
+            ),
+            Some(key) if tag.has_class("cf-turnstile") => self.add(
+                key,
+                Widget::Turnstile {
+                    action: tag.get("data-action").map(str::to_owned),
+                    cdata: tag.get("data-cdata").map(str::to_owned),
+                },
+            ),
+            Some(key) if tag.has_class("frc-captcha") => self.add(key, Widget::FriendlyCaptcha),
+            Some(key) if key.starts_with("MTPublic-") => self.add(key, Widget::MtCaptcha),
+            _ => {}
+        }
+
+        if let Some(key) = tag.get("data-pkey") {
+            self.add(
+                key,
+                Widget::ArkoseLabs {
+                    api_subdomain: None,
+                    data_blob: None,
+                },
+            );
+        }
+
+        if tag.name == "input" && tag.get("name") == Some("fc-token") {
+            if let Some(value) = tag.get("value") {
+                self.arkose_token(value);
+            }
+        }
+
+        let Some(src) = tag.src(self.page_url) else {
+            return;
+        };
+
+        match tag.name.as_str() {
+            "script" => self.script(&src),
+            "iframe" => self.iframe(&src),
+            _ => {}
+        }
+    }
+
+    fn script(&mut self, src: &Url) {
+        let host = src.host_str().unwrap_or_default();
+        let path = src.path();
+
+        if is_recaptcha_host(host)
+            && matches!(path, "/recaptcha/api.js" | "/recaptcha/enterprise.js")
+        {
+            let script = &mut self.recaptcha_script;
+            script.is_enterprise |= path == "/recaptcha/enterprise.js";
+            fill(&mut script.api_domain, api_domain(host));
+
+            let render = query(src, "render");
+            if let Some(key) = render.filter(|x| !matches!(x.as_str(), "explicit" | "onload")) {
+                self.recaptcha(&key, RecaptchaVersion::V3, None);
+            }
+        }
+
+        if is_amazon_waf_host(host) {
+            let src = Some(src.to_string());
+
+            if path.ends_with("/challenge.js") {
+                fill(&mut self.amazon_scripts.challenge, src);
+            } else if path.ends_with("/captcha.js") {
+                fill(&mut self.amazon_scripts.captcha, src);
+            }
+        }
+
+        if is_arkose_host(host) {
+            if let Some(captures) = ARKOSE_SCRIPT.captures(path) {
+                self.add(
+                    &captures[1],
+                    Widget::ArkoseLabs {
+                        api_subdomain: arkose_subdomain(host),
+                        data_blob: None,
+                    },
+                );
+            }
+        }
+    }
+
+    fn iframe(&mut self, src: &Url) {
+        let host = src.host_str().unwrap_or_default();
+        let path = src.path();
+
+        if is_recaptcha_host(host) && path.ends_with("/anchor") {
+            let Some(key) = query(src, "k") else {
+                return;
+            };
+
+            self.add(
+                &key,
+                Widget::Recaptcha {
+                    version: RecaptchaVersion::V2,
+                    is_enterprise: path.contains("/enterprise/"),
+                    is_invisible: query(src, "size").as_deref() == Some("invisible"),
+                    api_domain: api_domain(host),
+                    data_s: None,
+                    action: None,
+                },
+            );
+        }
+
+        if host == "hcaptcha.com" || host.ends_with(".hcaptcha.com") {
+            // hCaptcha passes its parameters in the fragment of the iframe's URL
+            let fragment = src.fragment().map(|fragment| {
+                url::form_urlencoded::parse(fragment.as_bytes())
+                    .into_owned()
+                    .collect::<Vec<_>>()
+            });
+
+            let param = |name: &str| {
+                query(src, name).or_else(|| {
+                    fragment
+                        .iter()
+                        .flatten()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value.clone())
+                })
+            };
+
+            if let Some(key) = param("sitekey") {
+                self.add(
+                    &key,
+                    Widget::HCaptcha {
+                        is_invisible: param("size").as_deref() == Some("invisible"),
+                    },
+                );
+            }
+        }
+    }
+
+    fn inline_script(&mut self, script: &str) {
+        for captures in RECAPTCHA_EXECUTE.captures_iter(script) {
+            self.add(
+                &captures[2],
+                Widget::Recaptcha {
+                    version: RecaptchaVersion::V3,
+                    is_enterprise: captures.get(1).is_some(),
+                    is_invisible: false,
+                    api_domain: None,
+                    data_s: None,
+                    action: captures.get(3).map(|x| x.as_str().to_owned()),
+                },
+            );
+        }
+
+        for captures in RENDER_SITEKEY.captures_iter(script) {
+            let key = &captures[2];
+            let vendor = captures[1].split_whitespace().collect::<String>();
+
+            // The options object ends at the first closing brace after the sitekey
+            let options = &script[captures.get(0).map_or(0, |x| x.start())..];
+            let options = &options[..options.find('}').unwrap_or(options.len())];
+            let option = |name: &str| {
+                RENDER_OPTION
+                    .captures_iter(options)
+                    .find(|x| &x[1] == name)
+                    .map(|x| x[2].to_owned())
+                    .filter(|x| !x.is_empty())
+            };
+
+            match vendor.as_str() {
+                "grecaptcha" | "grecaptcha.enterprise" => self.add(
+                    key,
+                    Widget::Recaptcha {
+                        version: RecaptchaVersion::V2,
+                        is_enterprise: vendor != "grecaptcha",
+                        is_invisible: false,
+                        api_domain: None,
+                        data_s: None,
+                        action: None,
+                    },
+                ),
+                "hcaptcha" => self.add(
+                    key,
+                    Widget::HCaptcha {
+                        is_invisible: false,
+                    },
+                ),
+                _ => self.add(
+                    key,
+                    Widget::Turnstile {
+                        action: option("action"),
+                        cdata: option("cData"),
+                    },
+                ),
+            }
+        }
+
+        for captures in MT_CAPTCHA_SITEKEY.captures_iter(script) {
+            self.add(&captures[1], Widget::MtCaptcha);
+        }
+
+        for captures in GOKU_PROPS.captures_iter(script) {
+            if let Ok(props) = serde_json::from_str::<GokuProps>(&captures[1]) {
+                self.add(
+                    &props.key,
+                    Widget::AmazonWaf {
+                        iv: props.iv,
+                        context: props.context,
+                        challenge_script: None,
+                        captcha_script: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Arkose Labs tokens look like `id|r=eu-west-1|pk=KEY|surl=https://...`
+    fn arkose_token(&mut self, token: &str) {
+        let param = |name: &str| {
+            token
+                .split('|')
+                .find_map(|x| x.strip_prefix(name)?.strip_prefix('='))
+        };
+
+        if let Some(key) = param("pk") {
+            let api_subdomain = param("surl")
+                .and_then(|x| Url::parse(&x.replace("%3A", ":").replace("%2F", "/")).ok())
+                .and_then(|x| x.host_str().and_then(arkose_subdomain));
+
+            self.add(
+                key,
+                Widget::ArkoseLabs {
+                    api_subdomain,
+                    data_blob: None,
+                },
+            );
+        }
+    }
+
+    fn finish(mut self) -> Vec<DetectedWidget> {
+        for widget in &mut self.widgets {
+            match &mut widget.widget {
+                Widget::Recaptcha {
+                    is_enterprise,
+                    api_domain,
+                    ..
+                } => {
+                    *is_enterprise |= self.recaptcha_script.is_enterprise;
+                    fill(api_domain, self.recaptcha_script.api_domain.clone());
+                }
+                Widget::AmazonWaf {
+                    challenge_script,
+                    captcha_script,
+                    ..
+                } => {
+                    fill(challenge_script, self.amazon_scripts.challenge.clone());
+                    fill(captcha_script, self.amazon_scripts.captcha.clone());
+                }
+                _ => {}
+            }
+        }
+
+        self.widgets
+    }
+}
+
+pub(crate) fn query(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn is_recaptcha_host(host: &str) -> bool {
+    matches!(
+        host,
+        "google.com" | "www.google.com" | "recaptcha.net" | "www.recaptcha.net"
+    )
+}
+
+pub(crate) fn api_domain(host: &str) -> Option<String> {
+    host.ends_with("recaptcha.net")
+        .then(|| "recaptcha.net".to_owned())
+}
+
+pub(crate) fn is_amazon_waf_host(host: &str) -> bool {
+    host.ends_with(".awswaf.com")
+}
+
+pub(crate) fn is_arkose_host(host: &str) -> bool {
+    host.ends_with(".arkoselabs.com") || host.ends_with(".funcaptcha.com")
+}
+
+pub(crate) fn arkose_subdomain(host: &str) -> Option<String> {
+    (is_arkose_host(host) && host != "client-api.arkoselabs.com").then(|| host.to_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect, DetectedTask, RecaptchaVersion, Vendor, Widget};
+
+    const PAGE_URL: &str = "https://example.com/login";
+
+    #[test]
+    fn detects_recaptcha() {
+        let html = r#"
+            <script src="https://www.recaptcha.net/recaptcha/enterprise.js?render=6LcR_okUAAAAAPYrPe-HK_0RULO1aZM15ENyM-Mf"></script>
+            <form>
+                <div class='g-recaptcha' data-sitekey="6LfD3PIbAAAAAJs_eEHvoOl75_83eXSqpPSRFJ_u" data-s="abc&amp;def"></div>
+                <iframe src="https://www.recaptcha.net/recaptcha/enterprise/anchor?ar=1&k=6LfD3PIbAAAAAJs_eEHvoOl75_83eXSqpPSRFJ_u&size=invisible"></iframe>
+            </form>
+            <script>
+                grecaptcha.enterprise.ready(function () {
+                    grecaptcha.enterprise.execute('6LcR_okUAAAAAPYrPe-HK_0RULO1aZM15ENyM-Mf', { action: 'login' });
+                });
+            </script>
+        "#;
+
+        let widgets = detect(html, PAGE_URL).unwrap();
+        assert_eq!(widgets.len(), 2);
+
+        assert_eq!(
+            widgets[0].widget,
+            Widget::Recaptcha {
+                version: RecaptchaVersion::V3,
+                is_enterprise: true,
+                is_invisible: false,
+                api_domain: Some("recaptcha.net".into()),
+                data_s: None,
+                action: Some("login".into()),
+            }
+        );
+
+        assert_eq!(
+            widgets[1].widget,
+            Widget::Recaptcha {
+                version: RecaptchaVersion::V2,
+                is_enterprise: true,
+                is_invisible: true,
+                api_domain: Some("recaptcha.net".into()),
+                data_s: Some("abc&def".into()),
+                action: None,
+            }
+        );
+
+        assert!(matches!(
+            widgets[0].to_task().unwrap(),
+            DetectedTask::RecaptchaV3(_)
+        ));
+        assert!(matches!(
+            widgets[1].to_task().unwrap(),
+            DetectedTask::RecaptchaV2Enterprise(_)
+        ));
+    }
+
+    #[test]
+    fn detects_other_vendors() {
+        let html = r#"
+            <div class="h-captcha" data-sitekey="a5f74b19-9e45-40e0-b45d-47ff91b7a6c2" data-size="invisible"></div>
+            <div class="cf-turnstile" data-sitekey="0x4AAAAAAAC3DHQFLr1GavRN" data-action="signup"></div>
+            <div class="frc-captcha" data-sitekey="FCMST5VUMCBOCGQ9"></div>
+            <script src="https://client-api.arkoselabs.com/v2/476068BF-9607-4799-B53D-966BE98E2B81/api.js" data-callback="setup"></script>
+            <script src="https://ab12cd34ef56.us-east-1.token.awswaf.com/ab12cd34ef56/e9d1bd6e2f1f/challenge.js"></script>
+            <script>
+                var mtcaptchaConfig = { "sitekey": "MTPublic-KzqLY1cKH" };
+                window.gokuProps = {
+                    "key": "AQIDAHjcYu/GjX+QlghicBgQ/7bFaQZ+m5FKCMDnO+vTbNg96AHDh0IR5vgzHNceHYqZR+GO",
+                    "iv": "CgAFRjIw2vAAABSM",
+                    "context": "zPT0jOl1rQlUNaldX6LUpn4D6Tl9bJ8VUQ/NrWFxPiiFujn"
+                };
+            </script>
+        "#;
+
+        let widgets = detect(html, PAGE_URL).unwrap();
+        let vendors = widgets.iter().map(|x| x.vendor()).collect::<Vec<_>>();
+
+        assert_eq!(
+            vendors,
+            [
+                Vendor::HCaptcha,
+                Vendor::Turnstile,
+                Vendor::FriendlyCaptcha,
+                Vendor::ArkoseLabs,
+                Vendor::MtCaptcha,
+                Vendor::AmazonWaf,
+            ]
+        );
+
+        assert_eq!(widgets[0].widget, Widget::HCaptcha { is_invisible: true });
+
+        let turnstile = serde_json::to_value(widgets[1].to_task().unwrap()).unwrap();
+        assert_eq!(turnstile["action"], "signup");
+        assert!(turnstile.get("data").is_none());
+
+        assert_eq!(
+            widgets[3].widget,
+            Widget::ArkoseLabs {
+                api_subdomain: None,
+                data_blob: None,
+            }
+        );
+        assert_eq!(widgets[4].website_key, "MTPublic-KzqLY1cKH");
+        assert!(matches!(
+            &widgets[5].widget,
+            Widget::AmazonWaf { challenge_script: Some(script), .. } if script.ends_with("/challenge.js")
+        ));
+
+        for widget in &widgets {
+            widget.to_task().unwrap();
+        }
+    }
+
+    #[test]
+    fn keeps_document_order() {
+        let html = r##"
+            <div class="h-captcha" data-sitekey="a5f74b19-9e45-40e0-b45d-47ff91b7a6c2"></div>
+            <script>
+                turnstile.render("#widget", {
+                    sitekey: "0x4AAAAAAAC3DHQFLr1GavRN",
+                    action: "login",
+                    cData: "session-42",
+                    callback: onToken,
+                });
+            </script>
+            <div class="frc-captcha" data-sitekey="FCMST5VUMCBOCGQ9"></div>
+        "##;
+
+        let widgets = detect(html, PAGE_URL).unwrap();
+        let vendors = widgets.iter().map(|x| x.vendor()).collect::<Vec<_>>();
+
+        assert_eq!(
+            vendors,
+            [Vendor::HCaptcha, Vendor::Turnstile, Vendor::FriendlyCaptcha]
+        );
+        assert_eq!(
+            widgets[1].widget,
+            Widget::Turnstile {
+                action: Some("login".to_owned()),
+                cdata: Some("session-42".to_owned()),
+            }
+        );
+    }
+}
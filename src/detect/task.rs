@@ -0,0 +1,257 @@
+use serde_json::json;
+
+use crate::{
+    captcha_types::{
+        amazon_captcha::AmazonCaptcha,
+        arkose_labs_captcha::ArkoseLabsCaptcha,
+        friendly_captcha::FriendlyCaptcha,
+        geetest::{GeeTestV3, GeeTestV4},
+        h_captcha::HCaptcha,
+        mt_captcha::MtCaptcha,
+        recaptcha::{RecaptchaV2, RecaptchaV2Enterprise, RecaptchaV3},
+        turnstile_captcha::TurnstileStandaloneCaptcha,
+        ToBuilder,
+    },
+    cookie::{Cookie, Cookies},
+    prelude::*,
+    CaptchaTask,
+};
+
+use super::{DetectedWidget, RecaptchaVersion, Widget, DEFAULT_MIN_SCORE};
+
+/// A proxyless task built from the parameters of a [`DetectedWidget`]
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum DetectedTask<'a> {
+    RecaptchaV2(RecaptchaV2<'a>),
+
+    /// The `data-s` value, if found, is sent as the `s` field of the enterprise payload
+    RecaptchaV2Enterprise(RecaptchaV2Enterprise<'a, serde_json::Value>),
+
+    /// Requests a score of [`DEFAULT_MIN_SCORE`]
+    RecaptchaV3(RecaptchaV3<'a>),
+
+    HCaptcha(HCaptcha<'a>),
+    TurnstileStandalone(TurnstileStandaloneCaptcha<'a>),
+    FriendlyCaptcha(FriendlyCaptcha<'a>),
+    MtCaptcha(MtCaptcha<'a>),
+
+    /// The `data[blob]` value, if found, is sent as the `blob` field of the data payload
+    ArkoseLabs(ArkoseLabsCaptcha<'a, serde_json::Value>),
+
+    GeeTestV3(GeeTestV3<'a>),
+    GeeTestV4(GeeTestV4<'a>),
+    AmazonWaf(AmazonCaptcha<'a>),
+}
+
+/// A proxyless builder pre-filled with the parameters of a [`DetectedWidget`],
+/// so a proxy, user agent or other fields can be added before building the task
+pub enum DetectedBuilder<'a> {
+    RecaptchaV2(<RecaptchaV2<'a> as ToBuilder>::Builder),
+    RecaptchaV2Enterprise(<RecaptchaV2Enterprise<'a, serde_json::Value> as ToBuilder>::Builder),
+    RecaptchaV3(<RecaptchaV3<'a> as ToBuilder>::Builder),
+    HCaptcha(<HCaptcha<'a> as ToBuilder>::Builder),
+    TurnstileStandalone(<TurnstileStandaloneCaptcha<'a> as ToBuilder>::Builder),
+    FriendlyCaptcha(<FriendlyCaptcha<'a> as ToBuilder>::Builder),
+    MtCaptcha(<MtCaptcha<'a> as ToBuilder>::Builder),
+    ArkoseLabs(<ArkoseLabsCaptcha<'a, serde_json::Value> as ToBuilder>::Builder),
+    GeeTestV3(<GeeTestV3<'a> as ToBuilder>::Builder),
+    GeeTestV4(<GeeTestV4<'a> as ToBuilder>::Builder),
+    AmazonWaf(<AmazonCaptcha<'a> as ToBuilder>::Builder),
+}
+
+impl<'a> DetectedBuilder<'a> {
+    /// Builds the task
+    ///
+    /// # Errors
+    /// This method will error if the task cannot be built
+    pub fn build(self) -> Result<DetectedTask<'a>> {
+        let task = match self {
+            Self::RecaptchaV2(builder) => DetectedTask::RecaptchaV2(builder.build()?),
+            Self::RecaptchaV2Enterprise(builder) => {
+                DetectedTask::RecaptchaV2Enterprise(builder.build()?)
+            }
+            Self::RecaptchaV3(builder) => DetectedTask::RecaptchaV3(builder.build()?),
+            Self::HCaptcha(builder) => DetectedTask::HCaptcha(builder.build()?),
+            Self::TurnstileStandalone(builder) => {
+                DetectedTask::TurnstileStandalone(builder.build()?)
+            }
+            Self::FriendlyCaptcha(builder) => DetectedTask::FriendlyCaptcha(builder.build()?),
+            Self::MtCaptcha(builder) => DetectedTask::MtCaptcha(builder.build()?),
+            Self::ArkoseLabs(builder) => DetectedTask::ArkoseLabs(builder.build()?),
+            Self::GeeTestV3(builder) => DetectedTask::GeeTestV3(builder.build()?),
+            Self::GeeTestV4(builder) => DetectedTask::GeeTestV4(builder.build()?),
+            Self::AmazonWaf(builder) => DetectedTask::AmazonWaf(builder.build()?),
+        };
+
+        Ok(task)
+    }
+}
+
+/// Details of the browser session a widget was found in, which are
+/// passed on to the tasks that accept them
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct TaskContext<'a> {
+    pub(crate) user_agent: Option<&'a str>,
+    pub(crate) cookies: &'a [(String, String)],
+}
+
+impl<'a> TaskContext<'a> {
+    fn cookies(&self) -> Option<Cookies<'a>> {
+        (!self.cookies.is_empty()).then(|| {
+            self.cookies
+                .iter()
+                .map(|(name, value)| Cookie::new(name.as_str(), value.as_str()))
+                .collect()
+        })
+    }
+}
+
+impl DetectedWidget {
+    /// Builds the proxyless task that solves this widget
+    ///
+    /// # Errors
+    /// This method will error if the task cannot be built
+    pub fn to_task(&self) -> Result<DetectedTask<'_>> {
+        self.to_builder().build()
+    }
+
+    /// A builder of the proxyless task that solves this widget, with the
+    /// parameters of the widget already provided
+    pub fn to_builder(&self) -> DetectedBuilder<'_> {
+        self.builder(TaskContext::default())
+    }
+
+    pub(crate) fn build_task<'a>(&'a self, context: TaskContext<'a>) -> Result<DetectedTask<'a>> {
+        self.builder(context).build()
+    }
+
+    pub(crate) fn builder<'a>(&'a self, context: TaskContext<'a>) -> DetectedBuilder<'a> {
+        let website_url = self.website_url.as_str();
+        let website_key = self.website_key.as_str();
+        let user_agent = context.user_agent;
+
+        match &self.widget {
+            Widget::Recaptcha {
+                version: RecaptchaVersion::V3,
+                is_enterprise,
+                api_domain,
+                action,
+                ..
+            } => DetectedBuilder::RecaptchaV3(
+                RecaptchaV3::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .min_score(DEFAULT_MIN_SCORE)
+                    .page_action(action.as_deref())
+                    .is_enterprise(is_enterprise.then_some(true))
+                    .api_domain(api_domain.as_deref()),
+            ),
+            Widget::Recaptcha {
+                version: RecaptchaVersion::V2,
+                is_enterprise: true,
+                is_invisible,
+                api_domain,
+                data_s,
+                ..
+            } => DetectedBuilder::RecaptchaV2Enterprise(
+                RecaptchaV2Enterprise::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .enterprise_payload(data_s.as_ref().map(|s| json!({ "s": s })))
+                    .is_invisible(is_invisible.then_some(true))
+                    .user_agent(user_agent)
+                    .cookies(context.cookies())
+                    .api_domain(api_domain.as_deref()),
+            ),
+            Widget::Recaptcha {
+                is_invisible,
+                api_domain,
+                data_s,
+                ..
+            } => DetectedBuilder::RecaptchaV2(
+                RecaptchaV2::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .recaptcha_data_s_value(data_s.as_deref())
+                    .is_invisible(is_invisible.then_some(true))
+                    .user_agent(user_agent)
+                    .cookies(context.cookies())
+                    .api_domain(api_domain.as_deref()),
+            ),
+            Widget::HCaptcha { is_invisible } => DetectedBuilder::HCaptcha(
+                <HCaptcha>::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .is_invisible(is_invisible.then_some(true))
+                    .user_agent(user_agent),
+            ),
+            Widget::Turnstile { action, cdata } => DetectedBuilder::TurnstileStandalone(
+                TurnstileStandaloneCaptcha::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .user_agent(user_agent)
+                    .action(action.as_deref())
+                    .data(cdata.as_deref()),
+            ),
+            Widget::FriendlyCaptcha => DetectedBuilder::FriendlyCaptcha(
+                FriendlyCaptcha::builder()
+                    .website_url(website_url)
+                    .website_key(website_key),
+            ),
+            Widget::MtCaptcha => DetectedBuilder::MtCaptcha(
+                MtCaptcha::builder()
+                    .website_url(website_url)
+                    .website_key(website_key),
+            ),
+            Widget::ArkoseLabs {
+                api_subdomain,
+                data_blob,
+            } => DetectedBuilder::ArkoseLabs(
+                ArkoseLabsCaptcha::builder()
+                    .website_url(website_url)
+                    .website_public_key(website_key)
+                    .funcaptcha_api_jssubdomain(api_subdomain.as_deref())
+                    .data(data_blob.as_ref().map(|blob| json!({ "blob": blob })))
+                    .user_agent(user_agent),
+            ),
+            Widget::GeeTestV3 {
+                challenge,
+                api_server,
+            } => DetectedBuilder::GeeTestV3(
+                GeeTestV3::builder()
+                    .website_url(website_url)
+                    .gt(website_key)
+                    .challenge(challenge.as_str())
+                    .geetest_api_server_subdomain(api_server.as_deref())
+                    .user_agent(user_agent),
+            ),
+            Widget::GeeTestV4 {
+                challenge,
+                api_server,
+            } => DetectedBuilder::GeeTestV4(
+                <GeeTestV4>::builder()
+                    .website_url(website_url)
+                    .gt(website_key)
+                    .challenge(challenge.as_str())
+                    .captcha_id(website_key)
+                    .geetest_api_server_subdomain(api_server.as_deref())
+                    .user_agent(user_agent),
+            ),
+            Widget::AmazonWaf {
+                iv,
+                context,
+                challenge_script,
+                captcha_script,
+            } => DetectedBuilder::AmazonWaf(
+                AmazonCaptcha::builder()
+                    .website_url(website_url)
+                    .website_key(website_key)
+                    .iv(iv.as_str())
+                    .context(context.as_str())
+                    .challenge_script(challenge_script.as_deref())
+                    .captcha_script(captcha_script.as_deref()),
+            ),
+        }
+    }
+}
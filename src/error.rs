@@ -17,6 +17,36 @@ pub enum Error {
     #[error(transparent)]
     #[serde(serialize_with = "serialize_error")]
     TwoCaptchaError(#[from] SolveError),
+
+    #[error("A solution was required, but the solver's `callback_url` is set, so it will be sent to the callback instead")]
+    CallbackUrlSet,
+
+    #[error("The solution expires in {remaining:?}, but at least {min_remaining:?} were required")]
+    ExpiringSolution {
+        remaining: std::time::Duration,
+        min_remaining: std::time::Duration,
+    },
+
+    #[error(transparent)]
+    #[serde(serialize_with = "serialize_error")]
+    IoError(#[from] std::io::Error),
+
+    #[error("No operator answered the task within {0:?}")]
+    OperatorTimeout(std::time::Duration),
+
+    #[error(transparent)]
+    #[serde(serialize_with = "serialize_error")]
+    InvalidAnswer(#[from] crate::captcha_types::normal_captcha::AnswerViolation),
+
+    #[cfg(feature = "image")]
+    #[error(transparent)]
+    #[serde(serialize_with = "serialize_error")]
+    PreprocessError(#[from] crate::preprocess::PreprocessError),
+
+    #[cfg(feature = "middleware")]
+    #[error(transparent)]
+    #[serde(serialize_with = "serialize_error")]
+    MiddlewareError(#[from] reqwest_middleware::Error),
 }
 
 fn serialize_error<S: serde::Serializer>(
@@ -0,0 +1,95 @@
+use super::Point;
+
+/// Describes where and at which size a captcha image is rendered, allowing
+/// coordinates returned for the image to be converted into element or page
+/// coordinates, and back.
+///
+/// # Example
+/// ```
+/// use captcha_oxide::geometry::{Frame, Geometry, Point};
+///
+/// // A 300x150 image rendered at (40, 200) with a size of 150x75 CSS pixels
+/// // on a screen with a device pixel ratio of 2
+/// let frame = Frame::element((300.0, 150.0), (150.0, 75.0))
+///     .translate(40.0, 200.0)
+///     .device_pixel_ratio(2.0);
+///
+/// let click = Point::new(100.0, 50.0).to_frame(&frame);
+/// assert_eq!(click, Point::new(180.0, 450.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    /// Where the image's origin lands in the target space
+    pub origin: Point,
+
+    /// Horizontal size of an image pixel in the target space
+    pub scale_x: f64,
+
+    /// Vertical size of an image pixel in the target space
+    pub scale_y: f64,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Frame {
+    /// Leaves the coordinates untouched
+    pub const IDENTITY: Self = Self {
+        origin: Point::ORIGIN,
+        scale_x: 1.0,
+        scale_y: 1.0,
+    };
+
+    /// The coordinate space of an element displaying the image, where `natural_size`
+    /// is the size of the image sent to 2captcha and `rendered_size` is the size of
+    /// the element, in CSS pixels
+    pub fn element(natural_size: (f64, f64), rendered_size: (f64, f64)) -> Self {
+        Self {
+            origin: Point::ORIGIN,
+            scale_x: rendered_size.0 / natural_size.0,
+            scale_y: rendered_size.1 / natural_size.1,
+        }
+    }
+
+    /// The coordinate space of the page, where `element` is the position of the
+    /// element displaying the image, e.g.: the result of `getBoundingClientRect()`
+    pub fn page(natural_size: (f64, f64), rendered_size: (f64, f64), element: Point) -> Self {
+        Self::element(natural_size, rendered_size).translate(element.x, element.y)
+    }
+
+    /// Moves the frame's origin
+    pub fn translate(self, dx: f64, dy: f64) -> Self {
+        Self {
+            origin: Point::new(self.origin.x + dx, self.origin.y + dy),
+            ..self
+        }
+    }
+
+    /// Converts the frame from CSS pixels to device pixels
+    pub fn device_pixel_ratio(self, ratio: f64) -> Self {
+        Self {
+            origin: Point::new(self.origin.x * ratio, self.origin.y * ratio),
+            scale_x: self.scale_x * ratio,
+            scale_y: self.scale_y * ratio,
+        }
+    }
+
+    /// Maps a point from the image into the frame
+    pub fn to_frame(&self, point: Point) -> Point {
+        Point::new(
+            point.x * self.scale_x + self.origin.x,
+            point.y * self.scale_y + self.origin.y,
+        )
+    }
+
+    /// Maps a point from the frame into the image
+    pub fn to_image(&self, point: Point) -> Point {
+        Point::new(
+            (point.x - self.origin.x) / self.scale_x,
+            (point.y - self.origin.y) / self.scale_y,
+        )
+    }
+}
@@ -0,0 +1,93 @@
+mod frame;
+mod point;
+mod polygon;
+mod rect;
+
+pub use frame::Frame;
+pub use point::Point;
+pub use polygon::Polygon;
+pub use rect::Rect;
+
+/// Shared behavior of the geometric types returned by the coordinate based
+/// captchas, allowing them to be moved between coordinate spaces
+pub trait Geometry: Sized {
+    /// Returns a copy of `self` with `f` applied to each of its points
+    fn map_points(&self, f: impl FnMut(Point) -> Point) -> Self;
+
+    fn scale(&self, scale_x: f64, scale_y: f64) -> Self {
+        self.map_points(|point| Point::new(point.x * scale_x, point.y * scale_y))
+    }
+
+    fn translate(&self, dx: f64, dy: f64) -> Self {
+        self.map_points(|point| Point::new(point.x + dx, point.y + dy))
+    }
+
+    /// Maps `self` from the image's coordinate space into the [`Frame`]
+    fn to_frame(&self, frame: &Frame) -> Self {
+        self.map_points(|point| frame.to_frame(point))
+    }
+
+    /// Maps `self` from the [`Frame`] into the image's coordinate space
+    fn to_image(&self, frame: &Frame) -> Self {
+        self.map_points(|point| frame.to_image(point))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Frame, Geometry, Point, Polygon, Rect};
+
+    fn triangle() -> Polygon {
+        Polygon::new([
+            Point::new(0.0, 0.0),
+            Point::new(6.0, 0.0),
+            Point::new(0.0, 6.0),
+        ])
+    }
+
+    #[test]
+    fn polygon_measurements() {
+        let triangle = triangle();
+
+        assert_eq!(triangle.area(), 18.0);
+        assert_eq!(triangle.centroid(), Some(Point::new(2.0, 2.0)));
+        assert!(triangle.contains(Point::new(1.0, 1.0)));
+        assert!(!triangle.contains(Point::new(4.0, 4.0)));
+        assert_eq!(
+            triangle.bounding_box(),
+            Some(Rect::from_size(Point::ORIGIN, 6.0, 6.0))
+        );
+        assert_eq!(Polygon::default().centroid(), None);
+    }
+
+    #[test]
+    fn frame_round_trip() {
+        let frame = Frame::page((400.0, 200.0), (200.0, 100.0), Point::new(10.0, 20.0))
+            .device_pixel_ratio(1.5);
+
+        let rect = Rect::from_size(Point::new(100.0, 100.0), 50.0, 20.0);
+        let mapped = rect.to_frame(&frame);
+
+        assert_eq!(mapped.min(), Point::new(90.0, 105.0));
+        assert_eq!(mapped.width(), 37.5);
+        assert_eq!(mapped.to_image(&frame), rect);
+        assert_eq!(triangle().scale(2.0, 2.0).translate(1.0, 0.0).area(), 72.0);
+    }
+
+    #[test]
+    fn serde() -> Result<(), serde_json::Error> {
+        let json = r#"[[{"x":1,"y":2},{"x":3,"y":4}]]"#;
+        let canvas: Vec<Polygon> = serde_json::from_str(json)?;
+
+        assert_eq!(canvas[0].points[1], Point::new(3.0, 4.0));
+        assert_eq!(
+            serde_json::to_string(&canvas)?,
+            r#"[[{"x":1.0,"y":2.0},{"x":3.0,"y":4.0}]]"#
+        );
+
+        let rect: Rect = serde_json::from_str(r#"{"xMin":1,"yMin":2,"xMax":3,"yMax":4}"#)?;
+        assert_eq!(rect.center(), Point::new(2.0, 3.0));
+
+        Ok(())
+    }
+}
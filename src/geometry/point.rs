@@ -0,0 +1,40 @@
+use super::Geometry;
+
+/// A point in a 2D coordinate space. When returned by 2captcha, the
+/// coordinates are measured in pixels from the top-left corner of the image
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0.0, 0.0);
+
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// The euclidean distance between two points
+    pub fn distance(&self, other: &Point) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+impl Geometry for Point {
+    fn map_points(&self, mut f: impl FnMut(Point) -> Point) -> Self {
+        f(*self)
+    }
+}
+
+impl From<(f64, f64)> for Point {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Point> for (f64, f64) {
+    fn from(value: Point) -> Self {
+        (value.x, value.y)
+    }
+}
@@ -0,0 +1,114 @@
+use super::{Geometry, Point, Rect};
+
+/// A closed polygon, such as the outline of an object drawn by a worker.
+/// The last point is implicitly connected to the first one
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Polygon {
+    pub points: Box<[Point]>,
+}
+
+impl Polygon {
+    pub fn new(points: impl Into<Box<[Point]>>) -> Self {
+        Self {
+            points: points.into(),
+        }
+    }
+
+    /// Iterates over the edges of the polygon, including the closing one
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.points
+            .iter()
+            .copied()
+            .zip(self.points.iter().copied().cycle().skip(1))
+    }
+
+    /// The shoelace formula, positive for counter-clockwise
+    /// polygons in a y-up coordinate space
+    fn signed_area(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f64>()
+            / 2.0
+    }
+
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+
+    /// The center of mass of the polygon's area. Degenerate polygons
+    /// (with no area) fall back to the average of their points.
+    /// Returns [`None`] if the polygon has no points
+    pub fn centroid(&self) -> Option<Point> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let area = self.signed_area();
+
+        if area.abs() < f64::EPSILON {
+            let count = self.points.len() as f64;
+            let (x, y) = self
+                .points
+                .iter()
+                .fold((0.0, 0.0), |(x, y), point| (x + point.x, y + point.y));
+
+            return Some(Point::new(x / count, y / count));
+        }
+
+        let (x, y) = self.edges().fold((0.0, 0.0), |(x, y), (a, b)| {
+            let cross = a.x * b.y - b.x * a.y;
+            (x + (a.x + b.x) * cross, y + (a.y + b.y) * cross)
+        });
+
+        Some(Point::new(x / (6.0 * area), y / (6.0 * area)))
+    }
+
+    /// Checks if the point lies inside the polygon using the even-odd rule
+    pub fn contains(&self, point: Point) -> bool {
+        self.edges()
+            .filter(|(a, b)| (a.y > point.y) != (b.y > point.y))
+            .filter(|(a, b)| point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x)
+            .count()
+            % 2
+            == 1
+    }
+
+    /// The smallest [`Rect`] containing every point of the polygon.
+    /// Returns [`None`] if the polygon has no points
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let (first, rest) = self.points.split_first()?;
+
+        Some(
+            rest.iter()
+                .fold(Rect::from_corners(*first, *first), |rect, point| Rect {
+                    x_min: rect.x_min.min(point.x),
+                    y_min: rect.y_min.min(point.y),
+                    x_max: rect.x_max.max(point.x),
+                    y_max: rect.y_max.max(point.y),
+                }),
+        )
+    }
+}
+
+impl Geometry for Polygon {
+    fn map_points(&self, f: impl FnMut(Point) -> Point) -> Self {
+        Self {
+            points: self.points.iter().copied().map(f).collect(),
+        }
+    }
+}
+
+impl From<Rect> for Polygon {
+    fn from(value: Rect) -> Self {
+        value.to_polygon()
+    }
+}
+
+impl FromIterator<Point> for Polygon {
+    fn from_iter<T: IntoIterator<Item = Point>>(iter: T) -> Self {
+        Self {
+            points: iter.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Geometry, Point, Polygon};
+
+/// An axis-aligned rectangle, represented by its top-left (`min`) and
+/// bottom-right (`max`) corners
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rect {
+    pub x_min: f64,
+    pub y_min: f64,
+    pub x_max: f64,
+    pub y_max: f64,
+}
+
+impl Rect {
+    /// Creates a rectangle from two opposite corners, in any order
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        Self {
+            x_min: a.x.min(b.x),
+            y_min: a.y.min(b.y),
+            x_max: a.x.max(b.x),
+            y_max: a.y.max(b.y),
+        }
+    }
+
+    /// Creates a rectangle from its top-left corner and its size
+    pub fn from_size(origin: Point, width: f64, height: f64) -> Self {
+        Self::from_corners(origin, Point::new(origin.x + width, origin.y + height))
+    }
+
+    pub const fn min(&self) -> Point {
+        Point::new(self.x_min, self.y_min)
+    }
+
+    pub const fn max(&self) -> Point {
+        Point::new(self.x_max, self.y_max)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.x_min + self.x_max) / 2.0,
+            (self.y_min + self.y_max) / 2.0,
+        )
+    }
+
+    /// Checks if the point lies inside the rectangle or on its edges
+    pub fn contains(&self, point: Point) -> bool {
+        (self.x_min..=self.x_max).contains(&point.x) && (self.y_min..=self.y_max).contains(&point.y)
+    }
+
+    /// The corners of the rectangle, clockwise from the top-left one
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new([
+            self.min(),
+            Point::new(self.x_max, self.y_min),
+            self.max(),
+            Point::new(self.x_min, self.y_max),
+        ])
+    }
+}
+
+impl Geometry for Rect {
+    fn map_points(&self, mut f: impl FnMut(Point) -> Point) -> Self {
+        Self::from_corners(f(self.min()), f(self.max()))
+    }
+}
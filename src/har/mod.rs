@@ -0,0 +1,774 @@
+//! Extraction of captcha tasks from HTTP Archive (HAR) files
+//!
+//! Browsers can export the traffic of a session as a HAR file. [`Har::findings`]
+//! scans it for the requests each captcha vendor makes and the pages that
+//! embed their widgets, and collects the parameters, user agent and cookies
+//! needed to solve them, together with the entries they were found in.
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{detect::DetectedBuilder, har::Har, proxy::Proxy};
+//!
+//! # fn main() -> Result<(), captcha_oxide::Error> {
+//! let har = Har::from_json(&std::fs::read_to_string("session.har").unwrap())?;
+//! let proxy: Option<Proxy> = None; // The proxy the session was recorded through
+//!
+//! for finding in har.findings() {
+//!     for evidence in &finding.evidence {
+//!         println!("{}: {} {}", evidence.description, evidence.method, evidence.url);
+//!     }
+//!
+//!     if let DetectedBuilder::HCaptcha(builder) = finding.to_builder() {
+//!         let task = builder.proxy(proxy.clone()).build()?;
+//!         // solver.solve(task).await?
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod model;
+
+pub use model::*;
+
+use std::collections::HashSet;
+
+use url::Url;
+
+use crate::{
+    detect::{
+        self, api_domain, arkose_subdomain, fill, is_amazon_waf_host, is_arkose_host,
+        is_recaptcha_host, query, DetectedBuilder, DetectedWidget, RecaptchaVersion, TaskContext,
+        Vendor, Widget,
+    },
+    prelude::*,
+};
+
+/// An entry of the HAR file that contributed to a [`HarFinding`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Evidence {
+    /// The index of the entry in the HAR file
+    pub entry: usize,
+    pub method: String,
+    pub url: String,
+
+    /// What was found in the entry
+    pub description: &'static str,
+}
+
+/// A captcha found in a HAR file
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarFinding {
+    pub widget: DetectedWidget,
+
+    /// The user agent of the browser that recorded the session
+    pub user_agent: Option<String>,
+
+    /// The cookies sent with the first request the captcha was found in
+    pub cookies: Vec<(String, String)>,
+
+    pub evidence: Vec<Evidence>,
+}
+
+impl HarFinding {
+    pub const fn vendor(&self) -> Vendor {
+        self.widget.vendor()
+    }
+
+    /// A builder of the proxyless task that solves this captcha, pre-filled
+    /// with its parameters and with the user agent and cookies when the task
+    /// accepts them, so a proxy or other fields can be added before building it
+    pub fn to_builder(&self) -> DetectedBuilder<'_> {
+        self.widget.builder(TaskContext {
+            user_agent: self.user_agent.as_deref(),
+            cookies: &self.cookies,
+        })
+    }
+}
+
+impl Har {
+    /// Parses the contents of a HAR file
+    ///
+    /// # Errors
+    /// This method will error if the file is not valid JSON or isn't a HAR file
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Finds the captchas in the recorded session, in the order they first appear.
+    /// Captchas seen in more than one entry are only returned once, with all
+    /// the entries listed in [`HarFinding::evidence`]
+    pub fn findings(&self) -> Vec<HarFinding> {
+        let entries = &self.log.entries;
+
+        let mut scanner = Scanner {
+            document: entries
+                .iter()
+                .find(|entry| entry.response.content.is_html())
+                .and_then(|entry| entry.request.parsed_url()),
+            recaptcha_v3_keys: entries
+                .iter()
+                .filter_map(|entry| recaptcha_script(&entry.request.parsed_url()?))
+                .filter_map(|(_, key)| key)
+                .collect(),
+            findings: Vec::new(),
+            scripts: Vec::new(),
+        };
+
+        for (index, entry) in entries.iter().enumerate() {
+            scanner.entry(index, entry);
+        }
+
+        scanner.finish()
+    }
+}
+
+/// A script that applies to every captcha of a vendor, regardless of its key
+struct VendorScript {
+    vendor: Vendor,
+    evidence: Evidence,
+    url: Url,
+}
+
+struct Scanner {
+    /// The first HTML page of the session, used when requests don't
+    /// reveal the page they were made from
+    document: Option<Url>,
+    recaptcha_v3_keys: HashSet<String>,
+    findings: Vec<HarFinding>,
+    scripts: Vec<VendorScript>,
+}
+
+impl Scanner {
+    fn entry(&mut self, index: usize, entry: &Entry) {
+        if entry.response.content.is_html() {
+            self.document(index, entry);
+        }
+
+        let Some(url) = entry.request.parsed_url() else {
+            return;
+        };
+
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+
+        if is_recaptcha_host(host) {
+            self.recaptcha(index, entry, &url);
+        } else if host == "hcaptcha.com" || host.ends_with(".hcaptcha.com") {
+            self.h_captcha(index, entry, &url);
+        } else if host == "geetest.com" || host.ends_with(".geetest.com") {
+            self.geetest(index, entry, &url);
+        } else if is_arkose_host(host) && path.starts_with("/fc/gt2/public_key/") {
+            self.arkose_labs(index, entry, &url);
+        } else if is_amazon_waf_host(host)
+            && (path.ends_with("/challenge.js") || path.ends_with("/captcha.js"))
+        {
+            self.script(index, entry, url, Vendor::AmazonWaf, "Amazon WAF script");
+        } else if host == "challenges.cloudflare.com" {
+            self.turnstile(index, entry, url);
+        }
+
+        self.geetest_register(index, entry);
+    }
+
+    fn add(
+        &mut self,
+        index: usize,
+        entry: &Entry,
+        widget: DetectedWidget,
+        description: &'static str,
+    ) -> &mut HarFinding {
+        let evidence = evidence(index, entry, description);
+
+        let position = self.findings.iter().position(|x| x.widget.is_same(&widget));
+
+        let finding = match position {
+            Some(position) => {
+                let finding = &mut self.findings[position];
+                finding.widget.merge(widget);
+                finding
+            }
+            None => {
+                self.findings.push(HarFinding {
+                    widget,
+                    user_agent: None,
+                    cookies: entry
+                        .request
+                        .cookies
+                        .iter()
+                        .map(|x| (x.name.clone(), x.value.clone()))
+                        .collect(),
+                    evidence: Vec::new(),
+                });
+
+                self.findings.last_mut().expect("A finding was just pushed")
+            }
+        };
+
+        fill(
+            &mut finding.user_agent,
+            entry.request.header("user-agent").map(str::to_owned),
+        );
+
+        if !finding.evidence.contains(&evidence) {
+            finding.evidence.push(evidence);
+        }
+
+        finding
+    }
+
+    /// The page a request was made from
+    fn page_url(&self, entry: &Entry) -> Option<Url> {
+        let referer = entry
+            .request
+            .header("referer")
+            .and_then(|x| Url::parse(x).ok());
+
+        match (referer, &self.document) {
+            // Referrer policies often strip the path, the page is more precise
+            (Some(referer), Some(document))
+                if referer.path() == "/" && referer.origin() == document.origin() =>
+            {
+                Some(document.clone())
+            }
+            (Some(referer), _) => Some(referer),
+            (None, document) => document.clone(),
+        }
+    }
+
+    fn document(&mut self, index: usize, entry: &Entry) {
+        let Some(html) = entry.response.content.text() else {
+            return;
+        };
+
+        for widget in detect::detect(html, &entry.request.url).unwrap_or_default() {
+            self.add(index, entry, widget, "Widget embedded in the page");
+        }
+    }
+
+    fn widget(
+        &mut self,
+        index: usize,
+        entry: &Entry,
+        key: &str,
+        widget: Widget,
+        description: &'static str,
+    ) {
+        let Some(website_url) = self.page_url(entry) else {
+            return;
+        };
+
+        let widget = DetectedWidget {
+            website_url,
+            website_key: key.to_owned(),
+            widget,
+        };
+
+        self.add(index, entry, widget, description);
+    }
+
+    fn recaptcha(&mut self, index: usize, entry: &Entry, url: &Url) {
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+
+        if let Some((is_enterprise, Some(key))) = recaptcha_script(url) {
+            let widget = Widget::Recaptcha {
+                version: RecaptchaVersion::V3,
+                is_enterprise,
+                is_invisible: false,
+                api_domain: api_domain(host),
+                data_s: None,
+                action: None,
+            };
+
+            return self.widget(index, entry, &key, widget, "reCAPTCHA V3 script");
+        }
+
+        let description = match path.rsplit('/').next() {
+            Some("anchor") => "reCAPTCHA anchor",
+            Some("reload") => "reCAPTCHA reload",
+            _ => return,
+        };
+
+        let Some(key) = query(url, "k") else {
+            return;
+        };
+
+        let version = match self.recaptcha_v3_keys.contains(&key) {
+            true => RecaptchaVersion::V3,
+            false => RecaptchaVersion::V2,
+        };
+
+        let widget = Widget::Recaptcha {
+            version,
+            is_enterprise: path.contains("/enterprise/"),
+            is_invisible: version == RecaptchaVersion::V2
+                && query(url, "size").as_deref() == Some("invisible"),
+            api_domain: api_domain(host),
+            data_s: None,
+            action: None,
+        };
+
+        self.widget(index, entry, &key, widget, description);
+    }
+
+    fn h_captcha(&mut self, index: usize, entry: &Entry, url: &Url) {
+        let path = url.path();
+
+        let description = if path.starts_with("/getcaptcha") {
+            "hCaptcha getcaptcha"
+        } else if path.starts_with("/checksiteconfig") {
+            "hCaptcha checksiteconfig"
+        } else {
+            return;
+        };
+
+        let param = |name: &str| {
+            query(url, name).or_else(|| entry.request.form_param(name).map(|x| x.into_owned()))
+        };
+
+        let key = param("sitekey").or_else(|| {
+            path.strip_prefix("/getcaptcha/")
+                .filter(|key| !key.is_empty())
+                .map(str::to_owned)
+        });
+
+        let Some(key) = key else {
+            return;
+        };
+
+        // hCaptcha reports the page's host instead of its URL
+        let page_url = match (param("host"), &self.document) {
+            (Some(host), Some(document)) if document.host_str() == Some(host.as_str()) => {
+                Some(document.clone())
+            }
+            (Some(host), _) => Url::parse(&format!("https://{host}/")).ok(),
+            (None, _) => self.page_url(entry),
+        };
+
+        let Some(website_url) = page_url else {
+            return;
+        };
+
+        let widget = DetectedWidget {
+            website_url,
+            website_key: key,
+            widget: Widget::HCaptcha {
+                is_invisible: false,
+            },
+        };
+
+        self.add(index, entry, widget, description);
+    }
+
+    fn geetest(&mut self, index: usize, entry: &Entry, url: &Url) {
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+
+        if path == "/load" {
+            let (Some(captcha_id), Some(challenge)) =
+                (query(url, "captcha_id"), query(url, "challenge"))
+            else {
+                return;
+            };
+
+            let widget = Widget::GeeTestV4 {
+                challenge,
+                api_server: (host != "gcaptcha4.geetest.com").then(|| host.to_owned()),
+            };
+
+            return self.widget(index, entry, &captcha_id, widget, "GeeTest V4 load");
+        }
+
+        if matches!(path, "/get.php" | "/ajax.php") {
+            let (Some(gt), Some(challenge)) = (query(url, "gt"), query(url, "challenge")) else {
+                return;
+            };
+
+            let widget = Widget::GeeTestV3 {
+                challenge,
+                api_server: (host != "api.geetest.com").then(|| host.to_owned()),
+            };
+
+            self.widget(index, entry, &gt, widget, "GeeTest V3 request");
+        }
+    }
+
+    /// Sites that use GeeTest V3 request the `gt` and `challenge` values from their
+    /// own `register` endpoint, which can be identified by its response
+    fn geetest_register(&mut self, index: usize, entry: &Entry) {
+        #[derive(serde::Deserialize)]
+        struct Register {
+            gt: String,
+            challenge: String,
+        }
+
+        let register = entry
+            .response
+            .content
+            .text()
+            .filter(|text| text.contains("\"challenge\""))
+            .and_then(|text| serde_json::from_str::<Register>(text).ok());
+
+        if let Some(Register { gt, challenge }) = register {
+            let widget = Widget::GeeTestV3 {
+                challenge,
+                api_server: None,
+            };
+
+            self.widget(index, entry, &gt, widget, "GeeTest V3 register");
+        }
+    }
+
+    fn arkose_labs(&mut self, index: usize, entry: &Entry, url: &Url) {
+        let request = &entry.request;
+
+        let key = request
+            .form_param("public_key")
+            .map(|x| x.into_owned())
+            .or_else(|| url.path_segments()?.next_back().map(str::to_owned));
+
+        let website_url = request
+            .form_param("site")
+            .and_then(|x| Url::parse(&x).ok())
+            .or_else(|| self.page_url(entry));
+
+        let (Some(key), Some(website_url)) = (key, website_url) else {
+            return;
+        };
+
+        let widget = DetectedWidget {
+            website_url,
+            website_key: key,
+            widget: Widget::ArkoseLabs {
+                api_subdomain: arkose_subdomain(url.host_str().unwrap_or_default()),
+                data_blob: request.form_param("data[blob]").map(|x| x.into_owned()),
+            },
+        };
+
+        let finding = self.add(index, entry, widget, "Arkose Labs fc/gt2");
+
+        // Arkose Labs validates the token against the user agent reported here
+        if let Some(user_agent) = request.form_param("userbrowser") {
+            finding.user_agent = Some(user_agent.into_owned());
+        }
+    }
+
+    fn turnstile(&mut self, index: usize, entry: &Entry, url: Url) {
+        let path = url.path();
+
+        if path.starts_with("/turnstile/") && path.ends_with("/api.js") {
+            return self.script(index, entry, url, Vendor::Turnstile, "Turnstile script");
+        }
+
+        // The sitekey is one of the segments of the iframe's path
+        let key = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .find(|segment| segment.starts_with("0x") && segment.len() > 10)
+            .map(str::to_owned);
+
+        if let Some(key) = key.filter(|_| path.contains("/turnstile/")) {
+            let widget = Widget::Turnstile {
+                action: None,
+                cdata: None,
+            };
+
+            self.widget(index, entry, &key, widget, "Turnstile iframe");
+        }
+    }
+
+    fn script(
+        &mut self,
+        index: usize,
+        entry: &Entry,
+        url: Url,
+        vendor: Vendor,
+        description: &'static str,
+    ) {
+        self.scripts.push(VendorScript {
+            vendor,
+            evidence: evidence(index, entry, description),
+            url,
+        });
+    }
+
+    /// Attaches the scripts to the captchas of their vendors, as
+    /// they may be loaded before the captcha is found
+    fn finish(mut self) -> Vec<HarFinding> {
+        for script in self.scripts {
+            for finding in &mut self.findings {
+                if finding.vendor() != script.vendor {
+                    continue;
+                }
+
+                if let Widget::AmazonWaf {
+                    challenge_script,
+                    captcha_script,
+                    ..
+                } = &mut finding.widget.widget
+                {
+                    let src = Some(script.url.to_string());
+
+                    match script.url.path().ends_with("/challenge.js") {
+                        true => fill(challenge_script, src),
+                        false => fill(captcha_script, src),
+                    }
+                }
+
+                if !finding.evidence.contains(&script.evidence) {
+                    finding.evidence.push(script.evidence.clone());
+                }
+            }
+        }
+
+        for finding in &mut self.findings {
+            finding.evidence.sort_by_key(|x| x.entry);
+        }
+
+        self.findings
+    }
+}
+
+fn evidence(index: usize, entry: &Entry, description: &'static str) -> Evidence {
+    Evidence {
+        entry: index,
+        method: entry.request.method.clone(),
+        url: entry.request.url.clone(),
+        description,
+    }
+}
+
+/// Whether the URL is that of the reCAPTCHA script, and if so, whether it is the
+/// enterprise version and the V3 sitekey passed in its `render` parameter
+fn recaptcha_script(url: &Url) -> Option<(bool, Option<String>)> {
+    let is_enterprise = match url.path() {
+        "/recaptcha/api.js" => false,
+        "/recaptcha/enterprise.js" => true,
+        _ => return None,
+    };
+
+    if !is_recaptcha_host(url.host_str()?) {
+        return None;
+    }
+
+    let key = query(url, "render").filter(|x| !matches!(x.as_str(), "explicit" | "onload"));
+    Some((is_enterprise, key))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::Har;
+    use crate::{
+        detect::{DetectedBuilder, DetectedTask, RecaptchaVersion, Vendor, Widget},
+        proxy::{Address, Proxy, ProxyType},
+    };
+
+    const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) Chrome/120.0.0.0 Safari/537.36";
+
+    fn entry(method: &str, url: &str, referer: Option<&str>, mime_type: &str, text: &str) -> Value {
+        let mut headers = vec![json!({ "name": "User-Agent", "value": USER_AGENT })];
+        if let Some(referer) = referer {
+            headers.push(json!({ "name": "Referer", "value": referer }));
+        }
+
+        json!({
+            "startedDateTime": "2024-01-01T00:00:00.000Z",
+            "request": {
+                "method": method,
+                "url": url,
+                "headers": headers,
+                "cookies": [{ "name": "session", "value": "abc" }],
+            },
+            "response": {
+                "status": 200,
+                "headers": [],
+                "content": { "mimeType": mime_type, "text": text },
+            },
+        })
+    }
+
+    fn har(entries: Vec<Value>) -> Har {
+        Har::from_json(&json!({ "log": { "entries": entries } }).to_string()).unwrap()
+    }
+
+    #[test]
+    fn finds_recaptcha_and_hcaptcha() {
+        let page = "https://example.com/login";
+
+        let har = har(vec![
+            entry("GET", page, None, "text/html", "<html><body>Login</body></html>"),
+            entry(
+                "GET",
+                "https://www.google.com/recaptcha/api.js?render=6LcR_okUAAAAAPYrPe-HK_0RULO1aZM15ENyM-Mf",
+                Some(page),
+                "text/javascript",
+                "",
+            ),
+            entry(
+                "GET",
+                "https://www.google.com/recaptcha/api2/anchor?ar=1&k=6LcR_okUAAAAAPYrPe-HK_0RULO1aZM15ENyM-Mf&size=invisible",
+                Some("https://example.com/"),
+                "text/html",
+                "",
+            ),
+            entry(
+                "POST",
+                "https://api.hcaptcha.com/getcaptcha/a5f74b19-9e45-40e0-b45d-47ff91b7a6c2",
+                Some("https://newassets.hcaptcha.com/"),
+                "application/json",
+                "{}",
+            ),
+        ]);
+
+        let findings = har.findings();
+        assert_eq!(findings.len(), 2);
+
+        let recaptcha = &findings[0];
+        assert_eq!(recaptcha.widget.website_url.as_str(), page);
+        assert!(matches!(
+            recaptcha.widget.widget,
+            Widget::Recaptcha {
+                version: RecaptchaVersion::V3,
+                ..
+            }
+        ));
+        assert_eq!(
+            recaptcha
+                .evidence
+                .iter()
+                .map(|x| x.entry)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(recaptcha.user_agent.as_deref(), Some(USER_AGENT));
+        assert!(matches!(
+            recaptcha.to_builder().build().unwrap(),
+            DetectedTask::RecaptchaV3(_)
+        ));
+
+        // The host of the page is missing, so the page found in the session is used
+        let h_captcha = &findings[1];
+        assert_eq!(h_captcha.vendor(), Vendor::HCaptcha);
+        assert_eq!(
+            h_captcha.widget.website_key,
+            "a5f74b19-9e45-40e0-b45d-47ff91b7a6c2"
+        );
+
+        // The builder is returned so a proxy can be added before building the task
+        let DetectedBuilder::HCaptcha(builder) = h_captcha.to_builder() else {
+            panic!("An hCaptcha builder was expected");
+        };
+        let task = builder
+            .proxy(Some(Proxy {
+                proxy_type: ProxyType::Http,
+                proxy_address: Address::HostName("proxy.example.com".into()),
+                proxy_port: "8080".into(),
+                proxy_login: None,
+                proxy_password: None,
+            }))
+            .build()
+            .unwrap();
+        let task = serde_json::to_value(task).unwrap();
+        assert_eq!(task["type"], "HCaptchaTask");
+        assert_eq!(task["userAgent"], USER_AGENT);
+    }
+
+    #[test]
+    fn finds_geetest_arkose_and_amazon() {
+        let page = "https://shop.example.com/checkout";
+        let html = r#"
+            <script>
+                window.gokuProps = { "key": "AQIDAHjcYu", "iv": "CgAFRjIw2vAAABSM", "context": "zPT0jOl1rQ" };
+            </script>
+        "#;
+
+        let mut arkose = entry(
+            "POST",
+            "https://example-api.arkoselabs.com/fc/gt2/public_key/476068BF-9607-4799-B53D-966BE98E2B81",
+            Some(page),
+            "application/json",
+            "{}",
+        );
+        arkose["request"]["postData"] = json!({
+            "mimeType": "application/x-www-form-urlencoded",
+            "text": "public_key=476068BF-9607-4799-B53D-966BE98E2B81&site=https%3A%2F%2Fshop.example.com&userbrowser=Custom%20Agent&data%5Bblob%5D=xyz",
+        });
+
+        let har = har(vec![
+            entry(
+                "GET",
+                "https://ab12cd34ef56.us-east-1.token.awswaf.com/ab12cd34ef56/e9d1bd6e2f1f/challenge.js",
+                Some(page),
+                "text/javascript",
+                "",
+            ),
+            entry("GET", page, None, "text/html", html),
+            entry(
+                "GET",
+                "https://shop.example.com/api/geetest/register?t=1700000000",
+                Some(page),
+                "application/json",
+                r#"{"success":1,"challenge":"2e2f0f65240058b683cb6ea21c303eea","gt":"81388ea1fc187e0c335c0a8907ff2625"}"#,
+            ),
+            arkose,
+            // Only geetest.com and its subdomains are GeeTest hosts
+            entry(
+                "GET",
+                "https://notgeetest.com/load?captcha_id=e392e1d7fd421dc63325744d5a2b9c73&challenge=abc",
+                Some(page),
+                "application/javascript",
+                "",
+            ),
+        ]);
+
+        let findings = har.findings();
+        let vendors = findings.iter().map(|x| x.vendor()).collect::<Vec<_>>();
+        assert_eq!(
+            vendors,
+            [Vendor::AmazonWaf, Vendor::GeeTest, Vendor::ArkoseLabs]
+        );
+
+        assert!(matches!(
+            &findings[0].widget.widget,
+            Widget::AmazonWaf {
+                challenge_script: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            findings[0]
+                .evidence
+                .iter()
+                .map(|x| x.entry)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        assert_eq!(
+            findings[1].widget.website_key,
+            "81388ea1fc187e0c335c0a8907ff2625"
+        );
+
+        assert_eq!(findings[2].user_agent.as_deref(), Some("Custom Agent"));
+        assert_eq!(
+            findings[2].widget.website_url.as_str(),
+            "https://shop.example.com/"
+        );
+        assert_eq!(
+            findings[2].widget.widget,
+            Widget::ArkoseLabs {
+                api_subdomain: Some("example-api.arkoselabs.com".into()),
+                data_blob: Some("xyz".into()),
+            }
+        );
+
+        for finding in &findings {
+            finding.to_builder().build().unwrap();
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+use url::Url;
+
+/// The parts of an HTTP Archive (HAR) file used to find captcha traffic.
+/// Every other field of the file is ignored
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Har {
+    pub log: Log,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Log {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+/// A request and the response it received
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    #[serde(default)]
+    pub started_date_time: String,
+    pub request: Request,
+    pub response: Response,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+
+    #[serde(default)]
+    pub cookies: Vec<NameValue>,
+
+    pub post_data: Option<PostData>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostData {
+    #[serde(default)]
+    pub mime_type: String,
+
+    pub text: Option<String>,
+
+    #[serde(default)]
+    pub params: Vec<NameValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Response {
+    pub status: i32,
+
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+
+    pub content: Content,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    #[serde(default)]
+    pub mime_type: String,
+
+    pub text: Option<String>,
+
+    /// `base64` if the body is binary, in which case it is
+    /// ignored when looking for captchas
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+impl Request {
+    /// The value of a header, ignoring the case of its name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find(&self.headers, name)
+    }
+
+    /// The value of a field of a URL encoded form body
+    pub fn form_param(&self, name: &str) -> Option<Cow<'_, str>> {
+        let post_data = self.post_data.as_ref()?;
+
+        if let Some(param) = post_data.params.iter().find(|x| x.name == name) {
+            return Some(Cow::Borrowed(&param.value));
+        }
+
+        url::form_urlencoded::parse(post_data.text.as_deref()?.as_bytes())
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    pub(super) fn parsed_url(&self) -> Option<Url> {
+        Url::parse(&self.url).ok()
+    }
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find(&self.headers, name)
+    }
+}
+
+impl Content {
+    /// The body of the response, unless it was recorded as binary
+    pub fn text(&self) -> Option<&str> {
+        match self.encoding.as_deref() {
+            Some("base64") => None,
+            _ => self.text.as_deref(),
+        }
+    }
+
+    pub fn is_html(&self) -> bool {
+        self.mime_type.starts_with("text/html")
+    }
+}
+
+fn find<'a>(values: &'a [NameValue], name: &str) -> Option<&'a str> {
+    values
+        .iter()
+        .find(|x| x.name.eq_ignore_ascii_case(name))
+        .map(|x| x.value.as_str())
+}
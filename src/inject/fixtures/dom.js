@@ -0,0 +1,181 @@
+// Just enough of a browser to run the injection scripts against the fixtures.
+// `load` turns the opening tags of a page into elements, every element being
+// a child of the form it appears in, and `spy` records the calls made to a
+// function of the page.
+
+var window = globalThis;
+var elements = [];
+var calls = [];
+
+function Element(tagName, attributes) {
+  var self = this;
+  this.tagName = tagName.toUpperCase();
+  this.attributes = attributes;
+  this.form = null;
+  this.value = attributes.value || "";
+  this.innerHTML = "";
+
+  ["id", "name", "type"].forEach(function (name) {
+    Object.defineProperty(self, name, {
+      get: function () {
+        return self.getAttribute(name);
+      },
+      set: function (value) {
+        self.attributes[name] = String(value);
+      },
+    });
+  });
+}
+
+Element.prototype.getAttribute = function (name) {
+  return Object.prototype.hasOwnProperty.call(this.attributes, name) ? this.attributes[name] : null;
+};
+
+Element.prototype.closest = function (selector) {
+  if (matches(this, selector)) {
+    return this;
+  }
+  return this.form && this.form.closest(selector);
+};
+
+Element.prototype.appendChild = function (child) {
+  child.form = this;
+  elements.push(child);
+  return child;
+};
+
+Element.prototype.submit = function () {
+  this.submitted = true;
+};
+
+function matches(element, selector) {
+  return selector.split(",").some(function (selector) {
+    selector = selector.trim();
+    var classes = (element.getAttribute("class") || "").split(/\s+/);
+    var attribute = /^\[([\w-]+)(?:(\*?)=["']?([^"'\]]*)["']?)?\]$/.exec(selector);
+
+    if (selector[0] === ".") {
+      return classes.indexOf(selector.slice(1)) >= 0;
+    }
+    if (selector[0] === "#") {
+      return element.id === selector.slice(1);
+    }
+    if (attribute) {
+      var value = element.getAttribute(attribute[1]);
+      if (value === null || attribute[3] === undefined) {
+        return value !== null;
+      }
+      return attribute[2] ? value.indexOf(attribute[3]) >= 0 : value === attribute[3];
+    }
+    return element.tagName === selector.toUpperCase();
+  });
+}
+
+var document = {
+  get forms() {
+    return this.querySelectorAll("form");
+  },
+  querySelector: function (selector) {
+    return this.querySelectorAll(selector)[0] || null;
+  },
+  querySelectorAll: function (selector) {
+    return elements.filter(function (element) {
+      return matches(element, selector);
+    });
+  },
+  getElementById: function (id) {
+    return this.querySelector("#" + id);
+  },
+  createElement: function (tagName) {
+    return new Element(tagName, {});
+  },
+};
+
+function load(html) {
+  var tag = /<(\/?)([a-z][a-z0-9-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>/gi;
+  var attribute = /([^\s"'<>\/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?/g;
+  var form = null;
+  var match;
+
+  while ((match = tag.exec(html))) {
+    if (match[1]) {
+      form = match[2].toLowerCase() === "form" ? null : form;
+      continue;
+    }
+
+    var attributes = {};
+    var pair;
+    while ((pair = attribute.exec(match[3]))) {
+      attributes[pair[1].toLowerCase()] = pair[2] || pair[3] || pair[4] || "";
+    }
+
+    var element = new Element(match[2], attributes);
+    if (form) {
+      form.appendChild(element);
+    } else {
+      elements.push(element);
+    }
+    if (element.tagName === "FORM") {
+      form = element;
+    }
+  }
+}
+
+function spy(path) {
+  var keys = path.split(".");
+  var key = keys.pop();
+  var object = keys.reduce(function (object, key) {
+    return object[key];
+  }, window);
+  var original = object[key];
+
+  object[key] = function (argument) {
+    calls.push([path, argument]);
+    return original.apply(this, arguments);
+  };
+}
+
+function report() {
+  var fields = {};
+  elements.forEach(function (element) {
+    if (element.name) {
+      (fields[element.name] = fields[element.name] || []).push(element.value);
+    }
+  });
+
+  return {
+    calls: calls,
+    fields: fields,
+    submitted: document.forms.some(function (form) {
+      return form.submitted;
+    }),
+  };
+}
+
+var stub = function () {
+  return {
+    render: function () {},
+    getResponse: function () {
+      return "";
+    },
+  };
+};
+
+var grecaptcha = stub();
+var hcaptcha = stub();
+var turnstile = stub();
+
+function initGeetest4(config, callback) {
+  window.geetest = {
+    appendTo: function () {},
+    onSuccess: function () {},
+    getValidate: function () {
+      return false;
+    },
+  };
+  callback(window.geetest);
+}
+
+function ArkoseEnforcement(options) {
+  this.options = options;
+}
@@ -0,0 +1,552 @@
+//! JavaScript snippets that put a solution into a page driven by a browser
+//!
+//! [`InjectSolution::injection`] describes how the solution of a token based
+//! captcha is handed to the page: the response fields of the widget are set,
+//! creating hidden inputs where the page expects them, the `getResponse` or
+//! `getValidate` method of the widget's API is made to return the solution
+//! and the callback of the widget is invoked. [`Injection::script`] turns it
+//! into a self-contained snippet to run with the automation tool of choice.
+//!
+//! The callback can be named with [`Injection::callback`], found in the HTML
+//! of the page with [`Injection::find_callback`] or looked up by the script
+//! itself with [`Injection::discover_callback`], e.g.: through the
+//! `___grecaptcha_cfg` object reCAPTCHA keeps its widgets in.
+//!
+//! # Example
+//! ```
+//! use captcha_oxide::{captcha_types::recaptcha::RecaptchaSolution, inject::InjectSolution};
+//!
+//! let solution = RecaptchaSolution {
+//!     g_recaptcha_response: "03AGdBq24PBCbwiDRaS_MJ7Z...".into(),
+//!     token: "03AGdBq24PBCbwiDRaS_MJ7Z...".into(),
+//! };
+//!
+//! let html = r#"<div class="g-recaptcha" data-sitekey="KEY" data-callback="onSubmit"></div>"#;
+//!
+//! let injection = solution.injection();
+//! let callback = injection.find_callback(html);
+//! assert_eq!(callback.as_deref(), Some("onSubmit"));
+//!
+//! let script = injection.callback(callback).script();
+//! // page.evaluate(&script).await?
+//! ```
+
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    apply::ApplySolution,
+    detect::{html, Vendor},
+    solution::CaptchaSolution,
+};
+
+lazy_static! {
+    static ref RENDER_CALLBACK: Regex = Regex::new(
+        r#"(grecaptcha(?:\s*\.\s*enterprise)?|hcaptcha|turnstile)\s*\.\s*render\s*\([^{]*\{[^}]*?['"]?callback['"]?\s*:\s*['"]?([A-Za-z_$][\w$.]*)"#
+    )
+    .expect("The render callback pattern is valid");
+    static ref GEETEST_CALLBACK: Regex =
+        Regex::new(r"\.\s*onSuccess\s*\(\s*([A-Za-z_$][\w$.]*)\s*\)")
+            .expect("The GeeTest callback pattern is valid");
+    static ref ARKOSE_CALLBACK: Regex =
+        Regex::new(r#"['"]?onCompleted['"]?\s*:\s*([A-Za-z_$][\w$.]*)"#)
+            .expect("The Arkose Labs callback pattern is valid");
+}
+
+/// The script the snippets are built from, called with the JSON
+/// configuration of the [`Injection`]
+const TEMPLATE: &str = r#"(function (config) {
+  var resolve = function (path) {
+    return path.split(".").reduce(function (object, key) {
+      return object == null ? undefined : object[key];
+    }, window);
+  };
+
+  var widget = document.querySelector(config.widget);
+  var form = (widget && widget.closest("form")) || document.forms[0];
+
+  Object.keys(config.fields).forEach(function (name) {
+    var elements = Array.prototype.slice.call(document.querySelectorAll('[name="' + name + '"]'));
+    if (!elements.length && form) {
+      var input = document.createElement("input");
+      input.type = "hidden";
+      input.name = name;
+      form.appendChild(input);
+      elements.push(input);
+    }
+
+    elements.forEach(function (element) {
+      element.value = config.fields[name];
+      if (element.tagName === "TEXTAREA") {
+        element.innerHTML = config.fields[name];
+      }
+    });
+  });
+
+  var apis = [];
+  if (config.api && resolve(config.api)) {
+    apis.push(resolve(config.api));
+  }
+  if (config.discover && config.getter === "getValidate") {
+    Object.keys(window).forEach(function (key) {
+      try {
+        var value = window[key];
+        if (value && typeof value.getValidate === "function" && apis.indexOf(value) < 0) {
+          apis.push(value);
+        }
+      } catch (e) {}
+    });
+  }
+  apis.forEach(function (api) {
+    api[config.getter] = function () {
+      return config.argument;
+    };
+  });
+
+  var callbacks = [];
+  var add = function (callback) {
+    if (typeof callback === "string") {
+      callback = resolve(callback);
+    }
+    if (typeof callback === "function" && callbacks.indexOf(callback) < 0) {
+      callbacks.push(callback);
+    }
+  };
+
+  if (config.callback) {
+    add(config.callback);
+  } else if (config.discover) {
+    if (widget && widget.getAttribute("data-callback")) {
+      add(widget.getAttribute("data-callback"));
+    }
+
+    var visit = function (object, depth) {
+      if (!object || typeof object !== "object" || depth > 5) {
+        return;
+      }
+      if (typeof Node !== "undefined" && object instanceof Node) {
+        return;
+      }
+      Object.keys(object).forEach(function (key) {
+        if (key === "callback") {
+          add(object[key]);
+        } else {
+          visit(object[key], depth + 1);
+        }
+      });
+    };
+
+    if (config.api === "grecaptcha" && window.___grecaptcha_cfg) {
+      visit(window.___grecaptcha_cfg.clients, 0);
+    }
+  }
+
+  callbacks.forEach(function (callback) {
+    callback(config.argument);
+  });
+
+  return callbacks.length;
+})"#;
+
+/// How a solution is put into the page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Injection<'a> {
+    vendor: Vendor,
+    fields: Vec<(&'static str, Cow<'a, str>)>,
+    argument: Value,
+    callback: Option<String>,
+    discover_callback: bool,
+}
+
+impl<'a> Injection<'a> {
+    fn new(vendor: Vendor, solution: &'a impl ApplySolution, argument: Value) -> Self {
+        Self {
+            vendor,
+            fields: solution.application().fields,
+            argument,
+            callback: None,
+            discover_callback: false,
+        }
+    }
+
+    /// The path of the function the widget calls once solved, relative to
+    /// `window`, e.g.: `onSubmit` or `app.captcha.done`
+    pub fn callback(mut self, callback: Option<String>) -> Self {
+        self.callback = callback;
+        self
+    }
+
+    /// Whether the script should look for the callback itself when none is
+    /// given: in the `data-callback` attribute of the widget and, for reCAPTCHA,
+    /// in the `___grecaptcha_cfg` object. For GeeTest, every global object with
+    /// a `getValidate` method is treated as a `captchaObj`
+    pub const fn discover_callback(mut self, discover_callback: bool) -> Self {
+        self.discover_callback = discover_callback;
+        self
+    }
+
+    /// Looks for the name of the callback in the HTML of the page: in the
+    /// `data-callback` attribute of the widget or in the options passed to
+    /// the `render` function of its API. Callbacks defined inline cannot be found
+    pub fn find_callback(&self, html: &str) -> Option<String> {
+        let class = self.widget_class();
+        let from_attribute = || {
+            html::tags(html)
+                .filter(|tag| class.is_some_and(|class| tag.has_class(class)))
+                .find_map(|tag| tag.get("data-callback").map(str::to_owned))
+        };
+
+        let from_scripts = || {
+            html::inline_scripts(html).find_map(|(_, script)| {
+                let name = match self.vendor {
+                    Vendor::GeeTest => GEETEST_CALLBACK.captures(script)?[1].to_owned(),
+                    Vendor::ArkoseLabs => ARKOSE_CALLBACK.captures(script)?[1].to_owned(),
+                    _ => {
+                        let api = self.api()?;
+                        RENDER_CALLBACK.captures_iter(script).find(|x| {
+                            x[1].split_whitespace().collect::<String>().starts_with(api)
+                        })?[2]
+                            .to_owned()
+                    }
+                };
+
+                (name != "function").then_some(name)
+            })
+        };
+
+        from_attribute().or_else(from_scripts)
+    }
+
+    /// The self-contained snippet, which evaluates to the number of
+    /// callbacks it invoked
+    pub fn script(&self) -> String {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, value)| ((*name).to_owned(), Value::from(value.as_ref())))
+            .collect::<Map<_, _>>();
+
+        let config = json!({
+            "widget": self.widget_selector(),
+            "api": self.api(),
+            "getter": if self.vendor == Vendor::GeeTest { "getValidate" } else { "getResponse" },
+            "fields": fields,
+            "argument": self.argument,
+            "callback": self.callback,
+            "discover": self.discover_callback,
+        });
+
+        format!("{TEMPLATE}({config});")
+    }
+
+    const fn widget_class(&self) -> Option<&'static str> {
+        match self.vendor {
+            Vendor::Recaptcha => Some("g-recaptcha"),
+            Vendor::HCaptcha => Some("h-captcha"),
+            Vendor::Turnstile => Some("cf-turnstile"),
+            _ => None,
+        }
+    }
+
+    const fn widget_selector(&self) -> &'static str {
+        match self.vendor {
+            Vendor::Recaptcha => ".g-recaptcha",
+            Vendor::HCaptcha => ".h-captcha",
+            Vendor::Turnstile => ".cf-turnstile",
+            Vendor::GeeTest => "[class*=geetest]",
+            _ => "#FunCaptcha, [data-pkey]",
+        }
+    }
+
+    const fn api(&self) -> Option<&'static str> {
+        match self.vendor {
+            Vendor::Recaptcha => Some("grecaptcha"),
+            Vendor::HCaptcha => Some("hcaptcha"),
+            Vendor::Turnstile => Some("turnstile"),
+            Vendor::GeeTest => Some("captchaObj"),
+            _ => None,
+        }
+    }
+}
+
+/// Solutions that can be put into a page with a JavaScript snippet
+pub trait InjectSolution {
+    fn injection(&self) -> Injection<'_>;
+}
+
+impl<T: InjectSolution> InjectSolution for CaptchaSolution<'_, T> {
+    fn injection(&self) -> Injection<'_> {
+        self.solution.injection()
+    }
+}
+
+mod solutions {
+    use serde_json::{json, Value};
+
+    use super::{InjectSolution, Injection};
+    use crate::{
+        captcha_types::{
+            arkose_labs_captcha::ArkoseLabsCaptchaSolution,
+            geetest::{GeeTestV3Solution, GeeTestV4Solution},
+            h_captcha::HCaptchaSolution,
+            recaptcha::RecaptchaSolution,
+            turnstile_captcha::TurnstileCaptchaSolution,
+        },
+        detect::Vendor,
+    };
+
+    impl InjectSolution for RecaptchaSolution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            let token = Value::from(self.g_recaptcha_response.as_ref());
+            Injection::new(Vendor::Recaptcha, self, token)
+        }
+    }
+
+    impl InjectSolution for HCaptchaSolution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            Injection::new(Vendor::HCaptcha, self, Value::from(self.token.as_ref()))
+        }
+    }
+
+    impl InjectSolution for TurnstileCaptchaSolution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            Injection::new(Vendor::Turnstile, self, Value::from(self.token.as_ref()))
+        }
+    }
+
+    impl InjectSolution for GeeTestV3Solution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            let validate = json!({
+                "geetest_challenge": self.challenge,
+                "geetest_validate": self.validate,
+                "geetest_seccode": self.seccode,
+            });
+
+            Injection::new(Vendor::GeeTest, self, validate)
+        }
+    }
+
+    impl InjectSolution for GeeTestV4Solution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            let validate = json!({
+                "captcha_id": self.captcha_id,
+                "lot_number": self.lot_number,
+                "pass_token": self.pass_token,
+                "gen_time": self.gen_time,
+                "captcha_output": self.captcha_output,
+            });
+
+            Injection::new(Vendor::GeeTest, self, validate)
+        }
+    }
+
+    impl InjectSolution for ArkoseLabsCaptchaSolution<'_> {
+        fn injection(&self) -> Injection<'_> {
+            Injection::new(Vendor::ArkoseLabs, self, json!({ "token": self.token }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::{InjectSolution, Injection};
+    use crate::{
+        captcha_types::{
+            arkose_labs_captcha::ArkoseLabsCaptchaSolution, geetest::GeeTestV4Solution,
+            h_captcha::HCaptchaSolution, recaptcha::RecaptchaSolution,
+            turnstile_captcha::TurnstileCaptchaSolution,
+        },
+        detect::html,
+    };
+
+    /// Loads the page into `fixtures/dom.js`, runs its inline scripts, spies on
+    /// `callback` and runs the script of the injection. Returns what the
+    /// script evaluated to and the state of the page afterwards
+    fn run(html: &str, callback: &str, injection: Injection) -> (i32, Value) {
+        let runtime = rquickjs::Runtime::new().unwrap();
+        let context = rquickjs::Context::full(&runtime).unwrap();
+
+        context.with(|context| {
+            let dom = include_str!("fixtures/dom.js");
+            let page = serde_json::to_string(html).unwrap();
+            context
+                .eval::<(), _>(format!("{dom}\nload({page});"))
+                .unwrap();
+
+            for (_, script) in html::inline_scripts(html) {
+                context.eval::<(), _>(script).unwrap();
+            }
+            context
+                .eval::<(), _>(format!("spy({callback:?});"))
+                .unwrap();
+
+            let count = context.eval::<i32, _>(injection.script()).unwrap();
+            let report = context
+                .eval::<String, _>("JSON.stringify(report());")
+                .unwrap();
+
+            (count, serde_json::from_str(&report).unwrap())
+        })
+    }
+
+    #[test]
+    fn finds_callbacks() {
+        let recaptcha = RecaptchaSolution {
+            g_recaptcha_response: "TOKEN".into(),
+            token: "TOKEN".into(),
+        };
+        let h_captcha = HCaptchaSolution {
+            token: "TOKEN".into(),
+            resp_key: "".into(),
+            user_agent: "Mozilla/5.0".into(),
+            g_recaptcha_response: "TOKEN".into(),
+        };
+        let turnstile = TurnstileCaptchaSolution {
+            token: "TOKEN".into(),
+            user_agent: "Mozilla/5.0".into(),
+        };
+        let geetest = GeeTestV4Solution {
+            captcha_id: "ID".into(),
+            lot_number: "LOT".into(),
+            pass_token: "PASS".into(),
+            gen_time: "1".into(),
+            captcha_output: "OUTPUT".into(),
+        };
+        let arkose = ArkoseLabsCaptchaSolution {
+            token: "TOKEN".into(),
+        };
+
+        let html = include_str!("fixtures/recaptcha.html");
+        assert_eq!(
+            recaptcha.injection().find_callback(html).as_deref(),
+            Some("onLogin")
+        );
+        assert_eq!(h_captcha.injection().find_callback(html), None);
+
+        let html = include_str!("fixtures/render.html");
+        assert_eq!(
+            h_captcha.injection().find_callback(html).as_deref(),
+            Some("app.onCaptcha")
+        );
+        assert_eq!(
+            turnstile.injection().find_callback(html).as_deref(),
+            Some("onTurnstile")
+        );
+        assert_eq!(recaptcha.injection().find_callback(html), None);
+
+        let html = include_str!("fixtures/geetest.html");
+        assert_eq!(
+            geetest.injection().find_callback(html).as_deref(),
+            Some("submitLogin")
+        );
+        assert_eq!(
+            arkose.injection().find_callback(html).as_deref(),
+            Some("onArkose")
+        );
+    }
+
+    #[test]
+    fn builds_scripts() {
+        let solution = GeeTestV4Solution {
+            captcha_id: "ID".into(),
+            lot_number: "LOT".into(),
+            pass_token: "PASS\"';".into(),
+            gen_time: "1".into(),
+            captcha_output: "OUTPUT".into(),
+        };
+
+        let script = solution
+            .injection()
+            .callback(Some("submitLogin".into()))
+            .script();
+
+        assert!(script.starts_with("(function (config) {"));
+        assert!(script.ends_with("});"));
+
+        let config = script
+            .rsplit_once("})(")
+            .and_then(|(_, x)| x.strip_suffix(");"))
+            .unwrap();
+        let config = serde_json::from_str::<serde_json::Value>(config).unwrap();
+
+        assert_eq!(config["getter"], "getValidate");
+        assert_eq!(config["callback"], "submitLogin");
+        assert_eq!(config["fields"]["pass_token"], "PASS\"';");
+        assert_eq!(config["argument"]["lot_number"], "LOT");
+    }
+
+    #[test]
+    fn runs_scripts_against_pages() {
+        let recaptcha = RecaptchaSolution {
+            g_recaptcha_response: "TOKEN".into(),
+            token: "TOKEN".into(),
+        };
+        let html = include_str!("fixtures/recaptcha.html");
+        let (count, page) = run(
+            html,
+            "onLogin",
+            recaptcha.injection().discover_callback(true),
+        );
+        assert_eq!(count, 1);
+        assert_eq!(page["calls"], json!([["onLogin", "TOKEN"]]));
+        assert_eq!(page["fields"]["g-recaptcha-response"], json!(["TOKEN"]));
+        assert_eq!(page["submitted"], true);
+
+        let h_captcha = HCaptchaSolution {
+            token: "TOKEN".into(),
+            resp_key: "".into(),
+            user_agent: "Mozilla/5.0".into(),
+            g_recaptcha_response: "TOKEN".into(),
+        };
+        let html = include_str!("fixtures/render.html");
+        let injection = h_captcha.injection();
+        let callback = injection.find_callback(html).unwrap();
+        let (count, page) = run(html, &callback, injection.callback(Some(callback.clone())));
+        assert_eq!(count, 1);
+        assert_eq!(page["calls"], json!([["app.onCaptcha", "TOKEN"]]));
+        assert_eq!(page["fields"]["h-captcha-response"], json!(["TOKEN"]));
+        assert_eq!(page["fields"]["g-recaptcha-response"], json!(["TOKEN"]));
+
+        let turnstile = TurnstileCaptchaSolution {
+            token: "TOKEN".into(),
+            user_agent: "Mozilla/5.0".into(),
+        };
+        let injection = turnstile.injection();
+        let callback = injection.find_callback(html);
+        let (count, page) = run(html, "onTurnstile", injection.callback(callback));
+        assert_eq!(count, 1);
+        assert_eq!(page["calls"], json!([["onTurnstile", "TOKEN"]]));
+        assert_eq!(page["fields"]["cf-turnstile-response"], json!(["TOKEN"]));
+
+        let geetest = GeeTestV4Solution {
+            captcha_id: "ID".into(),
+            lot_number: "LOT".into(),
+            pass_token: "PASS".into(),
+            gen_time: "1".into(),
+            captcha_output: "OUTPUT".into(),
+        };
+        let html = include_str!("fixtures/geetest.html");
+        let injection = geetest.injection().discover_callback(true);
+        let callback = injection.find_callback(html);
+        let (count, page) = run(html, "submitLogin", injection.callback(callback));
+        assert_eq!(count, 1);
+        assert_eq!(page["calls"][0][0], "submitLogin");
+        assert_eq!(page["calls"][0][1]["lot_number"], "LOT");
+        assert_eq!(page["fields"]["pass_token"], json!(["PASS"]));
+
+        let arkose = ArkoseLabsCaptchaSolution {
+            token: "TOKEN".into(),
+        };
+        let injection = arkose.injection();
+        let callback = injection.find_callback(html);
+        let (count, page) = run(html, "onArkose", injection.callback(callback));
+        assert_eq!(count, 1);
+        assert_eq!(page["calls"], json!([["onArkose", { "token": "TOKEN" }]]));
+        assert_eq!(page["fields"]["fc-token"], json!(["TOKEN"]));
+    }
+}
@@ -36,12 +36,36 @@
 mod prelude;
 pub(crate) const SOFT_ID: u16 = 4143;
 
+pub mod apply;
+pub mod archive;
+pub mod blocked;
+pub mod cache;
 pub mod captcha_types;
+pub mod consensus;
 pub mod cookie;
+pub mod detect;
 pub mod error;
+pub mod geometry;
+pub mod har;
+pub mod inject;
+pub mod local;
+pub mod manual;
+#[cfg(feature = "middleware")]
+pub mod middleware;
+pub mod owned;
+pub mod pool;
+#[cfg(feature = "image")]
+pub mod preprocess;
 pub mod proxy;
+pub mod session;
 pub mod solution;
 pub mod solver;
+pub mod stats;
+#[cfg(test)]
+mod test_server;
+pub mod trajectory;
+#[cfg(feature = "visualize")]
+pub mod visualize;
 
 pub use captcha_types::CaptchaTask;
 pub use error::Error;
@@ -0,0 +1,207 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use super::{LocalSolution, LocalSolver};
+
+lazy_static! {
+    static ref EXPRESSION: Regex =
+        Regex::new(&format!(r"{NUMBER}(?:\s*{OPERATOR}\s*{NUMBER})+")).unwrap();
+    static ref TOKEN: Regex = Regex::new(&format!(r"{NUMBER}|{OPERATOR}")).unwrap();
+}
+
+/// A number with an optional sign, e.g.: the `-3` in `"What is -3 + 4?"`
+const NUMBER: &str = r"-?\d+(?:\.\d+)?";
+const OPERATOR: &str = r"(?:[-+*/x×÷]|plus|minus|times|multiplied by|divided by)";
+
+/// The words that may surround an expression in a question that
+/// only asks for its result
+const FILLER: &[&str] = &[
+    "what",
+    "s",
+    "is",
+    "the",
+    "result",
+    "of",
+    "how",
+    "much",
+    "calculate",
+    "solve",
+    "compute",
+    "equals",
+    "equal",
+    "to",
+];
+
+/// Solves the [`crate::captcha_types::text_captcha::TextCaptcha`]s that ask for
+/// the result of an arithmetic expression, e.g.: `"What is 3 + 4?"`. Questions
+/// with anything besides the expression are answered with a low confidence,
+/// and results with infinitely many decimals are not answered
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArithmeticSolver;
+
+impl LocalSolver for ArithmeticSolver {
+    fn task_type(&self) -> &str {
+        "TextCaptchaTask"
+    }
+
+    fn solve(&self, task: &Value) -> Option<LocalSolution> {
+        let question = task.get("comment")?.as_str()?.to_lowercase();
+
+        let mut expressions = EXPRESSION.find_iter(&question);
+        let expression = expressions.next()?;
+        if expressions.next().is_some() {
+            return None;
+        }
+
+        let result = evaluate(expression.as_str())?.to_exact_string()?;
+
+        let rest = format!(
+            "{} {}",
+            &question[..expression.start()],
+            &question[expression.end()..]
+        );
+        let is_plain = rest
+            .split(|x: char| !x.is_alphanumeric())
+            .all(|x| x.is_empty() || FILLER.contains(&x));
+
+        Some(LocalSolution {
+            solution: json!({ "text": result }),
+            confidence: if is_plain { 1.0 } else { 0.5 },
+        })
+    }
+}
+
+/// Evaluates an expression matched by `EXPRESSION`, multiplying
+/// and dividing before adding and subtracting. A signed number right
+/// after another one, like the `-4` in `3-4`, is added to it
+fn evaluate(expression: &str) -> Option<Decimal> {
+    let mut terms = Vec::new();
+    let mut negative = false;
+    let mut term: Option<Decimal> = None;
+    let mut operator = "+";
+
+    for token in TOKEN.find_iter(expression).map(|x| x.as_str()) {
+        let Some(number) = Decimal::parse(token) else {
+            operator = token;
+            continue;
+        };
+
+        term = match operator {
+            "+" | "plus" | "-" | "minus" => {
+                if let Some(term) = term {
+                    terms.push(if negative { term.negate() } else { term });
+                }
+
+                negative = matches!(operator, "-" | "minus");
+                Some(number)
+            }
+            "*" | "x" | "×" | "times" | "multiplied by" => Some(term?.mul(number)?),
+            _ => Some(term?.div(number)?),
+        };
+        operator = "+";
+    }
+
+    let term = term?;
+    terms.push(if negative { term.negate() } else { term });
+    terms.into_iter().try_fold(Decimal::ZERO, Decimal::add)
+}
+
+/// An exact fraction, so the decimals of the questions are added
+/// and multiplied without the rounding errors of floats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decimal {
+    numerator: i128,
+    /// Always positive
+    denominator: i128,
+}
+
+impl Decimal {
+    const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    fn parse(number: &str) -> Option<Self> {
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let denominator = 10_i128.checked_pow(fraction.len().try_into().ok()?)?;
+        let numerator: i128 = format!("{integer}{fraction}").parse().ok()?;
+
+        Self::new(numerator, denominator)
+    }
+
+    fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Some(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    const fn negate(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+
+    fn add(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator
+                .checked_mul(other.denominator)?
+                .checked_add(other.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(other.numerator)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(other.denominator)?,
+            self.denominator.checked_mul(other.numerator)?,
+        )
+    }
+
+    /// The number written out with no trailing zeros, or [`None`] if
+    /// it has infinitely many decimals, like `10 / 3`
+    fn to_exact_string(self) -> Option<String> {
+        let mut scale = 1_i128;
+        let mut decimals = 0;
+        while scale % self.denominator != 0 {
+            scale = scale.checked_mul(10)?;
+            decimals += 1;
+        }
+
+        let digits = self
+            .numerator
+            .checked_mul(scale / self.denominator)?
+            .unsigned_abs()
+            .to_string();
+        let digits = format!("{digits:0>width$}", width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let sign = if self.numerator < 0 { "-" } else { "" };
+
+        match fraction.trim_end_matches('0') {
+            "" => Some(format!("{sign}{integer}")),
+            fraction => Some(format!("{sign}{integer}.{fraction}")),
+        }
+    }
+}
+
+const fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a.abs()
+}
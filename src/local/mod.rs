@@ -0,0 +1,331 @@
+//! Solving trivial captchas locally before paying 2captcha for them
+//!
+//! Some captchas, like arithmetic questions or images in a fixed font, are
+//! easy enough to be solved by your own code. The [`LocalSolver`]s of the
+//! [`LocalSolvers`] set with [`crate::solver::SolverBuilder::local_solvers`]
+//! are tried before a task is sent to 2captcha, and their answer is used if
+//! they are confident enough in it.
+//!
+//! Local solvers see the task as the JSON sent to 2captcha, and answer with
+//! the JSON 2captcha would have answered with, so they can handle any task
+//! type. Their solutions have no cost and no task id, and they aren't
+//! reported to 2captcha, though reporting them as bad still removes them
+//! from the [`crate::cache::SolutionCache`].
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     captcha_types::text_captcha::TextCaptcha,
+//!     local::{ArithmeticSolver, LocalSolvers},
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = CaptchaSolver::builder()
+//!     .api_key("YOUR_API_KEY")
+//!     .local_solvers(Some(LocalSolvers::new().solver(ArithmeticSolver)))
+//!     .build();
+//!
+//! let task = TextCaptcha::builder().comment("What is 3 + 4?").build();
+//!
+//! // Never sent to 2captcha
+//! let solution = solver.solve(task).await?.expect("No callback url was set");
+//! assert_eq!(solution.solution.text, "7");
+//! # Ok(())
+//! # }
+//! ```
+
+mod arithmetic;
+
+pub use arithmetic::ArithmeticSolver;
+
+use std::{
+    fmt,
+    net::Ipv4Addr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use crate::{prelude::*, CaptchaTask};
+
+/// The task id given to the solutions of local solvers, which is never
+/// used by 2captcha
+pub(crate) const LOCAL_TASK_ID: u64 = 0;
+
+/// A solver that runs on your machine
+pub trait LocalSolver: Send + Sync {
+    /// The `type` of the tasks the solver handles, e.g.: `"TextCaptchaTask"`
+    fn task_type(&self) -> &str;
+
+    /// Solves the task, given as the JSON that would be sent to 2captcha,
+    /// or returns [`None`] if the solver can't solve it
+    fn solve(&self, task: &Value) -> Option<LocalSolution>;
+}
+
+/// The answer of a [`LocalSolver`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalSolution {
+    /// The `solution` object 2captcha returns for the task, e.g.: `{"text": "7"}`
+    pub solution: Value,
+
+    /// How sure the solver is of the answer, from 0 to 1
+    pub confidence: f64,
+}
+
+/// How many tasks were solved locally and how many were sent to 2captcha
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LocalMetrics {
+    /// Tasks solved by a [`LocalSolver`]
+    pub local: u64,
+
+    /// Tasks sent to 2captcha
+    pub remote: u64,
+
+    /// Tasks a [`LocalSolver`] answered below the confidence threshold,
+    /// which were sent to 2captcha as well
+    pub low_confidence: u64,
+}
+
+/// The [`LocalSolver`]s a [`crate::CaptchaSolver`] tries first
+pub struct LocalSolvers {
+    solvers: Vec<Box<dyn LocalSolver>>,
+    threshold: f64,
+    local: AtomicU64,
+    remote: AtomicU64,
+    low_confidence: AtomicU64,
+}
+
+impl LocalSolvers {
+    /// No solvers, which only accept answers with a confidence of at least 0.9
+    pub fn new() -> Self {
+        Self {
+            solvers: Vec::new(),
+            threshold: 0.9,
+            local: AtomicU64::new(0),
+            remote: AtomicU64::new(0),
+            low_confidence: AtomicU64::new(0),
+        }
+    }
+
+    /// Adds a solver, which is tried after the ones added before it
+    pub fn solver(mut self, solver: impl LocalSolver + 'static) -> Self {
+        self.solvers.push(Box::new(solver));
+        self
+    }
+
+    /// The minimum confidence of the answers that are used. Tasks
+    /// answered with less confidence are sent to 2captcha
+    pub const fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn metrics(&self) -> LocalMetrics {
+        LocalMetrics {
+            local: self.local.load(Ordering::Relaxed),
+            remote: self.remote.load(Ordering::Relaxed),
+            low_confidence: self.low_confidence.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the JSON of a `/getTaskResult` response with the most confident
+    /// answer of the solvers of the task type, if it reaches the threshold
+    pub(crate) fn solve<T>(&self, task: &T) -> Result<Option<String>>
+    where
+        T: CaptchaTask,
+    {
+        let task = serde_json::to_value(task)?;
+        let task_type = task.get("type").and_then(Value::as_str).unwrap_or_default();
+
+        let answer = self
+            .solvers
+            .iter()
+            .filter(|x| x.task_type() == task_type)
+            .filter_map(|x| x.solve(&task))
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence));
+
+        match answer {
+            Some(answer) if answer.confidence >= self.threshold => {
+                self.local.fetch_add(1, Ordering::Relaxed);
+                return Ok(Some(task_result(answer.solution)));
+            }
+            Some(_) => {
+                self.low_confidence.fetch_add(1, Ordering::Relaxed);
+            }
+            None => {}
+        }
+
+        self.remote.fetch_add(1, Ordering::Relaxed);
+        Ok(None)
+    }
+}
+
+/// The JSON of a `/getTaskResult` response for a task that was solved
+/// with `solution` on this machine, free of charge
+pub(crate) fn task_result(solution: Value) -> String {
+    let now = Utc::now().timestamp();
+
+    json!({
+        "errorId": 0,
+        "status": "ready",
+        "solution": solution,
+        "cost": "0",
+        "ip": Ipv4Addr::LOCALHOST,
+        "createTime": now,
+        "endTime": now,
+        "solveCount": 0,
+    })
+    .to_string()
+}
+
+impl Default for LocalSolvers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LocalSolvers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let task_types = self
+            .solvers
+            .iter()
+            .map(|x| x.task_type())
+            .collect::<Vec<_>>();
+
+        f.debug_struct("LocalSolvers")
+            .field("solvers", &task_types)
+            .field("threshold", &self.threshold)
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::{ArithmeticSolver, LocalMetrics, LocalSolution, LocalSolver, LocalSolvers};
+    use crate::{
+        cache::SolutionCache,
+        captcha_types::{normal_captcha::NormalCaptcha, text_captcha::TextCaptcha},
+        solver::SolutionStatus,
+        test_server::{self, Response},
+        CaptchaSolver, CaptchaTask,
+    };
+
+    #[test]
+    fn solves_arithmetic() {
+        let solve = |question: &str| {
+            ArithmeticSolver
+                .solve(&json!({ "type": "TextCaptchaTask", "comment": question }))
+                .map(|x| {
+                    (
+                        x.solution["text"].as_str().unwrap().to_owned(),
+                        x.confidence,
+                    )
+                })
+        };
+
+        assert_eq!(solve("What is 3 + 4?"), Some(("7".into(), 1.0)));
+        assert_eq!(solve("2 + 3 * 4 ="), Some(("14".into(), 1.0)));
+        assert_eq!(
+            solve("Calculate 10 divided by 4"),
+            Some(("2.5".into(), 1.0))
+        );
+        assert_eq!(solve("12 minus 20"), Some(("-8".into(), 1.0)));
+        assert_eq!(solve("What is -3 + 4?"), Some(("1".into(), 1.0)));
+        assert_eq!(solve("2*3-4"), Some(("2".into(), 1.0)));
+        assert_eq!(solve("8 / -2 - -1"), Some(("-3".into(), 1.0)));
+        assert_eq!(solve("What is 2.00001 + 1?"), Some(("3.00001".into(), 1.0)));
+        assert_eq!(solve("0 - 0.5"), Some(("-0.5".into(), 1.0)));
+        assert_eq!(solve("What is 10 / 3?"), None);
+        assert_eq!(solve("0.1 + 0.2"), Some(("0.3".into(), 1.0)));
+        assert_eq!(solve("What is the capital of France?"), None);
+        assert_eq!(solve("1 / 0"), None);
+        assert!(
+            solve("If you had 3 + 4 apples, how many would you have?")
+                .unwrap()
+                .1
+                < 0.9
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn falls_back_to_2captcha() {
+        let url = test_server::serve(|request| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => Response::task_ready(r#"{"text":"7"}"#),
+            _ => panic!("Only tasks solved by 2captcha are reported"),
+        });
+
+        let solver = test_server::solver_builder(url)
+            .local_solvers(Some(LocalSolvers::new().solver(ArithmeticSolver)))
+            .build();
+
+        let task = TextCaptcha::builder().comment("What is 3 + 4?").build();
+        let solution = solver.solve(task).await.unwrap().unwrap();
+        assert_eq!(solution.solution.text, "7");
+        assert_eq!(solution.cost, "0");
+
+        solver.report(solution, SolutionStatus::Bad).await.unwrap();
+
+        let task = TextCaptcha::builder()
+            .comment("If you had 3 + 4 apples, how many would you have?")
+            .build();
+        solver.solve(task).await.unwrap();
+
+        let task = TextCaptcha::builder()
+            .comment("What is the capital of France?")
+            .build();
+        solver.solve(task).await.unwrap();
+
+        assert_eq!(
+            solver.local_metrics(),
+            Some(LocalMetrics {
+                local: 1,
+                remote: 2,
+                low_confidence: 1,
+            })
+        );
+    }
+
+    struct FixedFont;
+
+    impl LocalSolver for FixedFont {
+        fn task_type(&self) -> &str {
+            "ImageToTextTask"
+        }
+
+        fn solve(&self, _: &Value) -> Option<LocalSolution> {
+            Some(LocalSolution {
+                solution: json!({ "text": "w68hp" }),
+                confidence: 1.0,
+            })
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn evicts_bad_local_answers() {
+        let url = test_server::serve(|_| panic!("Local solutions aren't sent to 2captcha"));
+
+        let solver = test_server::solver_builder(url)
+            .cache(Some(SolutionCache::new()))
+            .local_solvers(Some(LocalSolvers::new().solver(FixedFont)))
+            .build();
+
+        let task = || NormalCaptcha::builder().body("IMAGE").build();
+        let local = |solver: &CaptchaSolver| solver.local_metrics().unwrap().local;
+
+        let solution = solver.solve_cached(task()).await.unwrap().unwrap();
+        solver.solve_cached(task()).await.unwrap();
+        assert_eq!(local(&solver), 1);
+
+        solver.report(&solution, SolutionStatus::Bad).await.unwrap();
+        solver.solve_cached(task()).await.unwrap();
+        assert_eq!(local(&solver), 2);
+    }
+}
@@ -0,0 +1,284 @@
+//! Routing image captchas to human operators
+//!
+//! When 2captcha is down or the budget runs out, a [`ManualSolver`] lets your
+//! own staff solve image captchas. Every task is written to a queue directory
+//! as `<id>.json`, with the task as it would be sent to 2captcha, and as
+//! `<id>.html`, a page showing the image, the `comment` and the
+//! `img_instructions` that operators can open in a browser.
+//!
+//! Operators answer by writing `<id>.answer` next to them, with either the
+//! `solution` object 2captcha would return, e.g.: `{"click":[1,4,7]}` for a
+//! [`GridCaptcha`](crate::captcha_types::grid_captcha::GridCaptcha), or the
+//! plain text answer of a [`NormalCaptcha`](crate::captcha_types::normal_captcha::NormalCaptcha).
+//! Once the answer is completely written, they create an empty `<id>.done`
+//! file. The answer isn't read before that, so a half written answer is never
+//! taken as the solution. The files of a task are removed once it's answered
+//! or it times out.
+//!
+//! # Example
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use captcha_oxide::{
+//!     captcha_types::normal_captcha::NormalCaptcha,
+//!     manual::ManualSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = ManualSolver::new("/srv/captcha-queue").timeout(Duration::from_secs(10 * 60));
+//!
+//! let task = NormalCaptcha::builder()
+//!     .body("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==")
+//!     .comment(Some("Type the red characters"))
+//!     .build();
+//!
+//! let solution = solver.solve(task).await?;
+//! println!("{}", solution.solution.text);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use crate::{
+    local::{task_result, LOCAL_TASK_ID},
+    prelude::*,
+    solution::CaptchaSolution,
+    solver::{parse_solution, SolveOptions, Solver},
+    CaptchaTask, Error,
+};
+
+/// Solves tasks by waiting for an operator to answer them in a queue directory
+#[derive(Debug, Clone)]
+pub struct ManualSolver {
+    directory: PathBuf,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl ManualSolver {
+    /// A solver that queues tasks in `directory`, waiting 5 minutes for an answer
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            timeout: Duration::from_secs(5 * 60),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// How long an operator has to answer a task
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How often the directory is checked for an answer
+    pub const fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Queues the task and waits for an operator to answer it
+    ///
+    /// # Errors
+    /// This method returns [`Error::OperatorTimeout`] if nobody answers the task
+    /// in time, [`Error::IoError`] if the queue directory can't be written, and
+    /// [`Error::SerializeError`] if the answer doesn't fit the solution of the task
+    pub async fn solve<'a, T>(&self, task: T) -> Result<CaptchaSolution<'a, T::Solution>>
+    where
+        T: CaptchaTask,
+    {
+        let id = format!("{}-{:08x}", Utc::now().timestamp(), rand::random::<u32>());
+        let task_json = serde_json::to_value(&task)?;
+
+        tokio::fs::create_dir_all(&self.directory).await?;
+        let queued = Queued::new(&self.directory, &id);
+        tokio::fs::write(&queued.task, serde_json::to_string_pretty(&task_json)?).await?;
+        tokio::fs::write(&queued.page, page(&id, &task_json)).await?;
+
+        let deadline = tokio::time::Instant::now() + self.timeout;
+
+        loop {
+            if tokio::fs::try_exists(&queued.done).await? {
+                let answer = tokio::fs::read_to_string(&queued.answer).await?;
+                let solution = serde_json::from_str::<Value>(&answer)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({ "text": answer.trim() }));
+
+                return parse_solution(
+                    &task_result(solution),
+                    LOCAL_TASK_ID,
+                    &task,
+                    SolveOptions::new(),
+                );
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::OperatorTimeout(self.timeout));
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+impl Solver for ManualSolver {
+    async fn solve<'a, T>(&self, task: T) -> Result<CaptchaSolution<'a, T::Solution>>
+    where
+        T: CaptchaTask + Send + Sync,
+    {
+        ManualSolver::solve(self, task).await
+    }
+}
+
+/// The files of a queued task, which are removed when it's dropped
+struct Queued {
+    task: PathBuf,
+    page: PathBuf,
+    answer: PathBuf,
+    done: PathBuf,
+}
+
+impl Queued {
+    fn new(directory: &Path, id: &str) -> Self {
+        Self {
+            task: directory.join(format!("{id}.json")),
+            page: directory.join(format!("{id}.html")),
+            answer: directory.join(format!("{id}.answer")),
+            done: directory.join(format!("{id}.done")),
+        }
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        for path in [&self.task, &self.page, &self.answer, &self.done] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// An HTML page with the images and instructions of the task
+fn page(id: &str, task: &Value) -> String {
+    let field = |name: &str| task.get(name).and_then(Value::as_str);
+
+    let image = |body: &str| match body.starts_with("data:") {
+        true => format!(r#"<img src="{}">"#, escape(body)),
+        false => format!(r#"<img src="data:image/png;base64,{}">"#, escape(body)),
+    };
+
+    let mut parameters = task.clone();
+    if let Some(parameters) = parameters.as_object_mut() {
+        parameters.remove("body");
+        parameters.remove("imgInstructions");
+    }
+
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head><title>{id}</title></head>\n<body>\n");
+
+    if let Some(body) = field("body") {
+        html += &format!("<p>{}</p>\n", image(body));
+    }
+    if let Some(comment) = field("comment") {
+        html += &format!("<p>{}</p>\n", escape(comment));
+    }
+    if let Some(img_instructions) = field("imgInstructions") {
+        html += &format!("<p>{}</p>\n", image(img_instructions));
+    }
+
+    html += &format!(
+        "<pre>{}</pre>\n<p>Write the answer to <code>{id}.answer</code>, \
+         then create <code>{id}.done</code></p>\n</body>\n</html>\n",
+        escape(&serde_json::to_string_pretty(&parameters).unwrap_or_default())
+    );
+
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path, time::Duration};
+
+    use super::ManualSolver;
+    use crate::{
+        captcha_types::{grid_captcha::GridCaptcha, normal_captcha::NormalCaptcha},
+        solver::Solver,
+        CaptchaTask, Error,
+    };
+
+    /// Answers the first task queued in `directory`, writing half of the
+    /// answer first, and returns its page
+    async fn answer(directory: &Path, answer: &str) -> String {
+        loop {
+            let entries = fs::read_dir(directory).into_iter().flatten().flatten();
+            let mut pages = entries
+                .map(|x| x.path())
+                .filter(|x| x.extension().is_some_and(|x| x == "html"));
+
+            if let Some(page) = pages.next() {
+                fs::write(page.with_extension("answer"), &answer[..answer.len() / 2]).unwrap();
+                tokio::time::sleep(Duration::from_secs(2)).await;
+
+                fs::write(page.with_extension("answer"), answer).unwrap();
+                fs::write(page.with_extension("done"), "").unwrap();
+                return fs::read_to_string(page).unwrap();
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_operators() {
+        let directory = std::env::temp_dir().join(format!("manual-{}", rand::random::<u32>()));
+        let solver = ManualSolver::new(&directory);
+
+        let task = NormalCaptcha::builder()
+            .body("IMAGE")
+            .comment(Some("Type the <red> characters"))
+            .build();
+
+        let (solution, page) = tokio::join!(solver.solve(task), answer(&directory, "w68hp\n"));
+        assert_eq!(solution.unwrap().solution.text, "w68hp");
+        assert!(page.contains(r#"<img src="data:image/png;base64,IMAGE">"#));
+        assert!(page.contains("Type the &lt;red&gt; characters"));
+
+        let task = GridCaptcha::builder()
+            .body("IMAGE")
+            .comment("Select all cars")
+            .build();
+
+        let (solution, _) = tokio::join!(
+            Solver::solve(&solver, task),
+            answer(&directory, r#"{"click":[1,4]}"#)
+        );
+        assert_eq!(&*solution.unwrap().solution.click, [1, 4]);
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let error = solver
+            .timeout(Duration::from_secs(60))
+            .solve(task)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::OperatorTimeout(_)));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    apply::{Application, ApplySolution},
+    blocked::BlockTask,
+    detect::DetectedTask,
+    prelude::*,
+    solver::CaptchaSolver,
+    CaptchaTask, Error,
+};
+
+/// Solves the task, returning the fields and cookies of its solution that have to
+/// be added to the blocked request for it to go through, or [`None`] for the tasks
+/// whose solution cannot be applied to a request
+pub(super) async fn solve(
+    solver: &CaptchaSolver,
+    task: BlockTask<'_>,
+) -> Result<Option<Application<'static>>> {
+    let application = match task {
+        BlockTask::DataDome(task) => apply(solver, task).await?,
+        // The token has to be handed to the challenge page's own script,
+        // which exchanges it for the `cf_clearance` cookie
+        BlockTask::TurnstileChallengePage(_) => return Ok(None),
+        BlockTask::Widget(task) => match task {
+            DetectedTask::RecaptchaV2(task) => apply(solver, task).await?,
+            DetectedTask::RecaptchaV2Enterprise(task) => apply(solver, task).await?,
+            DetectedTask::RecaptchaV3(task) => apply(solver, task).await?,
+            DetectedTask::HCaptcha(task) => apply(solver, task).await?,
+            DetectedTask::TurnstileStandalone(task) => apply(solver, task).await?,
+            DetectedTask::FriendlyCaptcha(task) => apply(solver, task).await?,
+            DetectedTask::MtCaptcha(task) => apply(solver, task).await?,
+            DetectedTask::ArkoseLabs(task) => apply(solver, task).await?,
+            DetectedTask::GeeTestV3(task) => apply(solver, task).await?,
+            DetectedTask::GeeTestV4(task) => apply(solver, task).await?,
+            // The voucher has to be exchanged for the `aws-waf-token` cookie
+            // by the WAF's own script
+            DetectedTask::AmazonWaf(_) => return Ok(None),
+        },
+    };
+
+    Ok(Some(application))
+}
+
+async fn apply<T>(solver: &CaptchaSolver, task: T) -> Result<Application<'static>>
+where
+    T: CaptchaTask,
+    T::Solution: ApplySolution,
+{
+    let solution = solver
+        .solve(task)
+        .await?
+        .map(|x| x.solution)
+        .ok_or(Error::CallbackUrlSet)?;
+
+    Ok(solution.application().into_owned())
+}
@@ -0,0 +1,384 @@
+//! A [`reqwest_middleware`] layer that solves the captchas blocking requests
+//!
+//! [`CaptchaMiddleware`] runs every response through [`classify`]. When it
+//! finds a challenge it can solve, it builds the task with the cookies and
+//! user agent of the request and the proxy of the middleware, solves it,
+//! applies the solution to the request and sends it again:
+//! * DataDome: the `datadome` cookie is added to the request
+//! * Widgets embedded in the page: the fields of the solution, as described by
+//!   [`crate::apply::ApplySolution`], are added to the form body of the request,
+//!   or to its query if it doesn't have one, e.g.: `g-recaptcha-response`
+//!
+//! Cloudflare challenge pages and Amazon WAF captchas are returned as they are,
+//! since their solutions are exchanged for a cookie by the scripts of the page
+//!
+//! Responses that are read to be classified lose their [`reqwest::Response::url`]
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     middleware::{CaptchaMiddleware, HostPolicy},
+//!     CaptchaSolver,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let middleware = CaptchaMiddleware::new(CaptchaSolver::new("YOUR_API_KEY"))
+//!     .user_agent(Some("Mozilla/5.0 ...".into()))
+//!     .max_solves(2)
+//!     .policy("status.example.com", HostPolicy::disabled());
+//!
+//! let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+//!     .with(middleware)
+//!     .build();
+//!
+//! let body = client.get("https://example.com/").send().await?.text().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod fix;
+mod policy;
+
+pub use policy::HostPolicy;
+
+use std::collections::HashMap;
+
+use reqwest::{header::USER_AGENT, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use task_local_extensions::Extensions;
+use url::Url;
+
+use crate::{
+    apply,
+    blocked::{classify, BlockedResponse},
+    detect::TaskContext,
+    prelude::*,
+    proxy::Proxy,
+    solver::CaptchaSolver,
+};
+
+/// Solves the captchas that block the requests of a
+/// [`reqwest_middleware::ClientWithMiddleware`] and retries them
+#[derive(Debug)]
+pub struct CaptchaMiddleware {
+    solver: CaptchaSolver,
+    default_policy: HostPolicy,
+    policies: HashMap<String, HostPolicy>,
+    max_solves: usize,
+    user_agent: Option<String>,
+    proxy: Option<Proxy<'static>>,
+}
+
+impl CaptchaMiddleware {
+    pub fn new(solver: CaptchaSolver) -> Self {
+        Self {
+            solver,
+            default_policy: HostPolicy::new(),
+            policies: HashMap::new(),
+            max_solves: 1,
+            user_agent: None,
+            proxy: None,
+        }
+    }
+
+    /// The number of captchas solved for a single request before the blocked
+    /// response is returned. Defaults to `1`
+    pub const fn max_solves(mut self, max_solves: usize) -> Self {
+        self.max_solves = max_solves;
+        self
+    }
+
+    /// The policy of the hosts without one of their own
+    pub fn default_policy(mut self, policy: HostPolicy) -> Self {
+        self.default_policy = policy;
+        self
+    }
+
+    /// The policy of the host and its subdomains
+    pub fn policy(mut self, host: impl Into<String>, policy: HostPolicy) -> Self {
+        self.policies.insert(host.into(), policy);
+        self
+    }
+
+    /// The user agent of the client, used when requests don't set their own.
+    /// DataDome captchas are only solved if a user agent is known
+    pub fn user_agent(mut self, user_agent: Option<String>) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// The proxy of the client, which the tasks are solved through.
+    /// DataDome captchas are only solved if it is set
+    pub fn proxy(mut self, proxy: Option<Proxy<'static>>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    fn host_policy(&self, url: &Url) -> &HostPolicy {
+        let mut host = url.host_str().unwrap_or_default();
+
+        loop {
+            if let Some(policy) = self.policies.get(host) {
+                return policy;
+            }
+
+            match host.split_once('.') {
+                Some((_, parent)) => host = parent,
+                None => return &self.default_policy,
+            }
+        }
+    }
+
+    /// Solves the challenge and applies its solution to the request, returning
+    /// whether the request can be retried
+    async fn fix(&self, request: &mut Request, blocked: &BlockedResponse) -> Result<bool> {
+        let cookies = apply::cookies(request);
+        let user_agent = request
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|x| x.to_str().ok())
+            .or(self.user_agent.as_deref());
+
+        let context = TaskContext {
+            user_agent,
+            cookies: &cookies,
+        };
+
+        let Some(task) = blocked.build_task(context, self.proxy.clone())? else {
+            return Ok(false);
+        };
+
+        let Some(application) = fix::solve(&self.solver, task).await? else {
+            return Ok(false);
+        };
+
+        application.apply_to(request);
+        Ok(true)
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for CaptchaMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let policy = self.host_policy(request.url());
+
+        // Requests with streamed bodies cannot be sent again
+        let Some(mut retry) = request.try_clone() else {
+            return next.run(request, extensions).await;
+        };
+
+        let mut response = next.clone().run(request, extensions).await?;
+
+        for _ in 0..policy.solve_limit(self.max_solves) {
+            let (read, blocked) = inspect(response).await?;
+            response = read;
+
+            let Some(blocked) = blocked.filter(|x| policy.allows(x.vendor())) else {
+                break;
+            };
+
+            let is_fixed = self
+                .fix(&mut retry, &blocked)
+                .await
+                .map_err(reqwest_middleware::Error::middleware)?;
+
+            let Some(request) = retry.try_clone().filter(|_| is_fixed) else {
+                break;
+            };
+
+            response = next.clone().run(request, extensions).await?;
+        }
+
+        Ok(response)
+    }
+}
+
+/// Classifies the response, reading its body only if its status or headers
+/// suggest it was blocked
+async fn inspect(
+    response: Response,
+) -> reqwest_middleware::Result<(Response, Option<BlockedResponse>)> {
+    let headers = response.headers();
+    let is_suspect = matches!(response.status().as_u16(), 202 | 403 | 405 | 429 | 503)
+        || ["x-dd-b", "cf-mitigated", "x-amzn-waf-action"]
+            .iter()
+            .any(|x| headers.contains_key(*x));
+
+    if !is_suspect {
+        return Ok((response, None));
+    }
+
+    let url = response.url().to_string();
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+
+    let blocked = classify(&url, status, &headers, &String::from_utf8_lossy(&body))
+        .ok()
+        .flatten();
+
+    let mut read = http::Response::new(body);
+    *read.status_mut() = status;
+    *read.version_mut() = version;
+    *read.headers_mut() = headers;
+
+    Ok((Response::from(read), blocked))
+}
+
+#[cfg(test)]
+mod test {
+    use url::Url;
+
+    use super::{CaptchaMiddleware, HostPolicy};
+    use crate::{
+        proxy::{Address, Proxy, ProxyType},
+        test_server::{self, RequestLog, Response},
+    };
+
+    /// Emulates both the 2captcha API and a site protected by DataDome
+    fn server() -> (Url, RequestLog) {
+        test_server::serve_logged(|request, _| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => {
+                Response::task_ready(r#"{"cookie":"datadome=solved; Max-Age=31536000; Path=/"}"#)
+            }
+            _ if request
+                .header("cookie")
+                .is_some_and(|x| x.contains("datadome=solved")) =>
+            {
+                Response::ok("welcome")
+            }
+            path => {
+                let t = if path == "/banned" { "bv" } else { "fe" };
+                Response {
+                    status: "403 Forbidden",
+                    headers: "x-datadome: protected\r\n".to_owned(),
+                    body: format!(
+                        r#"{{"url":"https://geo.captcha-delivery.com/captcha/?initialCid=abc&hash=def&cid=ghi&t={t}&s=1"}}"#
+                    ),
+                }
+            }
+        })
+    }
+
+    fn protected_client(url: &Url, policy: HostPolicy) -> reqwest_middleware::ClientWithMiddleware {
+        let solver = test_server::solver(url.clone());
+
+        let middleware = CaptchaMiddleware::new(solver)
+            .user_agent(Some("Mozilla/5.0".into()))
+            .proxy(Some(Proxy {
+                proxy_type: ProxyType::Http,
+                proxy_address: Address::HostName("proxy.example.com".into()),
+                proxy_port: "8080".into(),
+                proxy_login: None,
+                proxy_password: None,
+            }))
+            .default_policy(policy);
+
+        reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(middleware)
+            .build()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn solves_and_retries() {
+        let (url, requests) = server();
+        let client = protected_client(&url, HostPolicy::new());
+
+        let response = client
+            .get(url.join("/products").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "welcome");
+        assert_eq!(requests.count("/createTask"), 1);
+
+        let response = client
+            .get(url.join("/banned").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+        assert_eq!(requests.count("/createTask"), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn respects_policies() {
+        let (url, requests) = server();
+
+        let client = protected_client(&url, HostPolicy::disabled());
+        let response = client
+            .get(url.join("/products").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+
+        let client = protected_client(&url, HostPolicy::new().max_solves(Some(0)));
+        let response = client
+            .get(url.join("/products").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+
+        assert_eq!(requests.count("/createTask"), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resubmits_widget_forms() {
+        let form = r#"<form method="post"><div class="g-recaptcha" data-sitekey="6Le-wvkSAAAAAPBMRTvw0Q4Muexq9bi0DJwx_mJ-"></div></form>"#;
+        let challenge = r#"<script>window._cf_chl_opt = { cType: 'managed', cRay: '8a1b2c3d4e5f6789' };</script>"#;
+
+        let (url, requests) =
+            test_server::serve_logged(move |request, _| match request.path.as_str() {
+                "/createTask" => Response::task_created(),
+                "/getTaskResult" => {
+                    Response::task_ready(r#"{"gRecaptchaResponse":"TOKEN","token":"TOKEN"}"#)
+                }
+                "/login" if request.body.contains("g-recaptcha-response=TOKEN") => {
+                    Response::ok(request.body.clone())
+                }
+                "/login" => Response {
+                    status: "403 Forbidden",
+                    headers: String::new(),
+                    body: form.to_owned(),
+                },
+                _ => Response {
+                    status: "403 Forbidden",
+                    headers: "cf-mitigated: challenge\r\nserver: cloudflare\r\n".to_owned(),
+                    body: challenge.to_owned(),
+                },
+            });
+
+        let client = protected_client(&url, HostPolicy::new());
+
+        let response = client
+            .post(url.join("/login").unwrap())
+            .form(&[("user", "name")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("user=name"));
+        assert!(requests.bodies("/createTask")[0]
+            .contains(r#""websiteKey":"6Le-wvkSAAAAAPBMRTvw0Q4Muexq9bi0DJwx_mJ-""#));
+
+        // Cloudflare challenge pages need the page's own script, so they're returned as they are
+        let response = client
+            .get(url.join("/products").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+        assert_eq!(requests.count("/createTask"), 1);
+    }
+}
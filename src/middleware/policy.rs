@@ -0,0 +1,58 @@
+use crate::detect::Vendor;
+
+/// How [`super::CaptchaMiddleware`] treats the blocked responses of a host
+#[derive(Debug, Clone, Default)]
+pub struct HostPolicy {
+    is_disabled: bool,
+    vendors: Option<Vec<Vendor>>,
+    max_solves: Option<usize>,
+}
+
+impl HostPolicy {
+    /// A policy that solves every challenge it can
+    pub const fn new() -> Self {
+        Self {
+            is_disabled: false,
+            vendors: None,
+            max_solves: None,
+        }
+    }
+
+    /// A policy that passes blocked responses through untouched
+    pub const fn disabled() -> Self {
+        Self {
+            is_disabled: true,
+            vendors: None,
+            max_solves: None,
+        }
+    }
+
+    /// Only solves the challenges of these vendors. Rate limits are never solved
+    pub fn vendors(mut self, vendors: Option<Vec<Vendor>>) -> Self {
+        self.vendors = vendors;
+        self
+    }
+
+    /// Overrides [`super::CaptchaMiddleware::max_solves`] for this host
+    pub const fn max_solves(mut self, max_solves: Option<usize>) -> Self {
+        self.max_solves = max_solves;
+        self
+    }
+
+    pub(super) fn allows(&self, vendor: Option<Vendor>) -> bool {
+        !self.is_disabled
+            && vendor.is_some_and(|vendor| {
+                self.vendors
+                    .as_ref()
+                    .is_none_or(|vendors| vendors.contains(&vendor))
+            })
+    }
+
+    pub(super) fn solve_limit(&self, default: usize) -> usize {
+        if self.is_disabled {
+            0
+        } else {
+            self.max_solves.unwrap_or(default)
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Turning borrowed tasks and solutions into `'static` ones
+//!
+//! Tasks and solutions borrow their strings with [`Cow`], which avoids copies
+//! but ties them to the data they were built from. [`IntoOwned`] copies the
+//! borrowed data, so they can be stored, sent to other threads or tasks, and
+//! submitted again later. Tasks also have an inherent `into_owned` method.
+//!
+//! # Example
+//! ```
+//! use captcha_oxide::{
+//!     captcha_types::normal_captcha::NormalCaptcha,
+//!     CaptchaTask,
+//! };
+//!
+//! fn queued_task(body: &str) -> NormalCaptcha<'static> {
+//!     NormalCaptcha::builder().body(body).build().into_owned()
+//! }
+//!
+//! let body = String::from("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==");
+//! let task = queued_task(&body);
+//! drop(body);
+//! # let _ = task;
+//! ```
+
+use std::borrow::Cow;
+
+pub(crate) use captcha_oxide_derive::IntoOwned;
+
+/// A type that can be turned into a version of itself that doesn't borrow any data
+pub trait IntoOwned {
+    type Owned;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl IntoOwned for Cow<'_, str> {
+    type Owned = Cow<'static, str>;
+
+    fn into_owned(self) -> Cow<'static, str> {
+        Cow::Owned(Cow::into_owned(self))
+    }
+}
+
+impl<T> IntoOwned for Option<T>
+where
+    T: IntoOwned,
+{
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(IntoOwned::into_owned)
+    }
+}
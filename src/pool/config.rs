@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// How many tokens a [`super::TokenPool`] keeps ready and how it refills them
+#[derive(Debug, Clone, Copy)]
+pub struct PoolConfig {
+    pub(super) size: usize,
+    pub(super) max_size: usize,
+    pub(super) max_concurrency: usize,
+    pub(super) validity: Option<Duration>,
+}
+
+impl PoolConfig {
+    /// Keeps 2 tokens of each key ready, up to 10 when the demand is high,
+    /// solving at most 4 tasks at once
+    pub const fn new() -> Self {
+        Self {
+            size: 2,
+            max_size: 10,
+            max_concurrency: 4,
+            validity: None,
+        }
+    }
+
+    /// The number of tokens kept ready for each key when there is little demand
+    pub const fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// The number of tokens kept ready for each key when the demand is high.
+    /// The pool keeps enough tokens to cover the demand observed over the last
+    /// minute for as long as a task takes to be solved
+    pub const fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The number of tasks the pool solves at the same time, across all keys.
+    /// A concurrency of 0 is treated as 1, as no token could be solved otherwise
+    pub const fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = match max_concurrency {
+            0 => 1,
+            x => x,
+        };
+        self
+    }
+
+    /// How long a token can be used after it is received, which defaults to
+    /// [`crate::solution::CaptchaSolution::remaining`], or 2 minutes for the
+    /// tasks that don't set a validity. Expired tokens are dropped and replaced
+    pub const fn validity(mut self, validity: Option<Duration>) -> Self {
+        self.validity = validity;
+        self
+    }
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,540 @@
+//! A pool of prefetched tokens for token captchas
+//!
+//! Token captchas, such as reCAPTCHA v3, Turnstile or hCaptcha, take from 15 to
+//! 60 seconds to be solved. A [`TokenPool`] solves them ahead of time, so a
+//! token is ready as soon as it's needed. Each [`PoolKey`] registered in the
+//! pool is refilled in the background to keep [`PoolConfig::size`] tokens
+//! ready, or more when they are taken faster than they can be solved. Tokens
+//! are handed out at most once and dropped when they expire.
+//!
+//! The pool spawns its refill tasks on the current [`tokio`] runtime
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     captcha_types::recaptcha::RecaptchaV3,
+//!     pool::{PoolConfig, PoolKey, TokenPool},
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = TokenPool::new(
+//!     CaptchaSolver::new("YOUR_API_KEY"),
+//!     PoolConfig::new().size(5),
+//! );
+//!
+//! let key = PoolKey::new("https://example.com/login", "SOME_SITE_KEY");
+//! pool.register(key.clone(), |key| {
+//!     RecaptchaV3::builder()
+//!         .website_url(key.website_url.clone())
+//!         .website_key(key.website_key.clone())
+//!         .min_score(0.7)
+//!         .build()
+//! });
+//!
+//! let token = pool.take(&key).await?.expect("The key was registered");
+//! # Ok(())
+//! # }
+//! ```
+
+mod config;
+
+pub use config::PoolConfig;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{Notify, Semaphore},
+    time::Instant,
+};
+
+use crate::{prelude::*, solution::CaptchaSolution, CaptchaSolver, CaptchaTask, Error};
+
+/// How far back the takes of a key are counted to estimate its demand
+const DEMAND_WINDOW: Duration = Duration::from_secs(60);
+
+/// How long a task is assumed to take before one is solved
+const ASSUMED_SOLVE_TIME: Duration = Duration::from_secs(30);
+
+/// How often the expiry of the ready tokens is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a token is assumed to be valid for when neither
+/// the task nor the config say otherwise
+const DEFAULT_VALIDITY: Duration = Duration::from_secs(120);
+
+/// How long a key waits before refilling again after a task fails
+const ERROR_BACKOFF: Duration = Duration::from_secs(5);
+
+type Factory<T> = Arc<dyn Fn(&PoolKey) -> Result<T> + Send + Sync>;
+
+/// The parameters that identify the tokens of a site
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PoolKey {
+    pub website_url: String,
+    pub website_key: String,
+}
+
+impl PoolKey {
+    pub fn new(website_url: impl Into<String>, website_key: impl Into<String>) -> Self {
+        Self {
+            website_url: website_url.into(),
+            website_key: website_key.into(),
+        }
+    }
+}
+
+/// Keeps solved tokens of a task type ready for each registered [`PoolKey`]
+pub struct TokenPool<T: CaptchaTask> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T: CaptchaTask> {
+    solver: CaptchaSolver,
+    config: PoolConfig,
+    permits: Semaphore,
+    slots: Mutex<HashMap<PoolKey, Slot<T>>>,
+}
+
+struct Slot<T: CaptchaTask> {
+    factory: Factory<T>,
+    tokens: VecDeque<Token<T::Solution>>,
+    in_flight: usize,
+    takes: VecDeque<Instant>,
+    solve_time: Option<Duration>,
+    paused_until: Option<Instant>,
+
+    /// The error of the last task that failed, and when it did
+    error: Option<(Instant, Error)>,
+
+    /// Notified when a token is added or a task fails
+    ready: Arc<Notify>,
+
+    /// Notified when the number of tokens changes, waking the refill loop
+    changed: Arc<Notify>,
+}
+
+struct Token<S> {
+    solution: CaptchaSolution<'static, S>,
+    expires_at: Instant,
+}
+
+impl<T> TokenPool<T>
+where
+    T: CaptchaTask + Send + Sync + 'static,
+    T::Solution: Send + 'static,
+{
+    pub fn new(solver: CaptchaSolver, config: PoolConfig) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                solver,
+                config,
+                permits: Semaphore::new(config.max_concurrency),
+                slots: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Starts keeping tokens of `key` ready, building their tasks with `factory`.
+    /// Registering a key again only replaces its factory
+    ///
+    /// # Panics
+    /// This method panics if it's not called from a [`tokio`] runtime
+    pub fn register(
+        &self,
+        key: PoolKey,
+        factory: impl Fn(&PoolKey) -> Result<T> + Send + Sync + 'static,
+    ) {
+        let mut slots = self.shared.lock();
+
+        if let Some(slot) = slots.get_mut(&key) {
+            slot.factory = Arc::new(factory);
+            return;
+        }
+
+        let slot = Slot::new(Arc::new(factory));
+        let changed = Arc::clone(&slot.changed);
+        slots.insert(key.clone(), slot);
+
+        tokio::spawn(refill_loop(Arc::downgrade(&self.shared), key, changed));
+    }
+
+    /// Takes a token of `key`, waiting for one to be solved if none are ready.
+    /// Returns [`None`] if the key was never registered
+    ///
+    /// # Errors
+    /// This method returns the error of the task if one of the key fails
+    /// while waiting, e.g.: because the API key is invalid, instead of
+    /// waiting for the tasks that are retried after it
+    pub async fn take(
+        &self,
+        key: &PoolKey,
+    ) -> Result<Option<CaptchaSolution<'static, T::Solution>>> {
+        let since = Instant::now();
+        let Some(ready) = self.shared.record_take(key) else {
+            return Ok(None);
+        };
+
+        loop {
+            if let Some(token) = self.shared.pop(key).flatten() {
+                return Ok(Some(token));
+            }
+
+            if let Some(error) = self.shared.take_error(key, since) {
+                return Err(error);
+            }
+
+            ready.notified().await;
+        }
+    }
+
+    /// Takes a token of `key` if one is ready
+    pub fn try_take(&self, key: &PoolKey) -> Option<CaptchaSolution<'static, T::Solution>> {
+        self.shared.record_take(key)?;
+        self.shared.pop(key).flatten()
+    }
+
+    /// The number of tokens of `key` that are ready to be taken
+    pub fn available(&self, key: &PoolKey) -> usize {
+        let now = Instant::now();
+
+        self.shared.lock().get(key).map_or(0, |slot| {
+            slot.tokens.iter().filter(|x| x.expires_at > now).count()
+        })
+    }
+}
+
+impl<T> Shared<T>
+where
+    T: CaptchaTask + Send + Sync + 'static,
+    T::Solution: Send + 'static,
+{
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PoolKey, Slot<T>>> {
+        self.slots.lock().unwrap_or_else(|x| x.into_inner())
+    }
+
+    /// Counts a take towards the demand of `key`, returning the [`Notify`]
+    /// its new tokens are announced on
+    fn record_take(&self, key: &PoolKey) -> Option<Arc<Notify>> {
+        let mut slots = self.lock();
+        let slot = slots.get_mut(key)?;
+
+        slot.takes.push_back(Instant::now());
+        slot.changed.notify_one();
+
+        Some(Arc::clone(&slot.ready))
+    }
+
+    /// Removes the oldest token of `key` that hasn't expired. The outer [`Option`]
+    /// is [`None`] if the key isn't registered
+    fn pop(&self, key: &PoolKey) -> Option<Option<CaptchaSolution<'static, T::Solution>>> {
+        let mut slots = self.lock();
+        let slot = slots.get_mut(key)?;
+
+        slot.prune(Instant::now());
+        let token = slot.tokens.pop_front();
+
+        if token.is_some() {
+            slot.changed.notify_one();
+        }
+
+        Some(token.map(|x| x.solution))
+    }
+
+    /// Removes the error of the last task of `key` that failed, if it did after `since`
+    fn take_error(&self, key: &PoolKey, since: Instant) -> Option<Error> {
+        let mut slots = self.lock();
+        let slot = slots.get_mut(key)?;
+
+        match slot.error {
+            Some((failed_at, _)) if failed_at >= since => slot.error.take().map(|x| x.1),
+            _ => None,
+        }
+    }
+
+    /// Reserves the tasks needed to reach the target size of `key`,
+    /// returning how many and the factory to build them with
+    fn reserve(&self, key: &PoolKey) -> Option<(usize, Factory<T>)> {
+        let now = Instant::now();
+        let mut slots = self.lock();
+        let slot = slots.get_mut(key)?;
+
+        slot.prune(now);
+
+        if slot.paused_until.is_some_and(|x| x > now) {
+            return Some((0, Arc::clone(&slot.factory)));
+        }
+
+        let missing = slot
+            .target(&self.config)
+            .saturating_sub(slot.tokens.len() + slot.in_flight);
+        slot.in_flight += missing;
+
+        Some((missing, Arc::clone(&slot.factory)))
+    }
+
+    fn finish(
+        &self,
+        key: &PoolKey,
+        result: Result<(CaptchaSolution<'static, T::Solution>, Duration)>,
+    ) {
+        let now = Instant::now();
+        let mut slots = self.lock();
+        let Some(slot) = slots.get_mut(key) else {
+            return;
+        };
+
+        slot.in_flight -= 1;
+
+        match result {
+            Ok((solution, solve_time)) => {
+                slot.solve_time = Some(match slot.solve_time {
+                    Some(average) => (average * 3 + solve_time) / 4,
+                    None => solve_time,
+                });
+
+                let validity = self
+                    .config
+                    .validity
+                    .or_else(|| solution.remaining())
+                    .unwrap_or(DEFAULT_VALIDITY);
+
+                slot.tokens.push_back(Token {
+                    solution,
+                    expires_at: now + validity,
+                });
+                slot.error = None;
+            }
+            Err(error) => {
+                slot.paused_until = Some(now + ERROR_BACKOFF);
+                slot.error = Some((now, error));
+            }
+        }
+
+        slot.ready.notify_one();
+
+        slot.changed.notify_one();
+    }
+
+    async fn solve(&self, key: &PoolKey, factory: Factory<T>) {
+        let result = async {
+            let _permit = self
+                .permits
+                .acquire()
+                .await
+                .expect("The semaphore is never closed");
+
+            let start = Instant::now();
+            let solution = self
+                .solver
+                .solve(factory(key)?)
+                .await?
+                .ok_or(Error::CallbackUrlSet)?;
+
+            Ok((solution, start.elapsed()))
+        }
+        .await;
+
+        self.finish(key, result);
+    }
+}
+
+impl<T: CaptchaTask> Slot<T> {
+    fn new(factory: Factory<T>) -> Self {
+        Self {
+            factory,
+            tokens: VecDeque::new(),
+            in_flight: 0,
+            takes: VecDeque::new(),
+            solve_time: None,
+            paused_until: None,
+            error: None,
+            ready: Arc::new(Notify::new()),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.tokens.retain(|x| x.expires_at > now);
+
+        while self
+            .takes
+            .front()
+            .is_some_and(|x| now.duration_since(*x) > DEMAND_WINDOW)
+        {
+            self.takes.pop_front();
+        }
+    }
+
+    /// The number of tokens needed to cover the observed demand while
+    /// new ones are solved, within the bounds of the config
+    fn target(&self, config: &PoolConfig) -> usize {
+        let rate = self.takes.len() as f64 / DEMAND_WINDOW.as_secs_f64();
+        let solve_time = self.solve_time.unwrap_or(ASSUMED_SOLVE_TIME);
+        let demand = (rate * solve_time.as_secs_f64()).ceil() as usize;
+
+        config.size.max(demand).min(config.max_size)
+    }
+}
+
+/// Keeps the tokens of `key` topped up until the pool is dropped
+async fn refill_loop<T>(shared: Weak<Shared<T>>, key: PoolKey, changed: Arc<Notify>)
+where
+    T: CaptchaTask + Send + Sync + 'static,
+    T::Solution: Send + 'static,
+{
+    loop {
+        let Some(pool) = shared.upgrade() else {
+            return;
+        };
+
+        let Some((missing, factory)) = pool.reserve(&key) else {
+            return;
+        };
+
+        for _ in 0..missing {
+            let pool = Arc::clone(&pool);
+            let key = key.clone();
+            let factory = Arc::clone(&factory);
+
+            tokio::spawn(async move { pool.solve(&key, factory).await });
+        }
+
+        drop(pool);
+
+        let _ = tokio::time::timeout(CHECK_INTERVAL, changed.notified()).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use url::Url;
+
+    use super::{PoolConfig, PoolKey, Slot, TokenPool};
+    use crate::{
+        captcha_types::recaptcha::RecaptchaV3,
+        solver::SolveError,
+        test_server::{self, RequestLog, Response},
+        CaptchaTask, Error,
+    };
+
+    /// Emulates the 2captcha API, solving every task with a new token
+    fn server() -> (Url, RequestLog) {
+        test_server::serve_logged(|request, log| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            _ => {
+                let token = log.count("/getTaskResult");
+                Response::task_ready(&format!(
+                    r#"{{"gRecaptchaResponse":"TOKEN-{token}","token":"TOKEN-{token}"}}"#
+                ))
+            }
+        })
+    }
+
+    fn pool(url: &Url) -> (TokenPool<RecaptchaV3<'static>>, PoolKey) {
+        let solver = test_server::solver(url.clone());
+
+        let pool = TokenPool::new(
+            solver,
+            PoolConfig::new()
+                .size(2)
+                .max_concurrency(2)
+                .validity(Some(Duration::from_secs(120))),
+        );
+
+        let key = PoolKey::new("https://example.com/", "SITE_KEY");
+        pool.register(key.clone(), |key| {
+            RecaptchaV3::builder()
+                .website_url(key.website_url.clone())
+                .website_key(key.website_key.clone())
+                .min_score(0.7)
+                .build()
+        });
+
+        (pool, key)
+    }
+
+    /// The mock server answers in real time while the clock
+    /// is paused, so its progress has to be polled for
+    async fn eventually(condition: impl Fn() -> bool) -> bool {
+        for _ in 0..600 {
+            if condition() {
+                return true;
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        false
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hands_out_tokens_once() {
+        let (url, requests) = server();
+        let (pool, key) = pool(&url);
+
+        assert!(pool
+            .take(&PoolKey::new("https://other.com/", "KEY"))
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut tokens = Vec::new();
+        for _ in 0..4 {
+            let token = pool.take(&key).await.unwrap().unwrap();
+            tokens.push(token.solution.g_recaptcha_response.into_owned());
+        }
+
+        tokens.sort_unstable();
+        tokens.dedup();
+        assert_eq!(tokens.len(), 4);
+
+        assert!(eventually(|| pool.available(&key) == 2).await);
+
+        let created = requests.count("/createTask");
+        tokio::time::sleep(Duration::from_secs(121)).await;
+        assert!(eventually(|| requests.count("/createTask") > created).await);
+        assert!(eventually(|| pool.available(&key) == 2).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn surfaces_task_errors() {
+        let url = test_server::serve(|_| {
+            Response::ok(r#"{"errorId":1,"errorCode":"ERROR_KEY_DOES_NOT_EXIST"}"#)
+        });
+        let (pool, key) = pool(&url);
+
+        assert!(matches!(
+            pool.take(&key).await,
+            Err(Error::TwoCaptchaError(SolveError::InvalidApiKey))
+        ));
+    }
+
+    #[test]
+    fn clamps_concurrency() {
+        assert_eq!(PoolConfig::new().max_concurrency(0).max_concurrency, 1);
+    }
+
+    #[test]
+    fn scales_with_demand() {
+        let config = PoolConfig::new().size(2).max_size(8);
+        let mut slot = Slot::<RecaptchaV3<'static>>::new(Arc::new(|_| unreachable!()));
+        slot.solve_time = Some(Duration::from_secs(30));
+        assert_eq!(slot.target(&config), 2);
+
+        let now = tokio::time::Instant::now();
+        slot.takes.extend(std::iter::repeat_n(now, 10));
+        assert_eq!(slot.target(&config), 5);
+
+        slot.takes.extend(std::iter::repeat_n(now, 50));
+        assert_eq!(slot.target(&config), 8);
+    }
+}
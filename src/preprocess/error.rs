@@ -0,0 +1,21 @@
+/// Represents all the errors that can happen while preprocessing an image
+#[derive(thiserror::Error, Debug)]
+pub enum PreprocessError {
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("The crop rectangle does not overlap the image")]
+    EmptyCrop,
+
+    #[error("The image could not be compressed below {0} bytes")]
+    CannotFit(usize),
+
+    #[error("Expected {expected} tiles, but found {found}")]
+    TileCount { expected: usize, found: usize },
+
+    #[error("There is no tile with the index {0} in the grid")]
+    InvalidTile(u8),
+}
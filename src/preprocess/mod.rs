@@ -0,0 +1,444 @@
+//! Image preprocessing for image based captcha tasks
+//!
+//! 2captcha rejects images larger than 100kB or 600px on any side with
+//! [`crate::solver::error::SolveError::ImageTooBig`], which is common when the
+//! captcha is taken from a screenshot. The [`Preprocessor`] crops, downscales,
+//! filters and recompresses images until they fit, while keeping track of the
+//! [`ImageTransform`] required to map the workers' answers back onto the
+//! original image.
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     CaptchaTask,
+//!     captcha_types::coordinates_captcha::CoordinatesCaptcha,
+//!     preprocess::{Crop, Preprocessor},
+//! };
+//!
+//! # fn main() -> Result<(), captcha_oxide::Error> {
+//! # let screenshot: Vec<u8> = vec![];
+//! let processed = Preprocessor::new()
+//!     .crop(Some(Crop::new(120, 340, 800, 600)))
+//!     .grayscale(true)
+//!     .process(&screenshot)?;
+//!
+//! let captcha = CoordinatesCaptcha::builder()
+//!     .body(processed.body)
+//!     .comment(Some("Click the green apple"))
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod transform;
+
+pub use error::PreprocessError;
+pub use transform::ImageTransform;
+
+use std::io::Cursor;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{imageops::FilterType, DynamicImage, ImageOutputFormat};
+
+use crate::geometry::Rect;
+
+/// The largest file size accepted by 2captcha, in bytes
+pub const MAX_BYTES: usize = 100_000;
+
+/// The largest dimension accepted by 2captcha, in pixels
+pub const MAX_DIMENSION: u32 = 600;
+
+/// The images are never downscaled below this size while trying
+/// to fit them into [`Preprocessor::max_bytes`]
+const MIN_DIMENSION: u32 = 16;
+
+/// Each downscaling step shrinks the image to this fraction of its size
+const DOWNSCALE_STEP: f64 = 0.8;
+
+/// A rectangle of the original image, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<Rect> for Crop {
+    /// Converts the rectangle into the smallest crop containing it
+    fn from(value: Rect) -> Self {
+        let x = value.x_min.floor().max(0.0) as u32;
+        let y = value.y_min.floor().max(0.0) as u32;
+
+        Self {
+            x,
+            y,
+            width: (value.x_max.ceil() as u32).saturating_sub(x),
+            height: (value.y_max.ceil() as u32).saturating_sub(y),
+        }
+    }
+}
+
+impl Crop {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The format the processed image will be encoded with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Lossless, the image is downscaled until it fits
+    Png,
+
+    /// Lossy, the quality is lowered from `quality` in steps of 10
+    /// (down to 30) before the image is downscaled
+    Jpeg { quality: u8 },
+
+    /// Lossless and limited to 256 colors, the image is downscaled until it fits
+    Gif,
+}
+
+impl OutputFormat {
+    /// The default JPEG quality, used as the starting point when recompressing
+    pub const DEFAULT_JPEG: Self = Self::Jpeg { quality: 90 };
+
+    fn qualities(&self) -> Vec<Option<u8>> {
+        match self {
+            Self::Jpeg { quality } => {
+                let quality = (*quality).clamp(1, 100);
+                std::iter::successors(Some(quality), |x| x.checked_sub(10).filter(|x| *x >= 30))
+                    .map(Some)
+                    .collect()
+            }
+            Self::Png | Self::Gif => vec![None],
+        }
+    }
+
+    /// The MIME type of the encoded image
+    pub const fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg { .. } => "image/jpeg",
+            Self::Gif => "image/gif",
+        }
+    }
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        Self::DEFAULT_JPEG
+    }
+}
+
+/// The result of running an image through a [`Preprocessor`]
+#[derive(Debug, Clone)]
+pub struct PreprocessedImage {
+    /// The processed image encoded into Base64 format, ready
+    /// to be used as the `body` of an image task
+    pub body: String,
+
+    /// The width of the processed image
+    pub width: u32,
+
+    /// The height of the processed image
+    pub height: u32,
+
+    /// The format the image was encoded with
+    pub format: OutputFormat,
+
+    /// Maps coordinates of the processed image back onto the original one
+    pub transform: ImageTransform,
+}
+
+impl PreprocessedImage {
+    /// The processed image in the Data-URI format
+    pub fn data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.format.mime_type(), self.body)
+    }
+}
+
+/// A configurable preprocessing pipeline for image tasks.
+///
+/// The steps are applied in the following order:
+/// 1. [`Preprocessor::crop`]
+/// 2. [`Preprocessor::grayscale`]
+/// 3. [`Preprocessor::contrast`]
+/// 4. Downscaling to fit [`Preprocessor::max_dimension`], keeping the aspect ratio
+/// 5. Encoding with [`Preprocessor::format`], lowering the quality and
+///    downscaling further until the file fits [`Preprocessor::max_bytes`]
+#[derive(Debug, Clone)]
+pub struct Preprocessor {
+    crop: Option<Crop>,
+    grayscale: bool,
+    contrast: Option<f32>,
+    max_dimension: u32,
+    max_bytes: usize,
+    format: OutputFormat,
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Preprocessor {
+    /// Returns a [`Preprocessor`] that only ensures the image fits
+    /// within the limits imposed by 2captcha
+    pub const fn new() -> Self {
+        Self {
+            crop: None,
+            grayscale: false,
+            contrast: None,
+            max_dimension: MAX_DIMENSION,
+            max_bytes: MAX_BYTES,
+            format: OutputFormat::DEFAULT_JPEG,
+        }
+    }
+
+    /// Crops the image to the given rectangle before anything else is done,
+    /// e.g.: to extract a captcha element from a full page screenshot
+    pub fn crop(mut self, crop: Option<Crop>) -> Self {
+        self.crop = crop;
+        self
+    }
+
+    /// Converts the image to grayscale
+    pub fn grayscale(mut self, grayscale: bool) -> Self {
+        self.grayscale = grayscale;
+        self
+    }
+
+    /// Adjusts the contrast of the image. Positive values increase
+    /// the contrast and negative values decrease it
+    pub fn contrast(mut self, contrast: Option<f32>) -> Self {
+        self.contrast = contrast;
+        self
+    }
+
+    /// The largest size the image may have on any side. Default value: `600`
+    pub fn max_dimension(mut self, max_dimension: u32) -> Self {
+        self.max_dimension = max_dimension.max(MIN_DIMENSION);
+        self
+    }
+
+    /// The largest file size the encoded image may have. Default value: `100_000`
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// The format the processed image will be encoded with.
+    /// Default value: [`OutputFormat::DEFAULT_JPEG`]
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Runs the pipeline on an encoded image file, such as the bytes of a PNG screenshot
+    ///
+    /// # Errors
+    /// This method will error if the image cannot be decoded, if the crop rectangle
+    /// doesn't overlap the image or if the image cannot be compressed enough
+    pub fn process(&self, bytes: &[u8]) -> Result<PreprocessedImage, PreprocessError> {
+        self.process_image(image::load_from_memory(bytes)?)
+    }
+
+    /// Runs the pipeline on a Base64 encoded image, such as the `body` of an
+    /// image task. The Data-URI format is also supported
+    ///
+    /// # Errors
+    /// This method will error if the image cannot be decoded, if the crop rectangle
+    /// doesn't overlap the image or if the image cannot be compressed enough
+    pub fn process_base64(&self, body: &str) -> Result<PreprocessedImage, PreprocessError> {
+        self.process_image(decode_body(body)?)
+    }
+
+    /// Runs the pipeline on an already decoded image
+    ///
+    /// # Errors
+    /// This method will error if the crop rectangle doesn't overlap the image
+    /// or if the image cannot be compressed enough
+    pub fn process_image(&self, image: DynamicImage) -> Result<PreprocessedImage, PreprocessError> {
+        let (image, offset_x, offset_y) = match self.crop {
+            Some(crop) => {
+                let x = crop.x.min(image.width());
+                let y = crop.y.min(image.height());
+                let width = crop.width.min(image.width() - x);
+                let height = crop.height.min(image.height() - y);
+
+                if width == 0 || height == 0 {
+                    return Err(PreprocessError::EmptyCrop);
+                }
+
+                (image.crop_imm(x, y, width, height), x, y)
+            }
+            None => (image, 0, 0),
+        };
+
+        let (cropped_width, cropped_height) = (image.width(), image.height());
+
+        let image = if self.grayscale {
+            image.grayscale()
+        } else {
+            image
+        };
+
+        let image = match self.contrast {
+            Some(contrast) => image.adjust_contrast(contrast),
+            None => image,
+        };
+
+        let mut image = if image.width().max(image.height()) > self.max_dimension {
+            image.resize(self.max_dimension, self.max_dimension, FilterType::Lanczos3)
+        } else {
+            image
+        };
+
+        loop {
+            for quality in self.format.qualities() {
+                let bytes = encode(&image, self.format, quality)?;
+
+                if bytes.len() <= self.max_bytes {
+                    return Ok(PreprocessedImage {
+                        body: STANDARD.encode(bytes),
+                        width: image.width(),
+                        height: image.height(),
+                        format: self.format,
+                        transform: ImageTransform {
+                            offset_x,
+                            offset_y,
+                            scale_x: f64::from(image.width()) / f64::from(cropped_width),
+                            scale_y: f64::from(image.height()) / f64::from(cropped_height),
+                        },
+                    });
+                }
+            }
+
+            let largest_side = image.width().max(image.height());
+            if largest_side <= MIN_DIMENSION {
+                return Err(PreprocessError::CannotFit(self.max_bytes));
+            }
+
+            let target = ((f64::from(largest_side) * DOWNSCALE_STEP) as u32).max(MIN_DIMENSION);
+            image = image.resize(target, target, FilterType::Lanczos3);
+        }
+    }
+}
+
+/// Decodes a Base64 image, such as the `body` of an image task.
+/// The Data-URI format is also supported
+///
+/// # Errors
+/// This method will error if the Base64 string or the image are invalid
+pub fn decode_body(body: &str) -> Result<DynamicImage, PreprocessError> {
+    let body = match body.strip_prefix("data:") {
+        Some(data_uri) => data_uri.split_once(',').map_or(data_uri, |(_, data)| data),
+        None => body,
+    };
+
+    let bytes = STANDARD.decode(body.trim())?;
+    Ok(image::load_from_memory(&bytes)?)
+}
+
+fn encode(
+    image: &DynamicImage,
+    format: OutputFormat,
+    quality: Option<u8>,
+) -> Result<Vec<u8>, PreprocessError> {
+    let mut bytes = Cursor::new(Vec::new());
+
+    match format {
+        OutputFormat::Png => image.write_to(&mut bytes, ImageOutputFormat::Png)?,
+        OutputFormat::Gif => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_to(&mut bytes, ImageOutputFormat::Gif)?,
+        OutputFormat::Jpeg { quality: default } => {
+            // The JPEG encoder doesn't support transparency
+            let image = match image {
+                DynamicImage::ImageLuma8(_) => image.clone(),
+                DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLuma16(_) => {
+                    DynamicImage::ImageLuma8(image.to_luma8())
+                }
+                _ => DynamicImage::ImageRgb8(image.to_rgb8()),
+            };
+
+            image.write_to(
+                &mut bytes,
+                ImageOutputFormat::Jpeg(quality.unwrap_or(default)),
+            )?
+        }
+    }
+
+    Ok(bytes.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, Rgb, RgbImage};
+
+    use super::{Crop, OutputFormat, PreprocessError, Preprocessor};
+    use crate::{captcha_types::coordinates_captcha::CoordinatesCaptchaSolution, geometry::Point};
+
+    fn noise(width: u32, height: u32) -> DynamicImage {
+        let mut seed = 0x2545_f491_u32;
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |_, _| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let [r, g, b, _] = seed.to_le_bytes();
+            Rgb([r, g, b])
+        }))
+    }
+
+    #[test]
+    fn fits_within_limits() -> Result<(), PreprocessError> {
+        let mut png = Cursor::new(Vec::new());
+        noise(1600, 1200).write_to(&mut png, ImageOutputFormat::Png)?;
+
+        let processed = Preprocessor::new().process(png.get_ref())?;
+
+        assert!(processed.width <= 600 && processed.height <= 600);
+        assert!(processed.body.len() * 3 / 4 <= super::MAX_BYTES);
+        assert_eq!(processed.width * 3, processed.height * 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn restores_coordinates() -> Result<(), PreprocessError> {
+        let processed = Preprocessor::new()
+            .crop(Some(Crop::new(100, 50, 1000, 500)))
+            .format(OutputFormat::Png)
+            .max_bytes(usize::MAX)
+            .process_image(noise(1600, 1200))?;
+
+        assert_eq!((processed.width, processed.height), (600, 300));
+
+        let solution = CoordinatesCaptchaSolution {
+            coordinates: Box::new([Point::new(300.0, 150.0)]),
+        };
+
+        let restored = processed.transform.restore_coordinates(&solution);
+        assert_eq!(restored.coordinates[0], Point::new(600.0, 300.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_crop_outside_image() {
+        let result = Preprocessor::new()
+            .crop(Some(Crop::new(700, 0, 100, 100)))
+            .process_image(noise(600, 600));
+
+        assert!(matches!(result, Err(PreprocessError::EmptyCrop)));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    captcha_types::{
+        bounding_box_captcha::BoundingBoxCaptchaSolution,
+        coordinates_captcha::CoordinatesCaptchaSolution,
+        draw_around_captcha::DrawAroundCaptchaSolution,
+    },
+    geometry::{Geometry, Point},
+};
+
+/// Describes how a [`super::PreprocessedImage`] relates to the image it
+/// was created from, so the coordinates returned by the workers can be
+/// mapped back onto the original image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageTransform {
+    /// Horizontal position of the crop rectangle inside the original image
+    pub offset_x: u32,
+
+    /// Vertical position of the crop rectangle inside the original image
+    pub offset_y: u32,
+
+    /// Ratio between the width of the processed image and the width of the crop rectangle
+    pub scale_x: f64,
+
+    /// Ratio between the height of the processed image and the height of the crop rectangle
+    pub scale_y: f64,
+}
+
+impl Default for ImageTransform {
+    fn default() -> Self {
+        Self {
+            offset_x: 0,
+            offset_y: 0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+        }
+    }
+}
+
+impl ImageTransform {
+    /// Maps a geometry from the processed image onto the original image
+    pub fn to_original<G: Geometry>(&self, geometry: &G) -> G {
+        geometry.map_points(|point| {
+            Point::new(
+                point.x / self.scale_x + f64::from(self.offset_x),
+                point.y / self.scale_y + f64::from(self.offset_y),
+            )
+        })
+    }
+
+    /// Maps a geometry from the original image onto the processed image
+    pub fn to_processed<G: Geometry>(&self, geometry: &G) -> G {
+        geometry.map_points(|point| {
+            Point::new(
+                (point.x - f64::from(self.offset_x)) * self.scale_x,
+                (point.y - f64::from(self.offset_y)) * self.scale_y,
+            )
+        })
+    }
+
+    /// Maps the points of a [`CoordinatesCaptchaSolution`] obtained for the
+    /// processed image back onto the original image
+    pub fn restore_coordinates(
+        &self,
+        solution: &CoordinatesCaptchaSolution,
+    ) -> CoordinatesCaptchaSolution {
+        CoordinatesCaptchaSolution {
+            coordinates: solution
+                .coordinates
+                .iter()
+                .map(|point| self.to_original(point))
+                .collect(),
+        }
+    }
+
+    /// Maps the boxes of a [`BoundingBoxCaptchaSolution`] obtained for the
+    /// processed image back onto the original image
+    pub fn restore_bounding_boxes(
+        &self,
+        solution: &BoundingBoxCaptchaSolution,
+    ) -> BoundingBoxCaptchaSolution {
+        BoundingBoxCaptchaSolution {
+            bounding_boxes: solution
+                .bounding_boxes
+                .iter()
+                .map(|group| group.iter().map(|rect| self.to_original(rect)).collect())
+                .collect(),
+        }
+    }
+
+    /// Maps the polygons of a [`DrawAroundCaptchaSolution`] obtained for the
+    /// processed image back onto the original image
+    pub fn restore_canvas(
+        &self,
+        solution: &DrawAroundCaptchaSolution,
+    ) -> DrawAroundCaptchaSolution {
+        DrawAroundCaptchaSolution {
+            canvas: solution
+                .canvas
+                .iter()
+                .map(|polygon| self.to_original(polygon))
+                .collect(),
+        }
+    }
+}
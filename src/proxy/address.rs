@@ -1,6 +1,6 @@
 use std::net::Ipv4Addr;
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum Address {
     IpAddress(Ipv4Addr),
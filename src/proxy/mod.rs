@@ -1,11 +1,13 @@
 use std::borrow::Cow;
 
 pub use self::{address::Address, proxy_type::ProxyType};
+use crate::owned::IntoOwned;
 
 pub mod address;
 pub mod proxy_type;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, IntoOwned)]
+#[into_owned(crate = crate)]
 #[serde(rename_all = "camelCase")]
 pub struct Proxy<'a> {
     pub proxy_type: ProxyType,
@@ -1,4 +1,4 @@
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum ProxyType {
     Http,
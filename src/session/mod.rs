@@ -0,0 +1,310 @@
+//! A browser session shared by the tasks and the requests that follow them
+//!
+//! Many sites tie a solution to the browser that obtained it, so the tasks
+//! should be solved with the same user agent, cookies and proxy that the
+//! solution is later submitted with. A [`SessionProfile`] holds them in one
+//! place: every task builder that accepts any of them has a `session` method
+//! that fills them in, and [`SessionProfile::configure`] sets them on the
+//! [`reqwest::Client`] that sends the follow-up requests.
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     apply::ApplySolution,
+//!     captcha_types::turnstile_captcha::TurnstileStandaloneCaptcha,
+//!     session::SessionProfile,
+//!     CaptchaSolver,
+//!     CaptchaTask,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let session = SessionProfile::new("Mozilla/5.0 ...")
+//!     .accept_language(Some("en-US,en;q=0.9".into()))
+//!     .cookie("session", "abc");
+//!
+//! let task = TurnstileStandaloneCaptcha::builder()
+//!     .website_url("https://example.com/login")
+//!     .website_key("SOME_SITE_KEY")
+//!     .session(&session)
+//!     .build()?;
+//!
+//! let solver = CaptchaSolver::new("YOUR_API_KEY");
+//! let solution = solver.solve(task).await?.expect("No callback url was set");
+//!
+//! if let Some(mismatch) = session.check_user_agent(&solution) {
+//!     eprintln!("{mismatch}");
+//! }
+//!
+//! let client = session.client()?;
+//! let response = solution
+//!     .apply_query(client.get("https://example.com/login"))
+//!     .send()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, COOKIE, SET_COOKIE, USER_AGENT},
+    Client, ClientBuilder,
+};
+
+use crate::{
+    apply::ApplySolution,
+    prelude::*,
+    proxy::{Address, Proxy, ProxyType},
+};
+
+/// The user agent, languages, proxy and cookies of a browser session
+#[derive(Debug, Clone)]
+pub struct SessionProfile {
+    pub user_agent: String,
+
+    /// Sent as the `Accept-Language` header of the follow-up requests
+    pub accept_language: Option<String>,
+
+    pub proxy: Option<Proxy<'static>>,
+
+    pub cookies: Vec<(String, String)>,
+}
+
+impl SessionProfile {
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            accept_language: None,
+            proxy: None,
+            cookies: Vec::new(),
+        }
+    }
+
+    pub fn accept_language(mut self, accept_language: Option<String>) -> Self {
+        self.accept_language = accept_language;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Option<Proxy<'static>>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Adds a cookie to the jar, replacing any existing cookie with the same name
+    pub fn cookie(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set_cookie(name.into(), value.into());
+        self
+    }
+
+    /// Stores the cookies set by the `Set-Cookie` headers of a response
+    pub fn store_cookies(&mut self, headers: &HeaderMap) {
+        let cookies = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|x| x.to_str().ok())
+            .filter_map(|x| x.split(';').next()?.split_once('='))
+            .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+            .collect::<Vec<_>>();
+
+        for (name, value) in cookies {
+            self.set_cookie(name, value);
+        }
+    }
+
+    /// Stores the cookies of a solution, e.g.: the `datadome` cookie
+    pub fn store_solution(&mut self, solution: &impl ApplySolution) {
+        for (name, value) in solution.application().cookies {
+            self.set_cookie(name.into_owned(), value.into_owned());
+        }
+    }
+
+    /// Compares the user agent a solution was obtained with, if it has one,
+    /// to the user agent of the session. Submitting a solution with a
+    /// different user agent usually gets it rejected
+    pub fn check_user_agent(&self, solution: &impl ApplySolution) -> Option<UserAgentMismatch> {
+        solution
+            .application()
+            .user_agent
+            .filter(|x| x.as_ref() != self.user_agent)
+            .map(|x| UserAgentMismatch {
+                expected: self.user_agent.clone(),
+                returned: x.into_owned(),
+            })
+    }
+
+    /// Sets the user agent, `Accept-Language` and cookies of the session as
+    /// default headers of the client, and routes it through the proxy
+    /// of the session. SOCKS proxies require `reqwest`'s `socks` feature
+    pub fn configure(&self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        let mut headers = HeaderMap::new();
+
+        if let Ok(user_agent) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, user_agent);
+        }
+
+        if let Some(Ok(accept_language)) =
+            self.accept_language.as_deref().map(HeaderValue::from_str)
+        {
+            headers.insert(ACCEPT_LANGUAGE, accept_language);
+        }
+
+        if let Some(Ok(cookies)) = self.cookie_header().as_deref().map(HeaderValue::from_str) {
+            headers.insert(COOKIE, cookies);
+        }
+
+        let builder = builder.default_headers(headers);
+
+        Ok(match self.proxy {
+            Some(ref proxy) => builder.proxy(reqwest_proxy(proxy)?),
+            None => builder,
+        })
+    }
+
+    /// A [`reqwest::Client`] configured with [`SessionProfile::configure`]
+    pub fn client(&self) -> Result<Client> {
+        Ok(self.configure(Client::builder())?.build()?)
+    }
+
+    fn set_cookie(&mut self, name: String, value: String) {
+        match self.cookies.iter_mut().find(|(x, _)| *x == name) {
+            Some(cookie) => cookie.1 = value,
+            None => self.cookies.push((name, value)),
+        }
+    }
+
+    fn cookie_header(&self) -> Option<String> {
+        (!self.cookies.is_empty()).then(|| {
+            self.cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+}
+
+/// A solution was obtained with a different user agent than the one of the session
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "The solution was obtained with the user agent `{returned}`, but the session uses `{expected}`"
+)]
+pub struct UserAgentMismatch {
+    pub expected: String,
+    pub returned: String,
+}
+
+fn reqwest_proxy(proxy: &Proxy<'_>) -> Result<reqwest::Proxy> {
+    let scheme = match proxy.proxy_type {
+        ProxyType::Http => "http",
+        ProxyType::Socks4 => "socks4",
+        ProxyType::Socks5 => "socks5",
+    };
+
+    let host = match proxy.proxy_address {
+        Address::IpAddress(ref x) => x.to_string(),
+        Address::HostName(ref x) => x.to_string(),
+    };
+
+    let reqwest_proxy = reqwest::Proxy::all(format!("{scheme}://{host}:{}", proxy.proxy_port))?;
+
+    Ok(match proxy.proxy_login {
+        Some(ref login) => {
+            reqwest_proxy.basic_auth(login, proxy.proxy_password.as_deref().unwrap_or_default())
+        }
+        None => reqwest_proxy,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::header::{HeaderMap, SET_COOKIE};
+    use serde_json::json;
+
+    use super::SessionProfile;
+    use crate::{
+        captcha_types::{
+            data_dome_captcha::DataDomeCaptchaSolution,
+            recaptcha::RecaptchaV2,
+            turnstile_captcha::{TurnstileCaptchaSolution, TurnstileChallengePageCaptcha},
+        },
+        proxy::{Address, Proxy, ProxyType},
+        CaptchaTask,
+    };
+
+    fn session() -> SessionProfile {
+        SessionProfile::new("Mozilla/5.0")
+            .proxy(Some(Proxy {
+                proxy_type: ProxyType::Http,
+                proxy_address: Address::HostName("proxy.example.com".into()),
+                proxy_port: "8080".into(),
+                proxy_login: Some("user".into()),
+                proxy_password: Some("pass".into()),
+            }))
+            .cookie("session", "abc")
+    }
+
+    #[test]
+    fn fills_tasks() {
+        let session = session();
+
+        let task = RecaptchaV2::builder()
+            .website_url("https://example.com/")
+            .website_key("KEY")
+            .session(&session)
+            .build()
+            .unwrap();
+
+        let task = serde_json::to_value(task).unwrap();
+        assert_eq!(task["type"], "RecaptchaV2Task");
+        assert_eq!(task["userAgent"], "Mozilla/5.0");
+        assert_eq!(task["cookies"], "session=abc");
+        assert_eq!(task["proxyAddress"], "proxy.example.com");
+
+        let task = TurnstileChallengePageCaptcha::builder()
+            .website_url("https://example.com/")
+            .website_key("KEY")
+            .action("managed")
+            .data("DATA")
+            .page_data("PAGE_DATA")
+            .session(&SessionProfile::new("Mozilla/5.0"))
+            .build()
+            .unwrap();
+
+        let task = serde_json::to_value(task).unwrap();
+        assert_eq!(task["type"], "TurnstileTaskProxyless");
+        assert_eq!(task["userAgent"], json!("Mozilla/5.0"));
+    }
+
+    #[test]
+    fn tracks_cookies_and_user_agents() {
+        let mut session = session();
+        assert!(session.client().is_ok());
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, "session=def; Path=/".parse().unwrap());
+        headers.append(SET_COOKIE, "theme=dark".parse().unwrap());
+        session.store_cookies(&headers);
+
+        session.store_solution(&DataDomeCaptchaSolution {
+            cookie: "datadome=SOLVED; Max-Age=31536000; Path=/".into(),
+        });
+
+        assert_eq!(
+            session.cookie_header().unwrap(),
+            "session=def; theme=dark; datadome=SOLVED"
+        );
+
+        let solution = TurnstileCaptchaSolution {
+            token: "TOKEN".into(),
+            user_agent: "Mozilla/5.0".into(),
+        };
+        assert_eq!(session.check_user_agent(&solution), None);
+
+        let solution = TurnstileCaptchaSolution {
+            token: "TOKEN".into(),
+            user_agent: "Other/1.0".into(),
+        };
+        let mismatch = session.check_user_agent(&solution).unwrap();
+        assert_eq!(mismatch.expected, "Mozilla/5.0");
+        assert_eq!(mismatch.returned, "Other/1.0");
+    }
+}
@@ -1,14 +1,16 @@
 use chrono::{serde::ts_seconds, DateTime, Utc};
-use std::{borrow::Cow, net::IpAddr};
+use std::{borrow::Cow, fmt, net::IpAddr, time::Duration};
 
-#[derive(serde::Deserialize, Debug)]
+use crate::owned::IntoOwned;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CaptchaSolution<'a, T> {
     /// The task id is not returned by 2captcha, instead it
     /// is manually added to the struct in order to allow the
     /// use of the [crate::CaptchaSolver::report] method without
-    /// allowing the user to see (or more importantly, change)
-    /// the task id
+    /// allowing the user to change the task id. It can be read
+    /// with [`CaptchaSolution::task_id`]
     #[serde(default = "Default::default")]
     pub(crate) task_id: u64,
 
@@ -31,4 +33,163 @@ pub struct CaptchaSolution<'a, T> {
 
     /// The IP address that submitted the task request
     pub ip: IpAddr,
+
+    /// How long the solution can be used after the task was completed. Like the
+    /// task id, it's not returned by 2captcha, but taken from
+    /// [`crate::CaptchaTask::get_validity`] or [`crate::solver::SolveOptions::validity`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) validity: Option<Duration>,
+}
+
+/// The id 2captcha gave a task, which identifies its solution
+/// when it's reported with [`crate::CaptchaSolver::report`]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct TaskId(u64);
+
+impl TaskId {
+    pub const fn new(task_id: u64) -> Self {
+        Self(task_id)
+    }
+
+    pub const fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<u64> for TaskId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<&CaptchaSolution<'_, T>> for TaskId {
+    fn from(value: &CaptchaSolution<'_, T>) -> Self {
+        value.task_id()
+    }
+}
+
+impl<T> From<CaptchaSolution<'_, T>> for TaskId {
+    fn from(value: CaptchaSolution<'_, T>) -> Self {
+        value.task_id()
+    }
+}
+
+impl<T> CaptchaSolution<'_, T> {
+    /// The id of the task that was solved. Solutions of a
+    /// [`crate::local::LocalSolver`] or a [`crate::manual::ManualSolver`]
+    /// have the id 0, and reporting them only affects the solver's cache
+    pub const fn task_id(&self) -> TaskId {
+        TaskId(self.task_id)
+    }
+
+    /// The moment the solution stops being accepted,
+    /// or [`None`] if it doesn't expire
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        let validity = chrono::Duration::from_std(self.validity?).ok()?;
+        self.end_time.checked_add_signed(validity)
+    }
+
+    /// How long the solution can still be used for, or [`None`] if it doesn't expire
+    pub fn remaining(&self) -> Option<Duration> {
+        let remaining = self.expires_at()? - Utc::now();
+        Some(remaining.to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Whether the solution is no longer accepted. Solutions that
+    /// don't expire never are
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_some_and(|x| x.is_zero())
+    }
+}
+
+impl<T> IntoOwned for CaptchaSolution<'_, T>
+where
+    T: IntoOwned,
+{
+    type Owned = CaptchaSolution<'static, T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        CaptchaSolution {
+            task_id: self.task_id,
+            solution: self.solution.into_owned(),
+            cost: IntoOwned::into_owned(self.cost),
+            create_time: self.create_time,
+            end_time: self.end_time,
+            solve_count: self.solve_count,
+            ip: self.ip,
+            validity: self.validity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use super::CaptchaSolution;
+    use crate::{
+        captcha_types::normal_captcha::{NormalCaptcha, NormalCaptchaSolution},
+        owned::IntoOwned,
+        test_server::{self, Response},
+        CaptchaTask,
+    };
+
+    #[test]
+    fn tracks_expiry() {
+        let json = format!(
+            r#"{{"solution":"TOKEN","cost":"0.00299","createTime":{},"endTime":{},"solveCount":1,"ip":"1.2.3.4"}}"#,
+            Utc::now().timestamp() - 40,
+            Utc::now().timestamp() - 20,
+        );
+
+        let mut solution: CaptchaSolution<'_, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(solution.expires_at(), None);
+        assert!(!solution.is_expired());
+
+        solution.validity = Some(Duration::from_secs(120));
+        let remaining = solution.remaining().unwrap();
+        assert!(remaining > Duration::from_secs(90) && remaining <= Duration::from_secs(100));
+        assert!(!solution.is_expired());
+
+        solution.validity = Some(Duration::from_secs(10));
+        assert_eq!(solution.remaining(), Some(Duration::ZERO));
+        assert!(solution.is_expired());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn owns_tasks_and_solutions() {
+        let url = test_server::serve(|request| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            _ => Response::task_ready(r#"{"text":"w68hp"}"#),
+        });
+
+        let solver = test_server::solver(url);
+
+        let body = String::from("IMAGE");
+        let task: NormalCaptcha<'static> =
+            NormalCaptcha::builder().body(&*body).build().into_owned();
+        drop(body);
+
+        let solution: CaptchaSolution<'static, NormalCaptchaSolution<'static>> =
+            tokio::spawn(async move { solver.solve(task).await.unwrap().unwrap().into_owned() })
+                .await
+                .unwrap();
+
+        let json = serde_json::to_string(&solution).unwrap();
+        let stored: CaptchaSolution<'_, NormalCaptchaSolution<'_>> =
+            serde_json::from_str(&json).unwrap();
+
+        assert_eq!(stored, solution.clone());
+        assert_eq!(stored.task_id(), solution.task_id());
+        assert_eq!(stored.solution.text, "w68hp");
+    }
 }
@@ -1,4 +1,7 @@
-use super::{language_pool::LanguagePool, CaptchaSolver};
+use std::sync::Arc;
+
+use super::{language_pool::LanguagePool, CaptchaSolver, SolveOptions};
+use crate::{archive::TrainingArchive, cache::SolutionCache, local::LocalSolvers};
 use url::Url;
 
 pub struct NoApiKeyProvided;
@@ -8,6 +11,11 @@ pub struct SolverBuilder<T> {
     api_key: T,
     language_pool: LanguagePool,
     callback_url: Option<Url>,
+    api_url: Option<Url>,
+    cache: Option<SolutionCache>,
+    local_solvers: Option<LocalSolvers>,
+    archive: Option<TrainingArchive>,
+    solve_options: SolveOptions,
 }
 
 impl SolverBuilder<NoApiKeyProvided> {
@@ -16,6 +24,11 @@ impl SolverBuilder<NoApiKeyProvided> {
             api_key: NoApiKeyProvided,
             language_pool: LanguagePool::En,
             callback_url: None,
+            api_url: None,
+            cache: None,
+            local_solvers: None,
+            archive: None,
+            solve_options: SolveOptions::new(),
         }
     }
 }
@@ -32,6 +45,12 @@ impl SolverBuilder<ApiKey> {
             api_key: self.api_key.0,
             language_pool: self.language_pool,
             callback_url: self.callback_url,
+            api_url: self.api_url,
+            cache: self.cache,
+            local_solvers: self.local_solvers,
+            archive: self.archive.map(Arc::new),
+            solve_options: self.solve_options,
+            stats: Default::default(),
         }
     }
 }
@@ -42,6 +61,11 @@ impl<T> SolverBuilder<T> {
             api_key: ApiKey(api_key.into()),
             language_pool: self.language_pool,
             callback_url: self.callback_url,
+            api_url: self.api_url,
+            cache: self.cache,
+            local_solvers: self.local_solvers,
+            archive: self.archive,
+            solve_options: self.solve_options,
         }
     }
 
@@ -54,4 +78,36 @@ impl<T> SolverBuilder<T> {
         self.callback_url = callback_url;
         self
     }
+
+    /// Sends the requests to a service compatible with the 2captcha API
+    /// instead of `https://api.2captcha.com`
+    pub fn api_url(mut self, api_url: Option<Url>) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// The cache used by [`CaptchaSolver::solve_cached`]
+    pub fn cache(mut self, cache: Option<SolutionCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The solvers tried before a task is sent to 2captcha
+    pub fn local_solvers(mut self, local_solvers: Option<LocalSolvers>) -> Self {
+        self.local_solvers = local_solvers;
+        self
+    }
+
+    /// Where the image and audio tasks that are solved are archived
+    pub fn archive(mut self, archive: Option<TrainingArchive>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    /// The options every task is solved with, unless they're replaced
+    /// for a single call with [`CaptchaSolver::solve_with`]
+    pub fn solve_options(mut self, solve_options: SolveOptions) -> Self {
+        self.solve_options = solve_options;
+        self
+    }
 }
@@ -1,4 +1,7 @@
-use super::{requests::create_task::CreateTaskResponse, requests::get_balance::GetBalanceResponse};
+use super::{
+    requests::create_task::CreateTaskResponse, requests::get_balance::GetBalanceResponse,
+    requests::report::ReportResponse,
+};
 
 /// Represents all the errors that can be returned by the 2captcha API
 #[derive(thiserror::Error, Debug)]
@@ -115,3 +118,12 @@ impl From<GetBalanceResponse> for Result<f32, SolveError> {
         }
     }
 }
+
+impl From<ReportResponse> for Result<(), SolveError> {
+    fn from(value: ReportResponse) -> Self {
+        match value {
+            ReportResponse::Reported { .. } => Ok(()),
+            ReportResponse::Error { error_code } => Err(error_code.as_ref().into()),
+        }
+    }
+}
@@ -1,17 +1,42 @@
 mod builder;
+mod options;
 mod requests;
+mod solve;
+mod verify;
 
 pub mod error;
 pub mod language_pool;
 
+#[cfg(test)]
+pub(crate) use builder::ApiKey;
 pub use builder::SolverBuilder;
 pub(crate) use error::SolveError;
+pub use options::SolveOptions;
+pub use solve::Solver;
+pub use verify::{Attempt, Verdict, Verification};
+
+use std::{future::Future, sync::Arc};
 
 use lazy_static::lazy_static;
 use reqwest::Client;
+use serde::de::IgnoredAny;
+use tokio::time::Instant;
 use url::Url;
 
-use crate::{captcha_types::CaptchaTask, prelude::*, solution::CaptchaSolution, SOFT_ID};
+use crate::{
+    archive::TrainingArchive,
+    cache::{CacheableTask, SolutionCache},
+    captcha_types::{
+        normal_captcha::{AnswerRules, NormalCaptcha, NormalCaptchaSolution},
+        CaptchaTask,
+    },
+    consensus::{Consensus, ConsensusOptions, ConsensusTask},
+    local::{LocalMetrics, LocalSolvers, LOCAL_TASK_ID},
+    prelude::*,
+    solution::{CaptchaSolution, TaskId},
+    stats::{Stats, StatsRecorder},
+    Error, SOFT_ID,
+};
 
 use self::{
     builder::NoApiKeyProvided,
@@ -19,6 +44,7 @@ use self::{
     requests::create_task::{CreateTaskRequest, CreateTaskResponse},
     requests::get_balance::{GetBalanceRequest, GetBalanceResponse},
     requests::get_task_result::{GetTaskResultError, GetTaskResultRequest, GetTaskResultResponse},
+    requests::report::ReportResponse,
 };
 
 lazy_static! {
@@ -31,6 +57,12 @@ pub struct CaptchaSolver {
     api_key: Box<str>,
     language_pool: LanguagePool,
     callback_url: Option<Url>,
+    api_url: Option<Url>,
+    cache: Option<SolutionCache>,
+    local_solvers: Option<LocalSolvers>,
+    archive: Option<Arc<TrainingArchive>>,
+    solve_options: SolveOptions,
+    stats: StatsRecorder,
 }
 
 impl CaptchaSolver {
@@ -48,6 +80,15 @@ impl CaptchaSolver {
         SolverBuilder::<NoApiKeyProvided>::new()
     }
 
+    fn api_url(&self) -> &Url {
+        self.api_url.as_ref().unwrap_or(&API_URL)
+    }
+
+    /// How many tasks the [`LocalSolvers`] of the solver solved, if it has any
+    pub fn local_metrics(&self) -> Option<LocalMetrics> {
+        self.local_solvers.as_ref().map(LocalSolvers::metrics)
+    }
+
     /// Sends a request to the 2captcha api to solve the given puzzle
     ///
     /// # Errors
@@ -59,12 +100,331 @@ impl CaptchaSolver {
     /// to the [`CaptchaSolver`] struct, otherwise a successful request will always return
     /// [`Ok(Some(CaptchaSolution))`]
     pub async fn solve<'a, T>(&self, task: T) -> Result<Option<CaptchaSolution<'a, T::Solution>>>
+    where
+        T: CaptchaTask,
+    {
+        self.solve_with(task, self.solve_options).await
+    }
+
+    /// Same as [`CaptchaSolver::solve`], with the settings of `options` instead
+    /// of the [`SolverBuilder::solve_options`] of the solver
+    ///
+    /// # Errors
+    /// Besides the errors of [`CaptchaSolver::solve`], this method returns
+    /// [`Error::ExpiringSolution`] if [`SolveOptions::min_remaining`] is set
+    /// and the solution arrives too close to its expiry
+    pub async fn solve_with<'a, T>(
+        &self,
+        task: T,
+        options: SolveOptions,
+    ) -> Result<Option<CaptchaSolution<'a, T::Solution>>>
+    where
+        T: CaptchaTask,
+    {
+        match self.solve_json(&task).await? {
+            Some((task_id, json)) => parse_solution(&json, task_id, &task, options).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Same as [`CaptchaSolver::solve`], but the solution is taken from the
+    /// [`SolutionCache`] of the solver if the same task was already solved.
+    /// Identical tasks that are solved at the same time share one request
+    ///
+    /// Cached solutions keep the task id and cost of the task that was solved,
+    /// so reporting one as [`SolutionStatus::Bad`] evicts it from the cache
+    pub async fn solve_cached<'a, T>(
+        &self,
+        task: T,
+    ) -> Result<Option<CaptchaSolution<'a, T::Solution>>>
+    where
+        T: CacheableTask,
+    {
+        let Some(ref cache) = self.cache else {
+            return self.solve(task).await;
+        };
+
+        if self.callback_url.is_some() {
+            return self.solve(task).await;
+        }
+
+        let (task_id, json) = cache
+            .get_or_solve(&task, || async {
+                self.solve_json(&task).await?.ok_or(Error::CallbackUrlSet)
+            })
+            .await?;
+
+        parse_solution(&json, task_id, &task, self.solve_options).map(Some)
+    }
+
+    /// Solves the task and hands the solution to `verifier`, which submits it
+    /// to the target site and tells whether it was accepted. Accepted
+    /// solutions are reported as [`SolutionStatus::Good`], while rejected ones
+    /// are reported as [`SolutionStatus::Bad`] and the task is solved again,
+    /// up to `max_retries` times
+    ///
+    /// # Errors
+    /// This method returns [`Error::CallbackUrlSet`] if the solver has a
+    /// callback url, besides the errors of [`CaptchaSolver::solve`]. Reports
+    /// that fail don't discard the attempts and are kept in [`Attempt::report_error`]
+    ///
+    /// # Example
+    /// ```no_run
+    /// use captcha_oxide::{
+    ///     captcha_types::normal_captcha::NormalCaptcha,
+    ///     solver::Verdict,
+    ///     CaptchaSolver,
+    ///     CaptchaTask,
+    /// };
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let solver = CaptchaSolver::new("YOUR_API_KEY");
+    /// let task = NormalCaptcha::builder()
+    ///     .body("R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==")
+    ///     .build();
+    ///
+    /// let verification = solver
+    ///     .solve_verified(task, 2, |solution| {
+    ///         let form = [("captcha", solution.solution.text.to_string())];
+    ///
+    ///         async move {
+    ///             let response = reqwest::Client::new()
+    ///                 .post("https://example.com/login")
+    ///                 .form(&form)
+    ///                 .send()
+    ///                 .await;
+    ///
+    ///             match response {
+    ///                 Ok(x) if x.status().is_success() => Verdict::Accepted,
+    ///                 Ok(_) => Verdict::Rejected,
+    ///                 Err(_) => Verdict::Inconclusive,
+    ///             }
+    ///         }
+    ///     })
+    ///     .await?;
+    ///
+    /// println!("Solved after {} attempts", verification.attempts.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn solve_verified<'a, T, F, Fut>(
+        &self,
+        task: T,
+        max_retries: usize,
+        mut verifier: F,
+    ) -> Result<Verification<'a, T::Solution>>
+    where
+        T: CaptchaTask,
+        F: FnMut(&CaptchaSolution<'a, T::Solution>) -> Fut,
+        Fut: Future<Output = Verdict>,
+    {
+        let mut attempts = Vec::new();
+
+        for _ in 0..=max_retries {
+            let (task_id, json) = self.solve_json(&task).await?.ok_or(Error::CallbackUrlSet)?;
+
+            let solution = parse_solution(&json, task_id, &task, self.solve_options)?;
+            let verdict = verifier(&solution).await;
+
+            let status = match verdict {
+                Verdict::Accepted => Some(SolutionStatus::Good),
+                Verdict::Rejected => Some(SolutionStatus::Bad),
+                Verdict::Inconclusive => None,
+            };
+
+            let report_error = match status {
+                Some(status) => self.report_task(task_id, status).await.err(),
+                None => None,
+            };
+
+            attempts.push(Attempt {
+                solution,
+                verdict,
+                report_error,
+            });
+
+            if verdict != Verdict::Rejected {
+                break;
+            }
+        }
+
+        Ok(Verification { attempts })
+    }
+
+    /// Solves a [`NormalCaptcha`] and checks the answer against `rules`, which are
+    /// usually those of [`NormalCaptcha::answer_rules`]. Answers that break them
+    /// are reported as [`SolutionStatus::Bad`] and the task is solved again,
+    /// up to `max_retries` times
+    ///
+    /// # Errors
+    /// This method returns [`Error::InvalidAnswer`] if the last answer still
+    /// breaks the rules, besides the errors of [`CaptchaSolver::solve`]
+    ///
+    /// # Option
+    /// Like [`CaptchaSolver::solve`], this method only returns [`Ok(None)`] if a
+    /// [`CaptchaSolver::callback_url`] is set, in which case nothing is checked
+    pub async fn solve_checked<'a>(
+        &self,
+        task: NormalCaptcha<'a>,
+        rules: AnswerRules,
+        max_retries: usize,
+    ) -> Result<Option<CaptchaSolution<'a, NormalCaptchaSolution<'a>>>> {
+        let mut retries = 0;
+
+        loop {
+            let Some((task_id, json)) = self.solve_json(&task).await? else {
+                return Ok(None);
+            };
+
+            let solution: CaptchaSolution<'a, NormalCaptchaSolution<'a>> =
+                parse_solution(&json, task_id, &task, self.solve_options)?;
+
+            let Err(violation) = rules.check(&solution.solution.text) else {
+                return Ok(Some(solution));
+            };
+
+            self.report_task(task_id, SolutionStatus::Bad).await?;
+
+            if retries == max_retries {
+                return Err(violation.into());
+            }
+
+            retries += 1;
+        }
+    }
+
+    /// Submits the task several times, until enough of the answers agree or
+    /// [`ConsensusOptions::submissions`] is reached. The result has every answer
+    /// grouped by agreement and the total cost, and its [`Consensus::winner`]
+    /// is only set if the agreement was reached
+    ///
+    /// # Errors
+    /// This method returns [`Error::CallbackUrlSet`] if the solver has a
+    /// [`CaptchaSolver::callback_url`], as the answers have to be compared.
+    /// Submissions that fail, e.g.: because the captcha was unsolvable, don't
+    /// stop the others and are kept in [`Consensus::failures`] instead, as are
+    /// the errors of the minority answers that couldn't be reported
+    pub async fn solve_consensus<'a, T>(
+        &self,
+        task: T,
+        options: ConsensusOptions,
+    ) -> Result<Consensus<'a, T::Solution>>
+    where
+        T: ConsensusTask,
+    {
+        if self.callback_url.is_some() {
+            return Err(Error::CallbackUrlSet);
+        }
+
+        let mut consensus = Consensus::new(options.agreement);
+
+        while !consensus.is_reached() && consensus.submissions() < options.submissions {
+            // Only submits as many tasks as would reach the agreement if they all agreed,
+            // creating them all before waiting so they are solved at the same time
+            let batch = options
+                .agreement
+                .saturating_sub(consensus.leading())
+                .clamp(1, options.submissions - consensus.submissions());
+
+            let created_at = Instant::now();
+            let mut task_ids = Vec::with_capacity(batch);
+            for _ in 0..batch {
+                match self.create_task(&task).await {
+                    Ok(task_id) => task_ids.push(task_id),
+                    Err(error) => consensus.fail(error),
+                }
+            }
+
+            if !task_ids.is_empty() {
+                tokio::time::sleep(task.get_timeout()).await;
+            }
+
+            for task_id in task_ids {
+                let solution = self
+                    .finish_task(&task, task_id, created_at)
+                    .await
+                    .and_then(|json| parse_solution(&json, task_id, &task, self.solve_options));
+
+                match solution {
+                    Ok(solution) => consensus.push(&task, solution, &options),
+                    Err(error) => consensus.fail(error),
+                }
+            }
+        }
+
+        if options.report_minority {
+            let minority = consensus.minority().map(|x| x.task_id).collect::<Vec<_>>();
+            for task_id in minority {
+                if let Err(error) = self.report_task(task_id, SolutionStatus::Bad).await {
+                    consensus.fail_report(error);
+                }
+            }
+        }
+
+        Ok(consensus)
+    }
+
+    /// Creates the task and waits for it to be solved, returning
+    /// its id and the JSON of its result
+    async fn solve_json<T>(&self, task: &T) -> Result<Option<(u64, String)>>
+    where
+        T: CaptchaTask,
+    {
+        if let Some(ref local_solvers) = self.local_solvers {
+            if let Some(json) = local_solvers.solve(task)? {
+                return Ok(Some((LOCAL_TASK_ID, json)));
+            }
+        }
+
+        let created_at = Instant::now();
+        let task_id = self.create_task(task).await?;
+
+        if self.callback_url.is_some() {
+            return Ok(None);
+        }
+
+        tokio::time::sleep(task.get_timeout()).await;
+
+        let json = self.finish_task(task, task_id, created_at).await?;
+        Ok(Some((task_id, json)))
+    }
+
+    /// Waits for the result of a task created at `created_at`, adding it to
+    /// the [`Stats`] and the [`TrainingArchive`] of the solver. Archiving is
+    /// best-effort, so a task that can't be archived is still solved
+    async fn finish_task<T>(&self, task: &T, task_id: u64, created_at: Instant) -> Result<String>
+    where
+        T: CaptchaTask,
+    {
+        let json = match self.task_result(task_id).await {
+            Ok(json) => json,
+            Err(error) => {
+                if let Error::TwoCaptchaError(SolveError::UnsolvableCaptcha) = error {
+                    self.stats.unsolvable(task);
+                }
+
+                return Err(error);
+            }
+        };
+
+        self.stats
+            .solved(task, task_id, &json, created_at.elapsed());
+
+        if let Some(ref archive) = self.archive {
+            let _ = archive.archive(task, task_id, &json).await;
+        }
+
+        Ok(json)
+    }
+
+    async fn create_task<T>(&self, task: &T) -> Result<u64>
     where
         T: CaptchaTask,
     {
         let create_task = CreateTaskRequest {
             client_key: &self.api_key,
-            task: &task,
+            task,
             soft_id: SOFT_ID,
             callback_url: self.callback_url.as_ref(),
             language_pool: self.language_pool,
@@ -72,7 +432,7 @@ impl CaptchaSolver {
 
         let task_id = Into::<std::result::Result<_, _>>::into(
             CLIENT
-                .post(API_URL.join("/createTask")?)
+                .post(self.api_url().join("/createTask")?)
                 .header("Content-Type", "application/json")
                 .json(&create_task)
                 .send()
@@ -81,13 +441,12 @@ impl CaptchaSolver {
                 .await?,
         )?;
 
-        if self.callback_url.is_some() {
-            return Ok(None);
-        }
-
-        tokio::time::sleep(task.get_timeout()).await;
+        Ok(task_id)
+    }
 
-        let task_result_url = API_URL.join("/getTaskResult")?;
+    /// Polls 2captcha until the task is solved, returning the JSON of its result
+    async fn task_result(&self, task_id: u64) -> Result<String> {
+        let task_result_url = self.api_url().join("/getTaskResult")?;
         let task_result_request = GetTaskResultRequest {
             client_key: &self.api_key,
             task_id,
@@ -109,37 +468,71 @@ impl CaptchaSolver {
                 return Err(error.into());
             }
 
-            let task_result: GetTaskResultResponse<'_, _> = serde_json::from_str(&json)?;
+            let task_result: GetTaskResultResponse<'_, IgnoredAny> = serde_json::from_str(&json)?;
 
-            if let GetTaskResultResponse::Ready(mut solution) = task_result {
-                solution.task_id = task_id;
-                return Ok(Some(solution));
+            if let GetTaskResultResponse::Ready(_) = task_result {
+                return Ok(json);
             }
 
             tokio::time::sleep(std::time::Duration::from_secs(5)).await;
         }
     }
 
-    /// Allows you to report to 2captcha on wether or not the solution was valid
-    pub async fn report<'a, T>(
-        &self,
-        solution: CaptchaSolution<'a, T>,
-        status: SolutionStatus,
-    ) -> Result<()> {
-        let json = GetTaskResultRequest {
-            client_key: &self.api_key,
-            task_id: solution.task_id,
-        };
+    /// Allows you to report to 2captcha on wether or not the solution was valid.
+    /// The task can be given as a [`CaptchaSolution`], a reference to one, or
+    /// the [`TaskId`] of [`CaptchaSolution::task_id`], which can be stored to
+    /// report the solution later or from another process
+    ///
+    /// # Errors
+    /// This method will error if the network request fails or if 2captcha returns
+    /// an error, such as [`SolveError::CaptchaIdNotFound`] for unknown tasks
+    pub async fn report(&self, task: impl Into<TaskId>, status: SolutionStatus) -> Result<()> {
+        self.report_task(task.into().get(), status).await
+    }
 
-        CLIENT
-            .post(API_URL.join(status.report_endpoint())?)
-            .json(&json)
-            .send()
-            .await?;
+    async fn report_task(&self, task_id: u64, status: SolutionStatus) -> Result<()> {
+        // Local solutions were never sent to 2captcha, but they may still be
+        // cached. As they share their id, a bad one evicts all of them
+        if task_id != LOCAL_TASK_ID {
+            let json = GetTaskResultRequest {
+                client_key: &self.api_key,
+                task_id,
+            };
+
+            Into::<std::result::Result<_, _>>::into(
+                CLIENT
+                    .post(self.api_url().join(status.report_endpoint())?)
+                    .json(&json)
+                    .send()
+                    .await?
+                    .json::<ReportResponse>()
+                    .await?,
+            )?;
+        }
+
+        if let (SolutionStatus::Bad, Some(cache)) = (&status, &self.cache) {
+            cache.evict(task_id);
+        }
+
+        if let Some(ref archive) = self.archive {
+            let _ = archive.set_status(task_id, status).await;
+        }
+
+        self.stats.reported(task_id, status);
 
         Ok(())
     }
 
+    /// How the task was reported, if it was
+    pub fn report_status(&self, task: impl Into<TaskId>) -> Option<SolutionStatus> {
+        self.stats.status(task.into().get())
+    }
+
+    /// The accuracy, latency and cost of the task types the solver has solved
+    pub fn stats(&self) -> Stats {
+        self.stats.snapshot()
+    }
+
     /// Returns your account balance
     pub async fn get_balance(&self) -> Result<f32> {
         let request = GetBalanceRequest {
@@ -148,7 +541,7 @@ impl CaptchaSolver {
 
         let balance = Into::<std::result::Result<_, _>>::into(
             CLIENT
-                .post(API_URL.join("/getBalance")?)
+                .post(self.api_url().join("/getBalance")?)
                 .json(&request)
                 .send()
                 .await?
@@ -160,6 +553,48 @@ impl CaptchaSolver {
     }
 }
 
+impl Solver for CaptchaSolver {
+    async fn solve<'a, T>(&self, task: T) -> Result<CaptchaSolution<'a, T::Solution>>
+    where
+        T: CaptchaTask + Send + Sync,
+    {
+        CaptchaSolver::solve(self, task)
+            .await?
+            .ok_or(Error::CallbackUrlSet)
+    }
+}
+
+/// Parses the JSON of a ready task result
+pub(crate) fn parse_solution<'a, T>(
+    json: &str,
+    task_id: u64,
+    task: &T,
+    options: SolveOptions,
+) -> Result<CaptchaSolution<'a, T::Solution>>
+where
+    T: CaptchaTask,
+{
+    let GetTaskResultResponse::Ready(mut solution) = serde_json::from_str(json)? else {
+        unreachable!("Only the results of solved tasks are parsed");
+    };
+
+    solution.task_id = task_id;
+    solution.validity = options.validity.or_else(|| task.get_validity());
+
+    if let (Some(min_remaining), Some(remaining)) = (options.min_remaining, solution.remaining()) {
+        if remaining < min_remaining {
+            return Err(Error::ExpiringSolution {
+                remaining,
+                min_remaining,
+            });
+        }
+    }
+
+    Ok(solution)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum SolutionStatus {
     Good,
     Bad,
@@ -173,3 +608,219 @@ impl SolutionStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use url::Url;
+
+    use super::{SolutionStatus, SolveError, SolveOptions, Solver, Verdict};
+    use crate::{
+        captcha_types::normal_captcha::NormalCaptcha,
+        consensus::ConsensusOptions,
+        solution::TaskId,
+        test_server::{self, RequestLog, Response},
+        CaptchaTask, Error,
+    };
+
+    /// Emulates the 2captcha API, solving the n-th task with `answer-n`
+    fn server() -> (Url, RequestLog) {
+        test_server::serve_logged(|request, log| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => {
+                let n = log.count("/createTask");
+                Response::task_ready(&format!(r#"{{"text":"answer-{n}"}}"#))
+            }
+            _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn verifies_solutions() {
+        let (url, requests) = server();
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let verification = solver
+            .solve_verified(task, 3, |solution| {
+                let verdict = match solution.solution.text.as_ref() {
+                    "answer-3" => Verdict::Accepted,
+                    _ => Verdict::Rejected,
+                };
+
+                async move { verdict }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(verification.attempts.len(), 3);
+        assert_eq!(verification.accepted().unwrap().solution.text, "answer-3");
+        assert_eq!(
+            requests.paths("/report"),
+            ["/reportIncorrect", "/reportIncorrect", "/reportCorrect"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_retrying() {
+        let (url, requests) = server();
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let verification = solver
+            .solve_verified(task, 1, |_| async { Verdict::Rejected })
+            .await
+            .unwrap();
+
+        assert_eq!(verification.attempts.len(), 2);
+        assert_eq!(verification.verdict(), Some(Verdict::Rejected));
+        assert!(verification.into_accepted().is_none());
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let verification = solver
+            .solve_verified(task, 1, |_| async { Verdict::Inconclusive })
+            .await
+            .unwrap();
+
+        assert_eq!(verification.attempts.len(), 1);
+        assert_eq!(requests.paths("/report").len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_attempts_when_reports_fail() {
+        let url = test_server::serve(|request| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => Response::task_ready(r#"{"text":"w68hp"}"#),
+            _ => Response::ok(
+                r#"{"errorId":16,"errorCode":"ERROR_NO_SUCH_CAPCHA_ID","errorDescription":"Task not found"}"#,
+            ),
+        });
+
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let verification = solver
+            .solve_verified(task, 1, |_| async { Verdict::Accepted })
+            .await
+            .unwrap();
+
+        assert_eq!(verification.accepted().unwrap().solution.text, "w68hp");
+        assert!(matches!(
+            verification.attempts[0].report_error,
+            Some(Error::TwoCaptchaError(SolveError::CaptchaIdNotFound))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_task_ids() {
+        let url = test_server::serve(|request| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => Response::task_ready(r#"{"text":"w68hp"}"#),
+            _ if request.body.contains("72345678") => {
+                Response::ok(r#"{"errorId":0,"status":"success"}"#)
+            }
+            _ => Response::ok(
+                r#"{"errorId":16,"errorCode":"ERROR_NO_SUCH_CAPCHA_ID","errorDescription":"Task not found"}"#,
+            ),
+        });
+
+        let solver = test_server::solver(url);
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let solution = solver.solve(task).await.unwrap().unwrap();
+
+        solver
+            .report(&solution, SolutionStatus::Good)
+            .await
+            .unwrap();
+        assert_eq!(solver.report_status(&solution), Some(SolutionStatus::Good));
+
+        // Task ids can be stored and reported later
+        let json = serde_json::to_string(&solution.task_id()).unwrap();
+        assert_eq!(json, "72345678");
+
+        let task_id: TaskId = serde_json::from_str(&json).unwrap();
+        solver.report(task_id, SolutionStatus::Bad).await.unwrap();
+        assert_eq!(solver.report_status(task_id), Some(SolutionStatus::Bad));
+
+        let error = solver
+            .report(TaskId::new(1), SolutionStatus::Bad)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::TwoCaptchaError(SolveError::CaptchaIdNotFound)
+        ));
+        assert_eq!(solver.report_status(TaskId::new(1)), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn solves_through_the_trait() {
+        let (url, requests) = server();
+
+        let solver = test_server::solver(url.clone());
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let solution = Solver::solve(&solver, task).await.unwrap();
+        assert_eq!(solution.solution.text, "answer-1");
+
+        // The solution goes to the callback, so there's nothing to return
+        let solver = test_server::solver_builder(url.clone())
+            .callback_url(Some(url.join("/callback").unwrap()))
+            .build();
+
+        let task = NormalCaptcha::builder().body("IMAGE").build();
+        let error = Solver::solve(&solver, task).await.unwrap_err();
+        assert!(matches!(error, Error::CallbackUrlSet));
+        assert_eq!(requests.count("/createTask"), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn applies_default_options() {
+        let (url, _) = server();
+
+        // Every solution expires before the minimum remaining time
+        let solver = test_server::solver_builder(url)
+            .solve_options(
+                SolveOptions::new()
+                    .validity(Some(Duration::from_secs(10)))
+                    .min_remaining(Some(Duration::from_secs(20))),
+            )
+            .build();
+
+        let task = || NormalCaptcha::builder().body("IMAGE").build();
+        let is_expiring = |x| matches!(x, Err(Error::ExpiringSolution { .. }));
+
+        assert!(is_expiring(solver.solve(task()).await.map(drop)));
+        assert!(is_expiring(solver.solve_cached(task()).await.map(drop)));
+        assert!(is_expiring(
+            solver
+                .solve_verified(task(), 0, |_| async { Verdict::Accepted })
+                .await
+                .map(drop)
+        ));
+
+        let rules = task().answer_rules();
+        assert!(is_expiring(
+            solver.solve_checked(task(), rules, 0).await.map(drop)
+        ));
+
+        let consensus = solver
+            .solve_consensus(task(), ConsensusOptions::new())
+            .await
+            .unwrap();
+        assert!(consensus.votes.is_empty());
+        assert!(matches!(
+            consensus.failures[0],
+            Error::ExpiringSolution { .. }
+        ));
+
+        let solution = solver
+            .solve_with(task(), SolveOptions::new())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(solution.validity, None);
+    }
+}
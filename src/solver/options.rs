@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Settings of how solutions are checked, given to every task of a solver
+/// with [`super::SolverBuilder::solve_options`], or to a single one with
+/// [`super::CaptchaSolver::solve_with`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolveOptions {
+    pub(super) validity: Option<Duration>,
+    pub(super) min_remaining: Option<Duration>,
+}
+
+impl SolveOptions {
+    pub const fn new() -> Self {
+        Self {
+            validity: None,
+            min_remaining: None,
+        }
+    }
+
+    /// Overrides how long the solution can be used after the task is completed,
+    /// which defaults to [`crate::CaptchaTask::get_validity`]
+    pub const fn validity(mut self, validity: Option<Duration>) -> Self {
+        self.validity = validity;
+        self
+    }
+
+    /// Rejects solutions that arrive with less than this much time left before
+    /// they expire, returning [`crate::Error::ExpiringSolution`]
+    pub const fn min_remaining(mut self, min_remaining: Option<Duration>) -> Self {
+        self.min_remaining = min_remaining;
+        self
+    }
+}
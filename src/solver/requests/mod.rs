@@ -1,3 +1,4 @@
 pub(crate) mod create_task;
 pub(crate) mod get_balance;
 pub(crate) mod get_task_result;
+pub(crate) mod report;
@@ -0,0 +1,10 @@
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ReportResponse {
+    #[serde(rename_all = "camelCase")]
+    Error {
+        error_code: Box<str>,
+    },
+
+    Reported {},
+}
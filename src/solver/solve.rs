@@ -0,0 +1,21 @@
+use std::future::Future;
+
+use crate::{captcha_types::CaptchaTask, prelude::*, solution::CaptchaSolution};
+
+/// Anything that can solve a [`CaptchaTask`], so the same code can send tasks
+/// to 2captcha through a [`super::CaptchaSolver`] or to your own operators
+/// through a [`crate::manual::ManualSolver`]
+pub trait Solver {
+    /// Solves the task, waiting for its solution
+    ///
+    /// # Errors
+    /// Besides the errors of the solver itself, this method returns
+    /// [`crate::Error::CallbackUrlSet`] if the solution would be sent
+    /// to a callback url instead
+    fn solve<'a, T>(
+        &self,
+        task: T,
+    ) -> impl Future<Output = Result<CaptchaSolution<'a, T::Solution>>> + Send
+    where
+        T: CaptchaTask + Send + Sync;
+}
@@ -0,0 +1,58 @@
+use crate::{solution::CaptchaSolution, Error};
+
+/// What the target site made of a solution, as told by the verifier
+/// of [`super::CaptchaSolver::solve_verified`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The site accepted the solution, which is reported as [`super::SolutionStatus::Good`]
+    Accepted,
+
+    /// The site rejected the solution, which is reported as
+    /// [`super::SolutionStatus::Bad`] before the task is solved again
+    Rejected,
+
+    /// It's unclear whether the site accepted the solution, e.g.: because
+    /// the request failed. The solution isn't reported and the task isn't
+    /// solved again
+    Inconclusive,
+}
+
+/// A solution of [`super::CaptchaSolver::solve_verified`] and its verdict
+#[derive(Debug)]
+pub struct Attempt<'a, T> {
+    pub solution: CaptchaSolution<'a, T>,
+    pub verdict: Verdict,
+
+    /// Why the solution couldn't be reported. Reporting is best-effort,
+    /// so the attempts are kept even if it fails
+    pub report_error: Option<Error>,
+}
+
+/// Every attempt of [`super::CaptchaSolver::solve_verified`], in order
+#[derive(Debug)]
+pub struct Verification<'a, T> {
+    pub attempts: Vec<Attempt<'a, T>>,
+}
+
+impl<'a, T> Verification<'a, T> {
+    /// The solution that was accepted, if any
+    pub fn accepted(&self) -> Option<&CaptchaSolution<'a, T>> {
+        self.attempts
+            .last()
+            .filter(|x| x.verdict == Verdict::Accepted)
+            .map(|x| &x.solution)
+    }
+
+    pub fn into_accepted(self) -> Option<CaptchaSolution<'a, T>> {
+        self.attempts
+            .into_iter()
+            .last()
+            .filter(|x| x.verdict == Verdict::Accepted)
+            .map(|x| x.solution)
+    }
+
+    /// The verdict of the last attempt
+    pub fn verdict(&self) -> Option<Verdict> {
+        self.attempts.last().map(|x| x.verdict)
+    }
+}
@@ -0,0 +1,286 @@
+//! How accurate 2captcha is for each task type
+//!
+//! Every [`crate::CaptchaSolver`] keeps track of the tasks it solves and how
+//! they are reported, which can be used to pick the task types or providers
+//! that are worth their price. The statistics can be dumped as JSON with
+//! [`Stats::to_json`].
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::CaptchaSolver;
+//!
+//! # fn main() -> Result<(), captcha_oxide::Error> {
+//! let solver = CaptchaSolver::new("YOUR_API_KEY");
+//!
+//! // Solve and report some captchas...
+//!
+//! let stats = solver.stats();
+//! if let Some(accuracy) = stats.get("ImageToTextTask").and_then(|x| x.accuracy()) {
+//!     println!("{:.1}% of the reported answers were correct", accuracy * 100.0);
+//! }
+//!
+//! std::fs::write("stats.json", stats.to_json()?).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::{prelude::*, solver::SolutionStatus, CaptchaTask};
+
+/// The number of tasks whose report status is remembered
+const MAX_TASKS: usize = 10_000;
+
+/// The statistics of one task type
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq)]
+pub struct TaskTypeStats {
+    /// Solutions returned by 2captcha
+    pub used: u64,
+
+    /// Solutions reported as [`SolutionStatus::Good`]
+    pub good: u64,
+
+    /// Solutions reported as [`SolutionStatus::Bad`]
+    pub bad: u64,
+
+    /// Tasks 2captcha couldn't solve
+    pub unsolvable: u64,
+
+    /// The mean time between creating a task and receiving its solution, in seconds
+    pub mean_latency_secs: f64,
+
+    /// The mean price of a solution
+    pub mean_cost: f64,
+}
+
+impl TaskTypeStats {
+    /// The fraction of the reported solutions that were reported as
+    /// [`SolutionStatus::Good`], or [`None`] if none were reported
+    pub fn accuracy(&self) -> Option<f64> {
+        let reported = self.good + self.bad;
+        (reported > 0).then(|| self.good as f64 / reported as f64)
+    }
+}
+
+/// The [`TaskTypeStats`] of every task type a solver has solved,
+/// keyed by the `type` 2captcha knows it by, e.g.: `"ImageToTextTask"`
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Stats {
+    pub task_types: BTreeMap<String, TaskTypeStats>,
+}
+
+impl Stats {
+    pub fn get(&self, task_type: &str) -> Option<&TaskTypeStats> {
+        self.task_types.get(task_type)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// The running totals a solver keeps
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    totals: HashMap<String, Totals>,
+    tasks: HashMap<u64, Task>,
+    order: VecDeque<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    used: u64,
+    good: u64,
+    bad: u64,
+    unsolvable: u64,
+    latency: Duration,
+    cost: f64,
+}
+
+#[derive(Debug)]
+struct Task {
+    task_type: String,
+    status: Option<SolutionStatus>,
+}
+
+impl StatsRecorder {
+    /// Records a task that was solved after `latency`, given the JSON of its result
+    pub fn solved<T>(&self, task: &T, task_id: u64, json: &str, latency: Duration)
+    where
+        T: CaptchaTask,
+    {
+        let task_type = task_type(task);
+        let cost = serde_json::from_str::<Value>(json)
+            .ok()
+            .and_then(|x| x.get("cost")?.as_str()?.parse::<f64>().ok())
+            .unwrap_or_default();
+
+        let mut inner = self.lock();
+
+        let totals = inner.totals.entry(task_type.clone()).or_default();
+        totals.used += 1;
+        totals.latency += latency;
+        totals.cost += cost;
+
+        inner.tasks.insert(
+            task_id,
+            Task {
+                task_type,
+                status: None,
+            },
+        );
+        inner.order.push_back(task_id);
+
+        while inner.order.len() > MAX_TASKS {
+            if let Some(task_id) = inner.order.pop_front() {
+                inner.tasks.remove(&task_id);
+            }
+        }
+    }
+
+    pub fn unsolvable<T>(&self, task: &T)
+    where
+        T: CaptchaTask,
+    {
+        self.lock()
+            .totals
+            .entry(task_type(task))
+            .or_default()
+            .unsolvable += 1;
+    }
+
+    /// Records how a task was reported. Reporting a task again
+    /// replaces the status it was reported with before
+    pub fn reported(&self, task_id: u64, status: SolutionStatus) {
+        let mut inner = self.lock();
+        let Inner { totals, tasks, .. } = &mut *inner;
+
+        let Some(task) = tasks.get_mut(&task_id) else {
+            return;
+        };
+        let Some(totals) = totals.get_mut(&task.task_type) else {
+            return;
+        };
+
+        match task.status.replace(status) {
+            Some(SolutionStatus::Good) => totals.good -= 1,
+            Some(SolutionStatus::Bad) => totals.bad -= 1,
+            None => {}
+        }
+
+        match status {
+            SolutionStatus::Good => totals.good += 1,
+            SolutionStatus::Bad => totals.bad += 1,
+        }
+    }
+
+    pub fn status(&self, task_id: u64) -> Option<SolutionStatus> {
+        self.lock().tasks.get(&task_id)?.status
+    }
+
+    pub fn snapshot(&self) -> Stats {
+        let task_types = self
+            .lock()
+            .totals
+            .iter()
+            .map(|(task_type, x)| {
+                let used = x.used.max(1) as f64;
+                let stats = TaskTypeStats {
+                    used: x.used,
+                    good: x.good,
+                    bad: x.bad,
+                    unsolvable: x.unsolvable,
+                    mean_latency_secs: x.latency.as_secs_f64() / used,
+                    mean_cost: x.cost / used,
+                };
+
+                (task_type.clone(), stats)
+            })
+            .collect();
+
+        Stats { task_types }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|x| x.into_inner())
+    }
+}
+
+fn task_type<T>(task: &T) -> String
+where
+    T: CaptchaTask,
+{
+    serde_json::to_value(task)
+        .ok()
+        .and_then(|x| Some(x.get("type")?.as_str()?.to_owned()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        captcha_types::normal_captcha::NormalCaptcha,
+        solver::SolutionStatus,
+        test_server::{self, Response},
+        CaptchaTask, Error,
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn tracks_accuracy() {
+        let (url, _) = test_server::serve_logged(|request, log| match request.path.as_str() {
+            "/createTask" => Response::task_created(),
+            "/getTaskResult" => match log.count("/getTaskResult") {
+                3 => Response::ok(r#"{"errorId":12,"errorCode":"ERROR_CAPTCHA_UNSOLVABLE"}"#),
+                _ => Response::task_ready(r#"{"text":"w68hp"}"#),
+            },
+            _ => Response::ok(r#"{"errorId":0,"status":"success"}"#),
+        });
+
+        let solver = test_server::solver(url);
+
+        let task = || NormalCaptcha::builder().body("IMAGE").build();
+
+        let solution = solver.solve(task()).await.unwrap().unwrap();
+        assert_eq!(solver.report_status(&solution), None);
+
+        solver
+            .report(&solution, SolutionStatus::Good)
+            .await
+            .unwrap();
+        assert_eq!(solver.stats().get("ImageToTextTask").unwrap().good, 1);
+
+        // Reporting again replaces the previous status
+        solver.report(&solution, SolutionStatus::Bad).await.unwrap();
+        assert_eq!(solver.report_status(&solution), Some(SolutionStatus::Bad));
+
+        solver.solve(task()).await.unwrap();
+        let error = solver.solve(task()).await.unwrap_err();
+        assert!(matches!(error, Error::TwoCaptchaError(_)));
+
+        let stats = solver.stats();
+        let stats = stats.get("ImageToTextTask").unwrap();
+        assert_eq!(stats.used, 2);
+        assert_eq!((stats.good, stats.bad, stats.unsolvable), (0, 1, 1));
+        assert_eq!(stats.accuracy(), Some(0.0));
+        assert!((stats.mean_cost - 0.00299).abs() < 1e-9);
+        assert!(stats.mean_latency_secs >= Duration::from_secs(5).as_secs_f64());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&solver.stats().to_json().unwrap()).unwrap();
+        assert_eq!(json["ImageToTextTask"]["used"], 2);
+    }
+}
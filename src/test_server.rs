@@ -0,0 +1,181 @@
+//! A minimal HTTP server for the tests that emulate the 2captcha API
+//! and the sites protected by captchas
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+use url::Url;
+
+use crate::{
+    solver::{ApiKey, SolverBuilder},
+    CaptchaSolver,
+};
+
+#[derive(Clone)]
+pub(crate) struct Request {
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) struct Response {
+    pub status: &'static str,
+    pub headers: String,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            status: "200 OK",
+            headers: String::new(),
+            body: body.into(),
+        }
+    }
+
+    /// The response of `/createTask`
+    pub fn task_created() -> Self {
+        Self::ok(r#"{"errorId":0,"taskId":72345678}"#)
+    }
+
+    /// The response of `/getTaskResult` for a task solved with `solution`
+    pub fn task_ready(solution: &str) -> Self {
+        Self::ok(format!(
+            r#"{{"errorId":0,"status":"ready","solution":{solution},"cost":"0.00299","ip":"1.2.3.4","createTime":1692863536,"endTime":1692863556,"solveCount":1}}"#
+        ))
+    }
+}
+
+/// Every request received by a server started with [`serve_logged`]
+#[derive(Clone, Default)]
+pub(crate) struct RequestLog(Arc<Mutex<Vec<Request>>>);
+
+impl RequestLog {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Request>> {
+        self.0.lock().unwrap_or_else(|x| x.into_inner())
+    }
+
+    /// The number of requests whose path starts with `path`
+    pub fn count(&self, path: &str) -> usize {
+        self.lock()
+            .iter()
+            .filter(|x| x.path.starts_with(path))
+            .count()
+    }
+
+    /// The paths that start with `path`, in the order they were requested
+    pub fn paths(&self, path: &str) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|x| x.path.starts_with(path))
+            .map(|x| x.path.clone())
+            .collect()
+    }
+
+    /// The bodies of the requests whose path starts with `path`, in the order they were received
+    pub fn bodies(&self, path: &str) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|x| x.path.starts_with(path))
+            .map(|x| x.body.clone())
+            .collect()
+    }
+}
+
+/// A solver that sends its requests to the server at `url`
+pub(crate) fn solver(url: Url) -> CaptchaSolver {
+    solver_builder(url).build()
+}
+
+/// The builder of [`solver`], for the tests that set more of its options
+pub(crate) fn solver_builder(url: Url) -> SolverBuilder<ApiKey> {
+    CaptchaSolver::builder()
+        .api_key("API_KEY")
+        .api_url(Some(url))
+}
+
+/// Like [`serve`], but records every request before handing it to `handler`,
+/// which also receives the log, e.g.: to answer based on the earlier requests
+pub(crate) fn serve_logged(
+    handler: impl Fn(&Request, &RequestLog) -> Response + Send + 'static,
+) -> (Url, RequestLog) {
+    let log = RequestLog::default();
+    let requests = log.clone();
+
+    let url = serve(move |request| {
+        requests.lock().push(request.clone());
+        handler(request, &requests)
+    });
+
+    (url, log)
+}
+
+/// Serves every request with `handler` on a background thread, returning the URL of the server
+pub(crate) fn serve(handler: impl Fn(&Request) -> Response + Send + 'static) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut lines = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                lines.push(line.trim().to_owned());
+            }
+
+            let path = lines[0].split(' ').nth(1).unwrap().to_owned();
+            let headers = lines[1..]
+                .iter()
+                .filter_map(|x| x.split_once(':'))
+                .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+                .collect();
+
+            let mut request = Request {
+                path,
+                headers,
+                body: String::new(),
+            };
+
+            let length = request
+                .header("content-length")
+                .map_or(0, |x| x.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.body = String::from_utf8(body).unwrap();
+
+            let Response {
+                status,
+                headers,
+                body,
+            } = handler(&request);
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\n{headers}content-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+
+    url
+}
@@ -0,0 +1,77 @@
+use std::f64::consts::PI;
+
+use crate::geometry::Point;
+
+/// The shape of the path between the start and the end of a movement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// A straight line
+    Line,
+
+    /// A cubic Bézier curve whose control points are randomly displaced to
+    /// either side of the straight line by up to `curvature` times its length
+    Bezier { curvature: f64 },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self::Bezier { curvature: 0.25 }
+    }
+}
+
+/// How the pointer's speed changes along the path
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedProfile {
+    /// The same speed during the whole movement
+    Constant,
+
+    /// Accelerates and decelerates following a cosine
+    EaseInOut,
+
+    /// The minimum-jerk model of human reaching movements, with a bell shaped
+    /// velocity that peaks halfway through the movement
+    #[default]
+    MinimumJerk,
+}
+
+impl SpeedProfile {
+    /// Maps the elapsed fraction of the movement's duration
+    /// to the covered fraction of its path
+    pub fn progress(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Constant => t,
+            Self::EaseInOut => (1.0 - (PI * t).cos()) / 2.0,
+            Self::MinimumJerk => t.powi(3) * (10.0 - 15.0 * t + 6.0 * t.powi(2)),
+        }
+    }
+}
+
+/// A concrete path, with its random control points already chosen
+pub(super) enum Path {
+    Line(Point, Point),
+    Bezier([Point; 4]),
+}
+
+impl Path {
+    pub fn point(&self, t: f64) -> Point {
+        match self {
+            Self::Line(a, b) => Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t),
+            Self::Bezier([p0, p1, p2, p3]) => {
+                let u = 1.0 - t;
+                let (a, b, c, d) = (
+                    u.powi(3),
+                    3.0 * u.powi(2) * t,
+                    3.0 * u * t.powi(2),
+                    t.powi(3),
+                );
+
+                Point::new(
+                    a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+                    a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::geometry::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PointerEventKind {
+    /// The pointer moved to [`PointerEvent::position`]
+    Move,
+
+    /// The primary button was pressed
+    Down,
+
+    /// The primary button was released
+    Up,
+}
+
+/// A single step of a pointer trajectory
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerEvent {
+    pub kind: PointerEventKind,
+
+    /// Where the pointer is when the event happens
+    pub position: Point,
+
+    /// Milliseconds elapsed since the start of the trajectory
+    pub elapsed_ms: u64,
+}
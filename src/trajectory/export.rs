@@ -0,0 +1,78 @@
+use serde_json::{json, Value};
+
+use super::{PointerEvent, PointerEventKind};
+
+/// Converts a trajectory into a W3C WebDriver `pointer` input source,
+/// to be sent in the `actions` array of the `Perform Actions` command
+pub fn to_webdriver_actions(events: &[PointerEvent]) -> Value {
+    let mut elapsed_ms = 0;
+
+    let actions = events
+        .iter()
+        .flat_map(|event| {
+            let duration = event.elapsed_ms.saturating_sub(elapsed_ms);
+            elapsed_ms = event.elapsed_ms;
+
+            let pause = (duration > 0 && event.kind != PointerEventKind::Move)
+                .then(|| json!({ "type": "pause", "duration": duration }));
+
+            let action = match event.kind {
+                PointerEventKind::Move => json!({
+                    "type": "pointerMove",
+                    "duration": duration,
+                    "origin": "viewport",
+                    "x": event.position.x.round() as i64,
+                    "y": event.position.y.round() as i64,
+                }),
+                PointerEventKind::Down => json!({ "type": "pointerDown", "button": 0 }),
+                PointerEventKind::Up => json!({ "type": "pointerUp", "button": 0 }),
+            };
+
+            pause.into_iter().chain([action])
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "type": "pointer",
+        "id": "mouse",
+        "parameters": { "pointerType": "mouse" },
+        "actions": actions,
+    })
+}
+
+/// Converts a trajectory into the parameters of Chrome DevTools Protocol
+/// `Input.dispatchMouseEvent` calls, paired with the milliseconds elapsed
+/// since the start of the trajectory at which each call should be made
+pub fn to_cdp_mouse_events(events: &[PointerEvent]) -> Vec<(u64, Value)> {
+    let mut pressed = false;
+
+    events
+        .iter()
+        .map(|event| {
+            let (kind, button) = match event.kind {
+                PointerEventKind::Move => ("mouseMoved", if pressed { "left" } else { "none" }),
+                PointerEventKind::Down => {
+                    pressed = true;
+                    ("mousePressed", "left")
+                }
+                PointerEventKind::Up => {
+                    pressed = false;
+                    ("mouseReleased", "left")
+                }
+            };
+
+            let mut params = json!({
+                "type": kind,
+                "x": event.position.x,
+                "y": event.position.y,
+                "button": button,
+            });
+
+            if event.kind != PointerEventKind::Move {
+                params["clickCount"] = json!(1);
+            }
+
+            (event.elapsed_ms, params)
+        })
+        .collect()
+}
@@ -0,0 +1,379 @@
+//! Human-like pointer trajectories for applying coordinate based solutions
+//!
+//! A [`Trajectory`] turns a target point or a drag distance into a timed
+//! sequence of [`PointerEvent`]s, which can be replayed through the Chrome
+//! DevTools Protocol or WebDriver with [`to_cdp_mouse_events`] and
+//! [`to_webdriver_actions`].
+//!
+//! # Example
+//! ```
+//! use captcha_oxide::{
+//!     captcha_types::rotate_captcha::RotateCaptchaSolution,
+//!     geometry::Point,
+//!     trajectory::{Trajectory, to_webdriver_actions},
+//! };
+//!
+//! let solution = RotateCaptchaSolution { rotate: 90 };
+//! let handle = Point::new(120.0, 480.0);
+//!
+//! let events = Trajectory::new()
+//!     .seed(Some(42))
+//!     .drag_by(handle, solution.slider_offset(280.0));
+//!
+//! let actions = to_webdriver_actions(&events);
+//! # assert_eq!(events.last().unwrap().position, Point::new(190.0, 480.0));
+//! ```
+
+mod curve;
+mod event;
+mod export;
+
+pub use curve::{Curve, SpeedProfile};
+pub use event::{PointerEvent, PointerEventKind};
+pub use export::{to_cdp_mouse_events, to_webdriver_actions};
+
+use std::{f64::consts::PI, ops::Range};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::geometry::Point;
+
+use self::curve::Path;
+
+/// How often the pointer's position is sampled along a movement
+const SAMPLE_INTERVAL_MS: f64 = 16.0;
+
+/// How long the pointer rests before pressing the button
+const HOVER_MS: Range<f64> = 40.0..120.0;
+
+/// How long the button is held during a click
+const CLICK_HOLD_MS: Range<f64> = 60.0..140.0;
+
+/// How long the pointer rests after pressing or before releasing during a drag
+const DRAG_PAUSE_MS: Range<f64> = 80.0..180.0;
+
+/// Passing the target and correcting the position afterwards,
+/// as people often do with fast movements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overshoot {
+    /// The chance of a movement overshooting its target, between `0` and `1`
+    pub probability: f64,
+
+    /// How far past the target the pointer goes, as a fraction of the distance
+    /// covered by the movement
+    pub distance: f64,
+}
+
+impl Default for Overshoot {
+    fn default() -> Self {
+        Self {
+            probability: 0.3,
+            distance: 0.08,
+        }
+    }
+}
+
+/// A configurable generator of human-like pointer trajectories.
+///
+/// The duration of each movement follows Fitts's law, so longer movements
+/// take longer, but not proportionally so.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    curve: Curve,
+    speed_profile: SpeedProfile,
+    overshoot: Option<Overshoot>,
+    jitter: f64,
+    speed: f64,
+    seed: Option<u64>,
+}
+
+impl Default for Trajectory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trajectory {
+    pub fn new() -> Self {
+        Self {
+            curve: Curve::default(),
+            speed_profile: SpeedProfile::default(),
+            overshoot: Some(Overshoot::default()),
+            jitter: 0.6,
+            speed: 1.0,
+            seed: None,
+        }
+    }
+
+    /// The shape of the path. Default value: [`Curve::Bezier`] with a curvature of `0.25`
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// How the speed changes along the path. Default value: [`SpeedProfile::MinimumJerk`]
+    pub fn speed_profile(mut self, speed_profile: SpeedProfile) -> Self {
+        self.speed_profile = speed_profile;
+        self
+    }
+
+    /// Whether and how movements overshoot their target.
+    /// Default value: [`Overshoot::default`]
+    pub fn overshoot(mut self, overshoot: Option<Overshoot>) -> Self {
+        self.overshoot = overshoot;
+        self
+    }
+
+    /// The standard deviation, in pixels, of the tremor added to the points
+    /// in the middle of each movement. The start and end points are never
+    /// affected. Default value: `0.6`
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.max(0.0);
+        self
+    }
+
+    /// Multiplies the speed of every movement, `2.0` is twice as fast as
+    /// the default. Default value: `1.0`
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed.max(f64::EPSILON);
+        self
+    }
+
+    /// Makes the generated trajectories deterministic, the same seed and
+    /// arguments always produce the same events
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Moves the pointer from `from` to `to` without pressing any button
+    pub fn move_to(&self, from: Point, to: Point) -> Vec<PointerEvent> {
+        let mut rng = self.rng();
+        let mut events = vec![event(PointerEventKind::Move, from, 0.0)];
+
+        self.movement(&mut rng, &mut events, from, to, 0.0);
+        events
+    }
+
+    /// Moves the pointer from `from` to `to` and clicks it
+    pub fn click(&self, from: Point, to: Point) -> Vec<PointerEvent> {
+        self.click_points(from, [to])
+    }
+
+    /// Moves the pointer from `from` through each of the `targets`, clicking
+    /// them in order, e.g.: the points of a
+    /// [`crate::captcha_types::coordinates_captcha::CoordinatesCaptchaSolution`]
+    /// after they were mapped into page coordinates
+    pub fn click_points(
+        &self,
+        from: Point,
+        targets: impl IntoIterator<Item = Point>,
+    ) -> Vec<PointerEvent> {
+        let mut rng = self.rng();
+        let mut events = vec![event(PointerEventKind::Move, from, 0.0)];
+        let mut position = from;
+        let mut elapsed = 0.0;
+
+        for target in targets {
+            elapsed = self.movement(&mut rng, &mut events, position, target, elapsed);
+
+            elapsed += rng.gen_range(HOVER_MS);
+            events.push(event(PointerEventKind::Down, target, elapsed));
+
+            elapsed += rng.gen_range(CLICK_HOLD_MS);
+            events.push(event(PointerEventKind::Up, target, elapsed));
+
+            position = target;
+        }
+
+        events
+    }
+
+    /// Presses the button at `from`, drags the pointer to `to` and releases it
+    pub fn drag(&self, from: Point, to: Point) -> Vec<PointerEvent> {
+        let mut rng = self.rng();
+        let mut events = vec![
+            event(PointerEventKind::Move, from, 0.0),
+            event(PointerEventKind::Down, from, 0.0),
+        ];
+
+        let elapsed = rng.gen_range(DRAG_PAUSE_MS);
+        let elapsed = self.movement(&mut rng, &mut events, from, to, elapsed);
+        let elapsed = elapsed + rng.gen_range(DRAG_PAUSE_MS);
+
+        events.push(event(PointerEventKind::Up, to, elapsed));
+        events
+    }
+
+    /// Drags the pointer horizontally by `distance` pixels, e.g.: to move a slider
+    pub fn drag_by(&self, from: Point, distance: f64) -> Vec<PointerEvent> {
+        self.drag(from, Point::new(from.x + distance, from.y))
+    }
+
+    /// Appends the events of a movement from `from` to `to` starting at
+    /// `elapsed` milliseconds, returning the time at which it ends
+    fn movement(
+        &self,
+        rng: &mut StdRng,
+        events: &mut Vec<PointerEvent>,
+        from: Point,
+        to: Point,
+        elapsed: f64,
+    ) -> f64 {
+        let distance = from.distance(&to);
+        if distance < 1.0 {
+            return elapsed;
+        }
+
+        let overshoot = self
+            .overshoot
+            .filter(|overshoot| rng.gen_bool(overshoot.probability.clamp(0.0, 1.0)));
+
+        let Some(overshoot) = overshoot else {
+            return self.segment(rng, events, from, to, elapsed);
+        };
+
+        let past = overshoot.distance * distance * rng.gen_range(0.5..1.0);
+        let (dx, dy) = ((to.x - from.x) / distance, (to.y - from.y) / distance);
+        let sideways = past * rng.gen_range(-0.3..0.3);
+        let beyond = Point::new(
+            to.x + dx * past - dy * sideways,
+            to.y + dy * past + dx * sideways,
+        );
+
+        let elapsed = self.segment(rng, events, from, beyond, elapsed);
+        self.segment(rng, events, beyond, to, elapsed)
+    }
+
+    /// Appends the events of a single continuous movement, without overshooting
+    fn segment(
+        &self,
+        rng: &mut StdRng,
+        events: &mut Vec<PointerEvent>,
+        from: Point,
+        to: Point,
+        elapsed: f64,
+    ) -> f64 {
+        let distance = from.distance(&to);
+
+        // Fitts's law, assuming a target about 20px wide
+        let duration = (120.0 + 110.0 * (1.0 + distance / 20.0).log2()) * rng.gen_range(0.85..1.15)
+            / self.speed;
+
+        let path = match self.curve {
+            Curve::Line => Path::Line(from, to),
+            Curve::Bezier { curvature } => {
+                let (dx, dy) = ((to.x - from.x) / distance, (to.y - from.y) / distance);
+                let mut control = |t: f64| {
+                    let offset = distance * curvature * rng.gen_range(-1.0..=1.0);
+                    Point::new(
+                        from.x + (to.x - from.x) * t - dy * offset,
+                        from.y + (to.y - from.y) * t + dx * offset,
+                    )
+                };
+
+                Path::Bezier([from, control(1.0 / 3.0), control(2.0 / 3.0), to])
+            }
+        };
+
+        let steps = (duration / SAMPLE_INTERVAL_MS).ceil().max(2.0) as u32;
+
+        for step in 1..=steps {
+            let t = f64::from(step) / f64::from(steps);
+            let mut point = match step == steps {
+                true => to,
+                false => path.point(self.speed_profile.progress(t)),
+            };
+
+            if step < steps && self.jitter > 0.0 {
+                // Tremor fades in and out so the endpoints are preserved
+                let envelope = (PI * t).sin();
+                point.x += gaussian(rng) * self.jitter * envelope;
+                point.y += gaussian(rng) * self.jitter * envelope;
+            }
+
+            events.push(event(PointerEventKind::Move, point, elapsed + duration * t));
+        }
+
+        elapsed + duration
+    }
+}
+
+fn event(kind: PointerEventKind, position: Point, elapsed: f64) -> PointerEvent {
+    PointerEvent {
+        kind,
+        position,
+        elapsed_ms: elapsed.round() as u64,
+    }
+}
+
+/// A sample of the standard normal distribution, using the Box-Muller transform
+fn gaussian(rng: &mut StdRng) -> f64 {
+    let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let v: f64 = rng.gen();
+
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_webdriver_actions, Overshoot, PointerEventKind, Trajectory};
+    use crate::geometry::Point;
+
+    #[test]
+    fn seeded_trajectories_are_deterministic() {
+        let trajectory = Trajectory::new().seed(Some(7));
+        let (from, to) = (Point::new(10.0, 10.0), Point::new(400.0, 250.0));
+
+        assert_eq!(trajectory.click(from, to), trajectory.click(from, to));
+        assert_ne!(
+            trajectory.click(from, to),
+            trajectory.clone().seed(Some(8)).click(from, to)
+        );
+    }
+
+    #[test]
+    fn clicks_end_on_target() {
+        let trajectory = Trajectory::new().seed(Some(1)).overshoot(Some(Overshoot {
+            probability: 1.0,
+            distance: 0.2,
+        }));
+
+        let targets = [Point::new(50.0, 60.0), Point::new(300.0, 20.0)];
+        let events = trajectory.click_points(Point::ORIGIN, targets);
+
+        let presses = events
+            .iter()
+            .filter(|x| x.kind == PointerEventKind::Down)
+            .map(|x| x.position)
+            .collect::<Vec<_>>();
+
+        assert_eq!(presses, targets);
+        assert!(events
+            .windows(2)
+            .all(|x| x[0].elapsed_ms <= x[1].elapsed_ms));
+    }
+
+    #[test]
+    fn drags_by_distance() {
+        let events = Trajectory::new()
+            .seed(Some(3))
+            .drag_by(Point::new(20.0, 100.0), 135.0);
+
+        assert_eq!(events[1].kind, PointerEventKind::Down);
+
+        let release = events.last().unwrap();
+        assert_eq!(release.kind, PointerEventKind::Up);
+        assert_eq!(release.position, Point::new(155.0, 100.0));
+
+        let actions = to_webdriver_actions(&events);
+        assert_eq!(actions["actions"][1]["type"], "pointerDown");
+    }
+}
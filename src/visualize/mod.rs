@@ -0,0 +1,476 @@
+//! Debug rendering of solutions onto the images they were given for
+//!
+//! When an answer looks wrong, drawing it onto the task's `body` makes it
+//! quick to decide whether it should be reported with
+//! [`crate::solver::SolutionStatus::Bad`].
+//!
+//! # Example
+//! ```no_run
+//! use captcha_oxide::{
+//!     CaptchaTask,
+//!     CaptchaSolver,
+//!     captcha_types::grid_captcha::{GridCaptcha, GridLayout},
+//!     visualize::Visualizer,
+//! };
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let solver = CaptchaSolver::new("YOUR_API_KEY");
+//! let body = "/9j/4AAQSkZJ...OGSRF//Z";
+//!
+//! let captcha = GridCaptcha::builder()
+//!     .body(body)
+//!     .comment("Select all vehicles")
+//!     .rows(Some(4))
+//!     .columns(Some(4))
+//!     .build();
+//!
+//! let solution = solver.solve(captcha).await?.expect("No callback url was set");
+//!
+//! Visualizer::from_body(body)?
+//!     .grid(&solution.solution, GridLayout::FOUR_BY_FOUR)
+//!     .save("answer.png")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{f64::consts::PI, io::Cursor, path::Path};
+
+use image::{DynamicImage, ImageFormat, ImageOutputFormat, Rgba, RgbaImage};
+use imageproc::drawing::{
+    draw_filled_circle_mut, draw_filled_rect_mut, draw_hollow_circle_mut, draw_line_segment_mut,
+    draw_polygon_mut, Blend,
+};
+
+use crate::{
+    captcha_types::{
+        bounding_box_captcha::BoundingBoxCaptchaSolution,
+        coordinates_captcha::CoordinatesCaptchaSolution,
+        draw_around_captcha::DrawAroundCaptchaSolution,
+        grid_captcha::{GridCaptchaSolution, GridLayout},
+        rotate_captcha::RotateCaptchaSolution,
+    },
+    geometry::{Point, Rect},
+    preprocess::{decode_body, PreprocessError},
+    CaptchaTask,
+};
+
+/// The color used for the answers unless another one is set
+const DEFAULT_COLOR: Rgba<u8> = Rgba([230, 25, 75, 255]);
+
+/// The color used to outline markers and reference lines
+const OUTLINE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// The opacity of filled areas, such as the clicked tiles of a grid
+const FILL_ALPHA: u8 = 80;
+
+/// Implemented by the image tasks whose solutions can be drawn onto their `body`
+pub trait Visualize: CaptchaTask {
+    /// Decodes the task's image and draws the `solution` onto it
+    ///
+    /// # Errors
+    /// This method will error if the task's `body` is not a valid image
+    fn visualize(&self, solution: &Self::Solution) -> Result<Visualizer, PreprocessError>;
+}
+
+/// Draws solutions onto an image, which can then be encoded into a PNG.
+/// Each drawing method can be called any number of times, e.g.: to compare
+/// the answers of different workers
+pub struct Visualizer {
+    canvas: Blend<RgbaImage>,
+    color: Rgba<u8>,
+    thickness: u32,
+}
+
+impl Clone for Visualizer {
+    fn clone(&self) -> Self {
+        Self {
+            canvas: Blend(self.canvas.0.clone()),
+            color: self.color,
+            thickness: self.thickness,
+        }
+    }
+}
+
+impl std::fmt::Debug for Visualizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Visualizer")
+            .field("width", &self.canvas.0.width())
+            .field("height", &self.canvas.0.height())
+            .field("color", &self.color)
+            .field("thickness", &self.thickness)
+            .finish()
+    }
+}
+
+impl Visualizer {
+    pub fn new(image: &DynamicImage) -> Self {
+        Self {
+            canvas: Blend(image.to_rgba8()),
+            color: DEFAULT_COLOR,
+            thickness: 2,
+        }
+    }
+
+    /// Decodes a Base64 image, such as the `body` of an image task.
+    /// The Data-URI format is also supported
+    ///
+    /// # Errors
+    /// This method will error if the Base64 string or the image are invalid
+    pub fn from_body(body: &str) -> Result<Self, PreprocessError> {
+        Ok(Self::new(&decode_body(body)?))
+    }
+
+    /// The color of the shapes drawn after this call
+    pub fn color(mut self, color: Rgba<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// The width, in pixels, of the lines drawn after this call. Default value: `2`
+    pub fn thickness(mut self, thickness: u32) -> Self {
+        self.thickness = thickness.max(1);
+        self
+    }
+
+    /// Draws the grid's lines and highlights the clicked tiles
+    pub fn grid(mut self, solution: &GridCaptchaSolution, layout: GridLayout) -> Self {
+        let (width, height) = self.size();
+        let guide = with_alpha(OUTLINE_COLOR, 160);
+
+        for column in 1..layout.columns {
+            let x = width * f64::from(column) / f64::from(layout.columns);
+            self.line(Point::new(x, 0.0), Point::new(x, height), guide, 1);
+        }
+
+        for row in 1..layout.rows {
+            let y = height * f64::from(row) / f64::from(layout.rows);
+            self.line(Point::new(0.0, y), Point::new(width, y), guide, 1);
+        }
+
+        for tile in solution.tiles(layout, width, height).iter() {
+            self.fill(tile, with_alpha(self.color, FILL_ALPHA));
+            self.outline(tile);
+        }
+
+        self
+    }
+
+    /// Draws a marker on each of the clicked points
+    pub fn coordinates(mut self, solution: &CoordinatesCaptchaSolution) -> Self {
+        let radius = self.marker_radius();
+
+        for point in solution.coordinates.iter() {
+            let center = to_pixel(*point);
+            draw_filled_circle_mut(&mut self.canvas, center, radius, self.color);
+            draw_hollow_circle_mut(&mut self.canvas, center, radius, OUTLINE_COLOR);
+        }
+
+        self
+    }
+
+    /// Outlines each of the boxes
+    pub fn bounding_boxes(mut self, solution: &BoundingBoxCaptchaSolution) -> Self {
+        for rect in solution
+            .bounding_boxes
+            .iter()
+            .flat_map(|group| group.iter())
+        {
+            self.outline(rect);
+        }
+
+        self
+    }
+
+    /// Fills and outlines each of the polygons
+    pub fn canvas(mut self, solution: &DrawAroundCaptchaSolution) -> Self {
+        for polygon in solution.canvas.iter() {
+            let mut points = polygon
+                .points
+                .iter()
+                .map(|point| {
+                    imageproc::point::Point::new(point.x.round() as i32, point.y.round() as i32)
+                })
+                .collect::<Vec<_>>();
+
+            // `draw_polygon_mut` panics if the polygon is explicitly closed
+            points.dedup();
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+
+            if points.len() > 2 {
+                draw_polygon_mut(
+                    &mut self.canvas,
+                    &points,
+                    with_alpha(self.color, FILL_ALPHA),
+                );
+            }
+
+            for (start, end) in polygon
+                .points
+                .iter()
+                .zip(polygon.points.iter().cycle().skip(1))
+            {
+                self.line(*start, *end, self.color, self.thickness);
+            }
+        }
+
+        self
+    }
+
+    /// Draws the angle the image must be rotated by, clockwise from
+    /// a reference line pointing up from the center of the image
+    pub fn rotation(mut self, solution: &RotateCaptchaSolution) -> Self {
+        let (width, height) = self.size();
+        let center = Point::new(width / 2.0, height / 2.0);
+        let radius = width.min(height) * 0.4;
+        let angle = f64::from(solution.rotate % 360);
+
+        // Angles are measured clockwise from the top, as the y axis points down
+        let at = |degrees: f64, radius: f64| {
+            let radians = degrees.to_radians();
+            Point::new(
+                center.x + radius * radians.sin(),
+                center.y - radius * radians.cos(),
+            )
+        };
+
+        self.line(center, at(0.0, radius), OUTLINE_COLOR, self.thickness);
+
+        let steps = (angle / 5.0).ceil().max(1.0) as u32;
+        let arc = (0..=steps)
+            .map(|step| at(angle * f64::from(step) / f64::from(steps), radius * 0.5))
+            .collect::<Vec<_>>();
+
+        for segment in arc.windows(2) {
+            self.line(segment[0], segment[1], self.color, self.thickness);
+        }
+
+        let tip = at(angle, radius);
+        self.line(center, tip, self.color, self.thickness);
+
+        let head = radius * 0.12;
+        for side in [-1.0, 1.0] {
+            let radians = angle.to_radians() + PI + side * PI / 6.0;
+            let end = Point::new(tip.x + head * radians.sin(), tip.y - head * radians.cos());
+            self.line(tip, end, self.color, self.thickness);
+        }
+
+        self
+    }
+
+    /// The image with everything drawn so far
+    pub fn image(&self) -> &RgbaImage {
+        &self.canvas.0
+    }
+
+    pub fn into_image(self) -> DynamicImage {
+        DynamicImage::ImageRgba8(self.canvas.0)
+    }
+
+    /// Encodes the image as a PNG file
+    ///
+    /// # Errors
+    /// This method will error if the image cannot be encoded
+    pub fn to_png(&self) -> Result<Vec<u8>, PreprocessError> {
+        let mut bytes = Cursor::new(Vec::new());
+        self.canvas.0.write_to(&mut bytes, ImageOutputFormat::Png)?;
+
+        Ok(bytes.into_inner())
+    }
+
+    /// Saves the image as a PNG file, regardless of the extension of `path`
+    ///
+    /// # Errors
+    /// This method will error if the image cannot be encoded or written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PreprocessError> {
+        Ok(self.canvas.0.save_with_format(path, ImageFormat::Png)?)
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (
+            f64::from(self.canvas.0.width()),
+            f64::from(self.canvas.0.height()),
+        )
+    }
+
+    fn marker_radius(&self) -> i32 {
+        (self.thickness * 3).try_into().unwrap_or(i32::MAX)
+    }
+
+    fn fill(&mut self, rect: &Rect, color: Rgba<u8>) {
+        let (x, y) = to_pixel(rect.min());
+        let width = rect.width().round().max(1.0) as u32;
+        let height = rect.height().round().max(1.0) as u32;
+
+        draw_filled_rect_mut(
+            &mut self.canvas,
+            imageproc::rect::Rect::at(x, y).of_size(width, height),
+            color,
+        );
+    }
+
+    fn outline(&mut self, rect: &Rect) {
+        let corners = rect.to_polygon().points;
+
+        for (start, end) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+            self.line(*start, *end, self.color, self.thickness);
+        }
+    }
+
+    /// Draws a line with round caps, so consecutive lines join smoothly
+    fn line(&mut self, start: Point, end: Point, color: Rgba<u8>, thickness: u32) {
+        let (from, to) = (
+            (start.x as f32, start.y as f32),
+            (end.x as f32, end.y as f32),
+        );
+
+        if thickness <= 1 {
+            draw_line_segment_mut(&mut self.canvas, from, to, color);
+            return;
+        }
+
+        let length = start.distance(&end);
+        let half = f64::from(thickness) / 2.0;
+
+        if length >= 1.0 {
+            let (nx, ny) = (
+                -(end.y - start.y) / length * half,
+                (end.x - start.x) / length * half,
+            );
+
+            let quad = [
+                Point::new(start.x + nx, start.y + ny),
+                Point::new(end.x + nx, end.y + ny),
+                Point::new(end.x - nx, end.y - ny),
+                Point::new(start.x - nx, start.y - ny),
+            ]
+            .map(|point| {
+                let (x, y) = to_pixel(point);
+                imageproc::point::Point::new(x, y)
+            });
+
+            if quad.first() != quad.last() {
+                draw_polygon_mut(&mut self.canvas, &quad, color);
+            }
+        }
+
+        let radius = (half.round() as i32).max(1) - 1;
+        for point in [start, end] {
+            draw_filled_circle_mut(&mut self.canvas, to_pixel(point), radius, color);
+        }
+    }
+}
+
+fn to_pixel(point: Point) -> (i32, i32) {
+    (point.x.round() as i32, point.y.round() as i32)
+}
+
+fn with_alpha(color: Rgba<u8>, alpha: u8) -> Rgba<u8> {
+    let Rgba([r, g, b, _]) = color;
+    Rgba([r, g, b, alpha])
+}
+
+#[cfg(test)]
+mod test {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use image::{DynamicImage, Rgba, RgbaImage};
+
+    use super::{Visualize, Visualizer, DEFAULT_COLOR};
+    use crate::{
+        captcha_types::{
+            bounding_box_captcha::BoundingBoxCaptchaSolution,
+            coordinates_captcha::CoordinatesCaptchaSolution,
+            draw_around_captcha::DrawAroundCaptchaSolution,
+            grid_captcha::{GridCaptcha, GridCaptchaSolution},
+            rotate_captcha::RotateCaptchaSolution,
+        },
+        geometry::{Point, Polygon, Rect},
+        CaptchaTask,
+    };
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn blank(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, WHITE))
+    }
+
+    #[test]
+    fn highlights_clicked_tiles() {
+        let png = Visualizer::new(&blank(300, 300)).to_png().unwrap();
+
+        let captcha = GridCaptcha::builder()
+            .body(STANDARD.encode(png))
+            .comment("Select all vehicles")
+            .rows(Some(3))
+            .columns(Some(3))
+            .build();
+
+        let solution = GridCaptchaSolution {
+            click: Box::new([1, 5]),
+        };
+
+        let image = captcha
+            .visualize(&solution)
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+
+        assert_ne!(*image.get_pixel(50, 50), WHITE);
+        assert_ne!(*image.get_pixel(150, 150), WHITE);
+        assert_eq!(*image.get_pixel(250, 50), WHITE);
+    }
+
+    #[test]
+    fn outlines_bounding_boxes() {
+        let solution = BoundingBoxCaptchaSolution {
+            bounding_boxes: Box::new([Box::new([Rect::from_corners(
+                Point::new(20.0, 30.0),
+                Point::new(80.0, 90.0),
+            )])]),
+        };
+
+        let visualizer = Visualizer::new(&blank(100, 100)).bounding_boxes(&solution);
+        let image = visualizer.image();
+
+        assert_eq!(*image.get_pixel(20, 60), DEFAULT_COLOR);
+        assert_eq!(*image.get_pixel(50, 90), DEFAULT_COLOR);
+        assert_eq!(*image.get_pixel(50, 60), WHITE);
+
+        let decoded = image::load_from_memory(&visualizer.to_png().unwrap()).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (100, 100));
+    }
+
+    #[test]
+    fn draws_points_polygons_and_angles() {
+        let coordinates = CoordinatesCaptchaSolution {
+            coordinates: Box::new([Point::new(10.0, 10.0), Point::new(-5.0, 200.0)]),
+        };
+
+        let canvas = DrawAroundCaptchaSolution {
+            canvas: Box::new([
+                Polygon::new([
+                    Point::new(30.0, 30.0),
+                    Point::new(70.0, 35.0),
+                    Point::new(50.0, 80.0),
+                    Point::new(30.0, 30.0),
+                ]),
+                Polygon::new([Point::new(5.0, 5.0), Point::new(5.0, 5.0)]),
+            ]),
+        };
+
+        let image = Visualizer::new(&blank(100, 100))
+            .thickness(3)
+            .coordinates(&coordinates)
+            .canvas(&canvas)
+            .rotation(&RotateCaptchaSolution { rotate: 450 })
+            .into_image()
+            .to_rgba8();
+
+        assert_eq!(*image.get_pixel(10, 10), DEFAULT_COLOR);
+        assert_ne!(*image.get_pixel(42, 42), WHITE);
+        // 450 degrees is a quarter turn, the arrow points right
+        assert_eq!(*image.get_pixel(85, 50), DEFAULT_COLOR);
+    }
+}